@@ -293,6 +293,9 @@ fn launch_discord_with_cdp(options: LaunchOptions) -> Result<LaunchResult, Strin
         return Ok(launch_result(&install, options.port));
     }
 
+    // Resolve the install first: once Discord is closed its path can't be read anymore
+    let install = select_preferred_install(options.channel)?;
+
     if options.restart_existing {
         terminate_discord_processes(options.channel)?;
         wait_until_discord_exits(options.channel, Duration::from_secs(8))?;
@@ -305,7 +308,6 @@ fn launch_discord_with_cdp(options: LaunchOptions) -> Result<LaunchResult, Strin
 
     ensure_port_available_for_cdp(options.port)?;
 
-    let install = select_preferred_install(options.channel)?;
     let mut command = Command::new(&install.executable_path);
     command
         .current_dir(&install.working_dir)
@@ -412,7 +414,13 @@ fn launch_result(install: &DiscordInstall, port: u16) -> LaunchResult {
     }
 }
 
+/// Prefers the binary of a running Discord, so pinned versions and custom install
+/// locations are relaunched as-is; the directory scan is only a fallback.
 fn select_preferred_install(channel: Option<DiscordChannel>) -> Result<DiscordInstall, String> {
+    if let Some(install) = find_running_install(channel) {
+        return Ok(install);
+    }
+
     let installs = find_discord_installs();
     if let Some(channel) = channel {
         installs
@@ -436,6 +444,55 @@ fn find_discord_installs() -> Vec<DiscordInstall> {
     find_discord_installs_platform()
 }
 
+/// Install backing a running Discord process, read from the process table
+fn find_running_install(channel: Option<DiscordChannel>) -> Option<DiscordInstall> {
+    let channels = match channel {
+        Some(channel) => vec![channel],
+        None => vec![
+            DiscordChannel::Stable,
+            DiscordChannel::Ptb,
+            DiscordChannel::Canary,
+        ],
+    };
+
+    // One process-table query covers every channel
+    let process_names: Vec<String> = channels
+        .iter()
+        .copied()
+        .map(platform_process_name)
+        .collect();
+    let running = running_executable_paths(&process_names);
+
+    channels
+        .into_iter()
+        .zip(process_names)
+        .find_map(|(channel, process_name)| {
+            let executable_path = running
+                .iter()
+                .find(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(&process_name))
+                })?
+                .clone();
+            let working_dir = executable_path.parent()?.to_path_buf();
+            Some(DiscordInstall {
+                channel,
+                executable_path,
+                working_dir,
+            })
+        })
+}
+
+/// Paths listed one per line by a process-table query that name files that exist
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+fn existing_paths(stdout: &str) -> Vec<PathBuf> {
+    stdout
+        .lines()
+        .map(|line| PathBuf::from(line.trim()))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
 fn is_discord_running(channel: Option<DiscordChannel>) -> Result<bool, String> {
     is_discord_running_platform(channel)
 }
@@ -586,6 +643,26 @@ fn is_discord_running_platform(channel: Option<DiscordChannel>) -> Result<bool,
     }))
 }
 
+/// Executables of the running processes named `process_names`, in one PowerShell call
+#[cfg(target_os = "windows")]
+fn running_executable_paths(process_names: &[String]) -> Vec<PathBuf> {
+    let names: Vec<String> = process_names
+        .iter()
+        .map(|name| format!("'{}'", name.trim_end_matches(".exe")))
+        .collect();
+    let script = format!(
+        "(Get-Process -Name {} -ErrorAction SilentlyContinue).Path",
+        names.join(",")
+    );
+    match no_window_cmd("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+    {
+        Ok(output) => existing_paths(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn terminate_discord_processes_platform(channel: Option<DiscordChannel>) -> Result<(), String> {
     for name in process_names_for(channel) {
@@ -664,6 +741,16 @@ fn is_discord_running_platform(channel: Option<DiscordChannel>) -> Result<bool,
     Ok(false)
 }
 
+/// Executables of all running processes (`comm` is the full path on macOS); the caller
+/// matches them against the process names
+#[cfg(target_os = "macos")]
+fn running_executable_paths(_process_names: &[String]) -> Vec<PathBuf> {
+    match Command::new("ps").args(["-axo", "comm="]).output() {
+        Ok(output) => existing_paths(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn terminate_discord_processes_platform(channel: Option<DiscordChannel>) -> Result<(), String> {
     for name in process_names_for(channel) {
@@ -693,6 +780,11 @@ fn find_discord_installs_platform() -> Vec<DiscordInstall> {
     Vec::new()
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn running_executable_paths(_process_names: &[String]) -> Vec<PathBuf> {
+    Vec::new()
+}
+
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn is_discord_running_platform(_channel: Option<DiscordChannel>) -> Result<bool, String> {
     Err("Discord CDP launcher is only supported on Windows and macOS.".to_string())
@@ -733,9 +825,8 @@ mod win32 {
 #[cfg(target_os = "windows")]
 fn enable_dpi_awareness() {
     unsafe {
-        let _ = win32::SetProcessDpiAwarenessContext(
-            win32::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
-        );
+        let _ =
+            win32::SetProcessDpiAwarenessContext(win32::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
     }
 }
 
@@ -2,8 +2,12 @@
 
 use softbuffer::Surface;
 use std::env;
+use std::io::{self, BufRead, Write};
 use std::num::NonZeroU32;
+use std::process;
 use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
@@ -15,7 +19,21 @@ const CHAR_HEIGHT: usize = 8;
 // Commit hash embedded at compile time by build.rs
 const COMMIT_HASH: &str = env!("RUNNER_COMMIT_HASH");
 
-// Basic 5x7 font data
+// Env var that overrides the window text (set by the app to the game's name)
+const TEXT_ENV: &str = "DQH_RUNNER_TEXT";
+
+// Largest text scale; long titles are scaled down to fit the window
+const MAX_TEXT_SCALE: usize = 3;
+
+// Exit on our own after this many seconds, so a runner never outlives its quest
+// for long even if the app that started it crashed
+const TTL_ARG: &str = "--ttl-seconds";
+
+// Set by the app when it holds our stdin/stdout: we print "ready" once the window
+// exists and exit on a "quit" line or when stdin closes (the app went away)
+const IPC_ENV: &str = "DQH_RUNNER_IPC";
+
+// 5x7 font data covering printable ASCII; other characters render as '?'
 fn get_char_bitmap(c: char) -> [u8; 7] {
     match c {
         // Letters (uppercase)
@@ -84,6 +102,31 @@ fn get_char_bitmap(c: char) -> [u8; 7] {
         '8' => [0b01110, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110, 0b00000],
         '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110, 0b00000],
         // Punctuation
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100, 0b00000],
+        '"' => [0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '#' => [0b01010, 0b11111, 0b01010, 0b01010, 0b11111, 0b01010, 0b00000],
+        '$' => [0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100, 0b00000],
+        '%' => [0b11001, 0b11010, 0b00100, 0b01000, 0b10011, 0b10011, 0b00000],
+        '&' => [0b01100, 0b10010, 0b01100, 0b10101, 0b10010, 0b01101, 0b00000],
+        '\'' => [0b00100, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '*' => [0b00000, 0b10101, 0b01110, 0b11111, 0b01110, 0b10101, 0b00000],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        ';' => [0b00000, 0b00100, 0b00000, 0b00000, 0b00100, 0b01000, 0b00000],
+        '<' => [0b00010, 0b00100, 0b01000, 0b01000, 0b00100, 0b00010, 0b00000],
+        '=' => [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000],
+        '>' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00100, 0b01000, 0b00000],
+        '?' => [0b01110, 0b10001, 0b00010, 0b00100, 0b00000, 0b00100, 0b00000],
+        '@' => [0b01110, 0b10001, 0b10111, 0b10110, 0b10000, 0b01110, 0b00000],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110, 0b00000],
+        '\\' => [0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000, 0b00000],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110, 0b00000],
+        '^' => [0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000],
+        '_' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b00000],
+        '`' => [0b01000, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '{' => [0b00110, 0b00100, 0b01000, 0b00100, 0b00100, 0b00110, 0b00000],
+        '|' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000],
+        '}' => [0b01100, 0b00100, 0b00010, 0b00100, 0b00100, 0b01100, 0b00000],
+        '~' => [0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000, 0b00000],
         ':' => [0b00000, 0b00100, 0b00000, 0b00000, 0b00100, 0b00000, 0b00000],
         ')' => [0b01000, 0b00100, 0b00100, 0b00100, 0b00100, 0b01000, 0b00000],
         '(' => [0b00010, 0b00100, 0b00100, 0b00100, 0b00100, 0b00010, 0b00000],
@@ -92,7 +135,7 @@ fn get_char_bitmap(c: char) -> [u8; 7] {
         '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
         '/' => [0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000, 0b00000],
         ' ' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
-        _ =>   [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        _ => get_char_bitmap('?'),
     }
 }
 
@@ -136,19 +179,77 @@ fn draw_text_block(buffer: &mut [u32], width: usize, height: usize, lines: &[(&s
     let mut y = (height.saturating_sub(total_height)) / 2;
     for &(text, color, scale) in lines {
         let char_width = CHAR_WIDTH * scale;
-        let text_width = text.len() * char_width;
+        let text_width = text.chars().count() * char_width;
         let x = (width.saturating_sub(text_width)) / 2;
         draw_text(buffer, width, x, y, text, color, scale);
         y += CHAR_HEIGHT * scale + line_spacing;
     }
 }
 
+/// Largest scale (up to MAX_TEXT_SCALE) at which `text` fits in `width` pixels
+fn fit_scale(text: &str, width: usize) -> usize {
+    let chars = text.chars().count().max(1);
+    (width / (chars * CHAR_WIDTH)).clamp(1, MAX_TEXT_SCALE)
+}
+
+/// Window text argument (first positional) and `--ttl-seconds` value
+fn parse_args(args: impl Iterator<Item = String>) -> (Option<String>, Option<u64>) {
+    let mut text = None;
+    let mut ttl_secs = None;
+    let mut args = args.skip(1);
+    while let Some(arg) = args.next() {
+        if arg == TTL_ARG {
+            ttl_secs = args.next().and_then(|value| value.parse().ok());
+        } else if let Some(value) = arg.strip_prefix("--ttl-seconds=") {
+            ttl_secs = value.parse().ok();
+        } else if text.is_none() {
+            text = Some(arg);
+        }
+    }
+    (text, ttl_secs)
+}
+
+/// Listen for "quit" from the app on stdin; no-op unless the app asked for the channel
+fn start_ipc() -> bool {
+    if env::var_os(IPC_ENV).is_none() {
+        return false;
+    }
+    thread::spawn(|| {
+        for line in io::stdin().lock().lines() {
+            match line {
+                Ok(line) if line.trim() == "quit" => break,
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        process::exit(0);
+    });
+    true
+}
+
 fn main() {
+    let (arg_text, ttl_secs) = parse_args(env::args());
+    let ipc = start_ipc();
+    if let Some(secs) = ttl_secs {
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(secs));
+            process::exit(0);
+        });
+    }
+
     let exe_name = env::current_exe()
         .ok()
         .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
         .unwrap_or_else(|| "Runner".to_string());
 
+    // Window text: env var, then first CLI argument, then the exe name
+    let text = env::var(TEXT_ENV)
+        .ok()
+        .or(arg_text)
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| exe_name.clone());
+
     // Build the version line: "Version: abc1234"
     let version_line = format!("Version: {}", COMMIT_HASH);
 
@@ -166,6 +267,11 @@ fn main() {
 
     window.set_minimized(true);
 
+    if ipc {
+        let mut stdout = io::stdout();
+        let _ = writeln!(stdout, "ready").and_then(|_| stdout.flush());
+    }
+
     event_loop
         .run(move |event, elwt| {
             elwt.set_control_flow(ControlFlow::Wait);
@@ -196,10 +302,10 @@ fn main() {
 
                         buffer.fill(0);
 
-                        // Line 1: "Peace and Love :)" in white, scale 3
+                        // Line 1: window text in white, up to scale 3
                         // Line 2: "Version: {hash}" in gray, scale 2
                         draw_text_block(&mut buffer, width, height, &[
-                            ("Peace and Love :)", 0x00FFFFFF, 3),
+                            (&text, 0x00FFFFFF, fit_scale(&text, width)),
                             (&version_line, 0x00888888, 2),
                         ]);
 
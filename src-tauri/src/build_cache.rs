@@ -0,0 +1,157 @@
+//! Build Number Cache
+//!
+//! Keeps the last fetched client build number in the app data dir, so startup can use
+//! it right away instead of downloading Discord's login page and JS bundles on every
+//! launch. A cached value older than `MAX_AGE_HOURS` is refreshed in the background.
+
+use crate::json_store;
+use crate::SUPER_PROPERTIES_MANAGER;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// File name inside the app data dir
+const CACHE_FILE: &str = "build_number.json";
+
+/// Age after which the cached build number is refreshed
+const MAX_AGE_HOURS: i64 = 24;
+
+/// Last successfully fetched build number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedBuildNumber {
+    pub build_number: u64,
+    /// RFC 3339 timestamp of the fetch
+    pub fetched_at: String,
+}
+
+impl CachedBuildNumber {
+    /// Whether the entry is younger than `MAX_AGE_HOURS` at `now`
+    ///
+    /// Unparseable timestamps and ones in the future (clock changes) count as stale.
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.fetched_at).is_ok_and(|fetched| {
+            let age = now.signed_duration_since(fetched);
+            age >= chrono::Duration::zero() && age < chrono::Duration::hours(MAX_AGE_HOURS)
+        })
+    }
+}
+
+fn cache_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    json_store::path(app_handle, CACHE_FILE)
+}
+
+/// Cached build number, if one was saved
+pub fn load(app_handle: &tauri::AppHandle) -> Option<CachedBuildNumber> {
+    cache_path(app_handle)
+        .ok()
+        .and_then(|path| json_store::load(&path))
+}
+
+/// Whether a cached build number younger than `MAX_AGE_HOURS` exists
+pub fn is_fresh(app_handle: &tauri::AppHandle) -> bool {
+    load(app_handle).is_some_and(|cached| cached.is_fresh(Utc::now()))
+}
+
+/// Remember a successfully fetched build number (failures are only logged)
+pub fn save(app_handle: &tauri::AppHandle, build_number: u64) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let entry = CachedBuildNumber {
+        build_number,
+        fetched_at: Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = cache_path(app_handle).and_then(|path| json_store::save_atomic(&path, &entry)) {
+        log(
+            LogLevel::Warn,
+            LogCategory::TokenExtraction,
+            "Failed to save build number cache",
+            Some(&format!("{:#}", e)),
+        );
+    }
+}
+
+/// Load the cached build number into the manager at startup
+///
+/// When there is no cache or it's older than `MAX_AGE_HOURS`, a fresh build number is
+/// fetched in the background.
+pub fn restore(app_handle: &tauri::AppHandle) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let cached = load(app_handle);
+    if let Some(cached) = &cached {
+        SUPER_PROPERTIES_MANAGER
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .set_from_cache(cached.build_number);
+        log(
+            LogLevel::Info,
+            LogCategory::TokenExtraction,
+            &format!(
+                "Loaded cached build number {} (fetched {})",
+                cached.build_number, cached.fetched_at
+            ),
+            None,
+        );
+    }
+
+    if cached.is_some_and(|cached| cached.is_fresh(Utc::now()))
+        || crate::token_extractor::is_offline()
+    {
+        return;
+    }
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match crate::token_extractor::fetch_build_number_from_discord().await {
+            Ok(build_number) => {
+                {
+                    let mut manager = SUPER_PROPERTIES_MANAGER
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    // Never replace what a login already got from the client
+                    if manager.get_mode() != crate::super_properties::SourceMode::Cdp {
+                        manager.set_from_remote_js(build_number);
+                    }
+                }
+                save(&handle, build_number);
+                log(
+                    LogLevel::Info,
+                    LogCategory::TokenExtraction,
+                    &format!("Refreshed cached build number: {}", build_number),
+                    None,
+                );
+            }
+            Err(e) => log(
+                LogLevel::Warn,
+                LogCategory::TokenExtraction,
+                "Background build number refresh failed",
+                Some(&format!("{:#}", e)),
+            ),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_build_number_freshness() {
+        let now = Utc::now();
+        let entry = |hours_ago: i64| CachedBuildNumber {
+            build_number: 569817,
+            fetched_at: (now - chrono::Duration::hours(hours_ago)).to_rfc3339(),
+        };
+
+        assert!(entry(0).is_fresh(now));
+        assert!(entry(MAX_AGE_HOURS - 1).is_fresh(now));
+        assert!(!entry(MAX_AGE_HOURS).is_fresh(now));
+        assert!(!entry(-1).is_fresh(now));
+        assert!(!CachedBuildNumber {
+            build_number: 569817,
+            fetched_at: "yesterday".to_string(),
+        }
+        .is_fresh(now));
+    }
+}
@@ -5,13 +5,43 @@
 
 use anyhow::{Context, Result};
 use futures_util::{future::join_all, SinkExt, StreamExt};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU16, Ordering};
 use std::time::Duration;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 /// Default CDP debugging port
 pub const DEFAULT_CDP_PORT: u16 = 9223;
 
+/// Startup override of `DEFAULT_CDP_PORT` (`DQH_CDP_PORT`); 0 means none
+static DEFAULT_PORT_OVERRIDE: AtomicU16 = AtomicU16::new(0);
+
+/// Use `port` wherever a command doesn't pass one explicitly
+pub fn set_default_port(port: u16) {
+    DEFAULT_PORT_OVERRIDE.store(port, Ordering::Relaxed);
+}
+
+/// Port used when a command doesn't pass one
+pub fn default_port() -> u16 {
+    match DEFAULT_PORT_OVERRIDE.load(Ordering::Relaxed) {
+        0 => DEFAULT_CDP_PORT,
+        port => port,
+    }
+}
+
+/// Default `Runtime.evaluate` timeout for reading SuperProperties
+pub const SUPER_PROPERTIES_TIMEOUT_SECS: u64 = 10;
+
+/// Keepalive ping interval while waiting on a long evaluation
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A ping unanswered for this long means the connection is dead, not just busy
+const PONG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Ports scanned by `find_cdp_port` (Chromium's usual 9222 plus a few neighbours)
+pub const CDP_PORT_SCAN_RANGE: std::ops::RangeInclusive<u16> = 9222..=9230;
+
 /// CDP target info (returned from /json endpoint)
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -31,6 +61,12 @@ pub struct CdpTarget {
 pub struct CdpSuperProperties {
     pub base64: String,
     pub decoded: serde_json::Value,
+    /// How the properties were found (`named` or `scan`), see `JS_GET_SUPER_PROPERTIES`
+    #[serde(default)]
+    pub strategy: Option<String>,
+    /// Webpack chunk global the module was found through
+    #[serde(default)]
+    pub chunk: Option<String>,
 }
 
 /// CDP status
@@ -75,75 +111,202 @@ pub struct CapturedRequest {
     pub headers: std::collections::HashMap<String, String>,
 }
 
-/// JavaScript code: Get SuperProperties
+/// JavaScript prelude shared by the webpack scripts, defining `webpackRequires()`
 ///
 /// FRAGILE: This code relies on Discord's internal webpack module structure.
-/// The webpackChunkdiscord_app.push trick is used to access Discord's module system.
+/// The webpackChunk*.push trick is used to access Discord's module system.
 ///
-/// This approach may break if Discord:
-/// - Changes their webpack chunking mechanism
-/// - Renames the global variable
-/// - Modifies the module structure
-/// - Updates their bundler
+/// `webpackRequires()` returns `{ found: [{ chunk, wpRequire }], errors }`. Each
+/// `wpRequire` has a module cache (`c`); `webpackChunkdiscord_app` comes first, followed by
+/// any other `webpackChunk*` global in case it was renamed.
+const JS_WEBPACK_REQUIRES: &str = r#"
+        const webpackRequires = () => {
+            const chunkNames = ["webpackChunkdiscord_app"].concat(
+                Object.keys(window).filter(k => k.startsWith("webpackChunk") && k !== "webpackChunkdiscord_app")
+            ).filter(k => Array.isArray(window[k]));
+            if (chunkNames.length === 0) {
+                return { found: [], errors: ["No webpackChunk* global found; the Discord client structure may have changed."] };
+            }
+
+            const found = [];
+            const errors = [];
+            for (const chunk of chunkNames) {
+                try {
+                    const wpRequire = window[chunk].push([[Symbol()], {}, r => r]);
+                    window[chunk].pop();
+                    if (wpRequire?.c) {
+                        found.push({ chunk, wpRequire });
+                    } else {
+                        errors.push(chunk + ": no module cache");
+                    }
+                } catch (e) {
+                    errors.push(chunk + ": " + ((e && e.message) ? e.message : String(e)));
+                }
+            }
+            return { found, errors };
+        };
+"#;
+
+/// Wrap a script body so it runs with `webpackRequires()` in scope
+///
+/// The body must return a JSON string; anything it throws is returned as `{ error }`.
+fn webpack_script(body: &str) -> String {
+    format!(
+        "(() => {{\n    try {{{JS_WEBPACK_REQUIRES}{body}    }} catch (e) {{\n        return JSON.stringify({{ error: (e && e.message) ? e.message : String(e) }});\n    }}\n}})()\n"
+    )
+}
+
+/// JavaScript code: Get SuperProperties
+///
+/// Built on `JS_WEBPACK_REQUIRES`. To survive bundler updates it tries, in order:
+/// 1. `named`: a module exporting `getSuperPropertiesBase64` and `getSuperProperties`
+/// 2. `scan`: a module whose source mentions `getSuperPropertiesBase64` but whose exports
+///    are minified; only its zero-argument exports are called, and a result counts only
+///    if it is base64 decoding to JSON with `client_build_number`. Other modules' exports
+///    are never invoked.
+///
+/// The result names the strategy and chunk global that succeeded.
 ///
 /// Fallback behavior: If extraction fails, the app falls back to:
 /// 1. Remote JS (fetching from Discord's website)
 /// 2. Built-in defaults
-const JS_GET_SUPER_PROPERTIES: &str = r#"
-(() => {
-    try {
-        if (typeof window !== "undefined" && !window.webpackChunkdiscord_app) {
-            return JSON.stringify({ error: "Discord webpackChunkdiscord_app not found; the Discord client structure may have changed." });
-        }
+static JS_GET_SUPER_PROPERTIES: Lazy<String> = Lazy::new(|| {
+    webpack_script(
+        r#"
+        const { found, errors } = webpackRequires();
 
-        let wpRequire = webpackChunkdiscord_app.push([[Symbol()], {}, r => r]);
-        webpackChunkdiscord_app.pop();
-        
-        // Search for the correct SuperProperties module
-        // Module must have both getSuperPropertiesBase64 and getSuperProperties methods
-        // And getSuperPropertiesBase64() must return a string (base64 encoded)
-        let superPropsModule = null;
-        for (const m of Object.values(wpRequire.c)) {
+        const decode = (base64) => {
             try {
-                const exp = m?.exports?.default;
-                if (exp && typeof exp.getSuperPropertiesBase64 === 'function' && typeof exp.getSuperProperties === 'function') {
-                    const base64Result = exp.getSuperPropertiesBase64();
-                    // The real SuperProperties returns a base64 string, not an object
-                    if (typeof base64Result === 'string' && base64Result.length > 50) {
-                        superPropsModule = m;
-                        break;
+                const decoded = JSON.parse(atob(base64));
+                return decoded && typeof decoded === 'object' && decoded.client_build_number ? decoded : null;
+            } catch (e) {
+                return null;
+            }
+        };
+
+        // Strategy 1: the module with the well-known method names
+        // getSuperPropertiesBase64() must return a string (base64 encoded), not an object
+        const named = (modules) => {
+            for (const m of modules) {
+                try {
+                    const exp = m?.exports?.default;
+                    if (exp && typeof exp.getSuperPropertiesBase64 === 'function' && typeof exp.getSuperProperties === 'function') {
+                        const base64 = exp.getSuperPropertiesBase64();
+                        const decoded = exp.getSuperProperties();
+                        if (typeof base64 === 'string' && base64.length > 50 && decoded && typeof decoded === 'object' && decoded.client_build_number) {
+                            return { base64, decoded };
+                        }
                     }
+                } catch (e) {
+                    continue;
                 }
-            } catch (e) {
-                continue;
             }
+            return null;
+        };
+
+        // Strategy 2: the getter module found by its source, with minified export names
+        const SIGNATURE = "getSuperPropertiesBase64";
+        const scan = (wpRequire) => {
+            for (const [id, factory] of Object.entries(wpRequire.m || {})) {
+                let source;
+                try {
+                    source = Function.prototype.toString.call(factory);
+                } catch (e) {
+                    continue;
+                }
+                if (!source.includes(SIGNATURE)) continue;
+                const exports = wpRequire.c?.[id]?.exports;
+                if (!exports || typeof exports !== 'object') continue;
+                // The module itself and its exported objects (`default`, minified names, ...)
+                let candidates;
+                try {
+                    candidates = [exports].concat(Object.values(exports).filter(v => v && typeof v === 'object'));
+                } catch (e) {
+                    continue;
+                }
+                for (const exp of candidates) {
+                    for (const key of Object.keys(exp)) {
+                        try {
+                            const fn = exp[key];
+                            if (typeof fn !== 'function' || fn.length !== 0) continue;
+                            const base64 = fn.call(exp);
+                            if (typeof base64 !== 'string' || base64.length <= 50) continue;
+                            const decoded = decode(base64);
+                            if (decoded) return { base64, decoded };
+                        } catch (e) {
+                            continue;
+                        }
+                    }
+                }
+            }
+            return null;
+        };
+
+        for (const { chunk, wpRequire } of found) {
+            const modules = Object.values(wpRequire.c);
+            for (const [strategy, find] of [["named", () => named(modules)], ["scan", () => scan(wpRequire)]]) {
+                const result = find();
+                if (result) return JSON.stringify({ ...result, strategy, chunk });
+            }
+            errors.push(chunk + ": SuperProperties module not found");
         }
-        
-        if (!superPropsModule) return JSON.stringify({ error: "SuperProperties module not found" });
-        
-        const base64 = superPropsModule.exports.default.getSuperPropertiesBase64();
-        const decoded = superPropsModule.exports.default.getSuperProperties();
-        
-        // Verify return value format
-        if (typeof base64 !== 'string') {
-            return JSON.stringify({ error: "getSuperPropertiesBase64 did not return a string" });
-        }
-        if (!decoded || typeof decoded !== 'object' || !decoded.client_build_number) {
-            return JSON.stringify({ error: "getSuperProperties did not return valid object" });
-        }
-        
-        return JSON.stringify({ base64, decoded });
-    } catch (e) {
-        let message = (e && e.message) ? e.message : String(e);
-        try {
-            if (typeof window !== "undefined" && !window.webpackChunkdiscord_app) {
-                message = "Discord webpackChunkdiscord_app not found; variable missing during execution. Original error: " + message;
+
+        return JSON.stringify({ error: errors.join("; ") });
+"#,
+    )
+});
+
+/// JavaScript code: Get the logged-in user from Discord's `UserStore`
+///
+/// Built on `JS_WEBPACK_REQUIRES`, so it finds the same chunk globals as
+/// `JS_GET_SUPER_PROPERTIES`. The store is found by its `getCurrentUser` method; fields are
+/// renamed to the REST API's snake_case so the result deserializes as a `DiscordUser`.
+static JS_GET_CURRENT_USER: Lazy<String> = Lazy::new(|| {
+    webpack_script(
+        r#"
+        const { found, errors } = webpackRequires();
+
+        for (const { chunk, wpRequire } of found) {
+            let userStore = null;
+            for (const m of Object.values(wpRequire.c)) {
+                try {
+                    const exp = m?.exports;
+                    if (!exp) continue;
+                    for (const key of Object.keys(exp)) {
+                        const val = exp[key];
+                        if (val && typeof val.__proto__?.getCurrentUser === 'function' && typeof val.__proto__?.getUser === 'function') {
+                            userStore = val;
+                            break;
+                        }
+                    }
+                    if (userStore) break;
+                } catch (e) {
+                    continue;
+                }
+            }
+
+            if (!userStore) {
+                errors.push(chunk + ": UserStore module not found");
+                continue;
             }
-        } catch (_) {}
-        return JSON.stringify({ error: message });
-    }
-})()
-"#;
+
+            const user = userStore.getCurrentUser();
+            if (!user || !user.id) return JSON.stringify({ error: "Discord has no logged-in user" });
+
+            return JSON.stringify({
+                id: String(user.id),
+                username: user.username ?? "",
+                discriminator: user.discriminator ?? "0",
+                avatar: user.avatar ?? null,
+                global_name: user.globalName ?? user.global_name ?? null,
+                premium_type: user.premiumType ?? user.premium_type ?? null
+            });
+        }
+
+        return JSON.stringify({ error: errors.join("; ") });
+"#,
+    )
+});
 
 /// Check if CDP port is available
 pub async fn check_cdp_available(port: u16) -> CdpStatus {
@@ -176,7 +339,9 @@ pub async fn check_cdp_available(port: u16) -> CdpStatus {
 
 /// Get CDP target list
 async fn get_cdp_targets(port: u16) -> Result<Vec<CdpTarget>> {
+    // CDP always lives on localhost; never route it through a system or user proxy
     let client = reqwest::Client::builder()
+        .no_proxy()
         .connect_timeout(Duration::from_secs(2))
         .timeout(Duration::from_secs(3))
         .build()?;
@@ -246,6 +411,36 @@ fn select_discord_targets<'a>(targets: &'a [CdpTarget]) -> Vec<&'a CdpTarget> {
     selected_targets
 }
 
+/// Ports to probe, in order: the preferred one first, then the scan range
+fn ports_to_scan(preferred: Option<u16>) -> Vec<u16> {
+    preferred
+        .into_iter()
+        .chain(CDP_PORT_SCAN_RANGE.filter(|port| Some(*port) != preferred))
+        .collect()
+}
+
+/// Find a port where Discord is exposing CDP
+///
+/// Probes `preferred` and every port in `CDP_PORT_SCAN_RANGE` concurrently and
+/// returns the first (in that order) whose target list contains a Discord page.
+/// Other Electron apps on the range are ignored.
+pub async fn find_cdp_port(preferred: Option<u16>) -> Option<u16> {
+    let ports = ports_to_scan(preferred);
+    let results = join_all(ports.iter().map(|port| get_cdp_targets(*port))).await;
+
+    ports
+        .into_iter()
+        .zip(results)
+        .find(|(_, targets)| {
+            targets.as_ref().is_ok_and(|targets| {
+                targets
+                    .iter()
+                    .any(|t| is_discord_target(t) && t.web_socket_debugger_url.is_some())
+            })
+        })
+        .map(|(port, _)| port)
+}
+
 pub async fn get_primary_discord_target(port: u16) -> Result<CdpTarget> {
     let targets = get_cdp_targets(port).await?;
 
@@ -302,11 +497,17 @@ pub async fn bring_primary_discord_target_to_front(port: u16) -> Result<()> {
     bring_target_to_front_via_ws(ws_url, 5).await
 }
 
+/// Evaluate `js_code` on the primary Discord page
+///
+/// With `retry_on_new_target`, a connection lost because Discord replaced the page is
+/// retried once on the new page. Only pass it for read-only scripts: the first
+/// evaluation may already have taken effect before the connection dropped.
 pub async fn execute_js_via_primary_discord_target(
     port: u16,
     js_code: &str,
     await_promise: bool,
     timeout_secs: u64,
+    retry_on_new_target: bool,
 ) -> Result<String> {
     use crate::logger::{log, LogCategory, LogLevel};
 
@@ -329,12 +530,42 @@ pub async fn execute_js_via_primary_discord_target(
         None,
     );
 
-    execute_js_via_ws(ws_url, js_code, await_promise, timeout_secs).await
+    match execute_js_via_ws(ws_url, js_code, await_promise, timeout_secs).await {
+        Err(e) if retry_on_new_target && e.is::<ConnectionLost>() => {
+            // Discord may have replaced the page (reload, update, new window); only retry
+            // on a different target so the script can't run twice on the same page
+            let retarget = get_primary_discord_target(port).await?;
+            if retarget.id == target.id || retarget.web_socket_debugger_url.is_none() {
+                return Err(e);
+            }
+            log(
+                LogLevel::Warn,
+                LogCategory::TokenExtraction,
+                &format!(
+                    "CDP target changed during evaluation, retrying on {}",
+                    retarget.url
+                ),
+                Some(&e.to_string()),
+            );
+            let ws_url = retarget
+                .web_socket_debugger_url
+                .as_ref()
+                .context("Target has no WebSocket URL")?;
+            execute_js_via_ws(ws_url, js_code, await_promise, timeout_secs).await
+        }
+        result => result,
+    }
 }
 
 /// Get SuperProperties via CDP
-pub async fn fetch_super_properties_via_cdp(port: u16) -> Result<CdpSuperProperties> {
-    use crate::logger::{log, LogCategory, LogLevel};
+///
+/// `timeout_secs` bounds the evaluation; `SUPER_PROPERTIES_TIMEOUT_SECS` suits a
+/// responsive client, slower machines may need more.
+pub async fn fetch_super_properties_via_cdp(
+    port: u16,
+    timeout_secs: u64,
+) -> Result<CdpSuperProperties> {
+    use crate::logger::{log, truncate_chars, LogCategory, LogLevel};
 
     log(
         LogLevel::Info,
@@ -346,158 +577,27 @@ pub async fn fetch_super_properties_via_cdp(port: u16) -> Result<CdpSuperPropert
         None,
     );
 
-    // Get targets
-    let targets = get_cdp_targets(port).await?;
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        &format!("Found {} CDP targets", targets.len()),
-        None,
-    );
-
-    let target = pick_discord_target(&targets).context("No Discord target found")?;
-
-    let ws_url = target
-        .web_socket_debugger_url
-        .as_ref()
-        .context("Target has no WebSocket URL")?;
+    let result_value = execute_js_via_primary_discord_target(
+        port,
+        &JS_GET_SUPER_PROPERTIES,
+        false,
+        timeout_secs,
+        true,
+    )
+    .await?;
 
     log(
         LogLevel::Debug,
         LogCategory::TokenExtraction,
         &format!(
-            "Connecting to CDP target: {} (URL: {})",
-            target.title, ws_url
-        ),
-        None,
-    );
-
-    // Establish WebSocket connection
-    let (ws_stream, _) = connect_async(ws_url)
-        .await
-        .context("Failed to connect to CDP WebSocket")?;
-
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        "WebSocket connection established",
-        None,
-    );
-
-    let (mut write, mut read) = ws_stream.split();
-
-    // Send Runtime.evaluate request
-    let request = serde_json::json!({
-        "id": 1,
-        "method": "Runtime.evaluate",
-        "params": {
-            "expression": JS_GET_SUPER_PROPERTIES,
-            "returnByValue": true,
-            "awaitPromise": false
-        }
-    });
-
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        "Sending Runtime.evaluate request",
-        None,
-    );
-
-    write
-        .send(Message::Text(request.to_string().into()))
-        .await
-        .context("Failed to send CDP request")?;
-
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        "Request sent, waiting for response...",
-        None,
-    );
-
-    // Read response
-    let response = tokio::time::timeout(Duration::from_secs(10), async {
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    log(
-                        LogLevel::Debug,
-                        LogCategory::TokenExtraction,
-                        &format!(
-                            "Received message: {}...",
-                            &text.chars().take(200).collect::<String>()
-                        ),
-                        None,
-                    );
-
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                        if json.get("id") == Some(&serde_json::json!(1)) {
-                            return Ok(json);
-                        }
-                    }
-                }
-                Ok(other) => {
-                    log(
-                        LogLevel::Debug,
-                        LogCategory::TokenExtraction,
-                        &format!("Received non-text message: {:?}", other),
-                        None,
-                    );
-                    continue;
-                }
-                Err(e) => {
-                    log(
-                        LogLevel::Error,
-                        LogCategory::TokenExtraction,
-                        &format!("WebSocket error: {}", e),
-                        None,
-                    );
-                    return Err(anyhow::anyhow!("WebSocket error: {}", e));
-                }
-            }
-        }
-        log(
-            LogLevel::Error,
-            LogCategory::TokenExtraction,
-            "WebSocket closed unexpectedly",
-            None,
-        );
-        Err(anyhow::anyhow!("WebSocket closed unexpectedly"))
-    })
-    .await
-    .context("CDP request timed out (10s)")??;
-
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        "Received valid CDP response",
-        None,
-    );
-
-    // Close connection
-    let _ = write.close().await;
-
-    // Parse response
-    let result_value = response
-        .get("result")
-        .and_then(|r| r.get("result"))
-        .and_then(|r| r.get("value"))
-        .and_then(|v| v.as_str())
-        .context("Invalid CDP response structure")?;
-
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        &format!(
-            "JavaScript returned: {}...",
-            &result_value.chars().take(100).collect::<String>()
+            "JavaScript returned: {}",
+            truncate_chars(&result_value, 100)
         ),
         None,
     );
 
     let parsed: serde_json::Value =
-        serde_json::from_str(result_value).context("Failed to parse JavaScript result")?;
+        serde_json::from_str(&result_value).context("Failed to parse JavaScript result")?;
 
     // Check for errors
     if let Some(error) = parsed.get("error") {
@@ -517,12 +617,14 @@ pub async fn fetch_super_properties_via_cdp(port: u16) -> Result<CdpSuperPropert
         LogLevel::Info,
         LogCategory::TokenExtraction,
         &format!(
-            "Successfully fetched SuperProperties via CDP. Build number: {}",
+            "Successfully fetched SuperProperties via CDP. Build number: {} (strategy: {}, chunk: {})",
             super_props
                 .decoded
                 .get("client_build_number")
                 .and_then(|v| v.as_u64())
-                .unwrap_or(0)
+                .unwrap_or(0),
+            super_props.strategy.as_deref().unwrap_or("unknown"),
+            super_props.chunk.as_deref().unwrap_or("unknown")
         ),
         None,
     );
@@ -530,6 +632,34 @@ pub async fn fetch_super_properties_via_cdp(port: u16) -> Result<CdpSuperPropert
     Ok(super_props)
 }
 
+/// Get the logged-in user from the running Discord client via CDP
+///
+/// Reads Discord's own user store, so it works without extracting or decrypting a
+/// token (e.g. when DPAPI or the keychain refuses access). It only identifies the
+/// account signed in to the client, e.g. to match a detected account or show who CDP
+/// quests run as; no token is returned, so it cannot log in on its own.
+pub async fn fetch_current_user_via_cdp(port: u16) -> Result<crate::models::DiscordUser> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let raw =
+        execute_js_via_primary_discord_target(port, &JS_GET_CURRENT_USER, false, 10, true).await?;
+    let parsed: serde_json::Value =
+        serde_json::from_str(&raw).context("Failed to parse JavaScript result")?;
+    if let Some(error) = parsed.get("error") {
+        anyhow::bail!("JavaScript error: {}", error);
+    }
+
+    let user: crate::models::DiscordUser =
+        serde_json::from_value(parsed).context("Failed to parse current user")?;
+    log(
+        LogLevel::Info,
+        LogCategory::TokenExtraction,
+        &format!("Read current user via CDP: {}", user.id),
+        None,
+    );
+    Ok(user)
+}
+
 /// Capture Discord API request headers via CDP Network interception.
 ///
 /// Enables CDP Network domain, listens for ALL outgoing requests for `duration_secs`,
@@ -801,17 +931,91 @@ pub async fn execute_js_on_target(
     execute_js_via_ws(ws_url, js_code, await_promise, timeout_secs).await
 }
 
+/// The WebSocket to a target failed or closed before answering, e.g. because Discord
+/// replaced the page. Callers can re-pick the target and retry.
+#[derive(Debug)]
+struct ConnectionLost(String);
+
+impl std::fmt::Display for ConnectionLost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConnectionLost {}
+
+/// Wait for the response to request `id`, pinging the target so a long evaluation on a
+/// live connection isn't mistaken for a dead one (and a dead one fails fast)
+async fn await_cdp_response<W, R>(
+    write: &mut W,
+    read: &mut R,
+    id: u64,
+    timeout: Duration,
+) -> Result<serde_json::Value>
+where
+    W: futures_util::Sink<Message> + Unpin,
+    W::Error: std::fmt::Display,
+    R: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut ping =
+        tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+    let mut unanswered_since: Option<tokio::time::Instant> = None;
+
+    loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
+                        if json.get("id") == Some(&serde_json::json!(id)) {
+                            return Ok(json);
+                        }
+                    }
+                }
+                Some(Ok(Message::Pong(_))) => unanswered_since = None,
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    return Err(ConnectionLost(format!("WebSocket error: {}", e)).into());
+                }
+                None => {
+                    return Err(ConnectionLost("WebSocket closed unexpectedly".to_string()).into());
+                }
+            },
+            _ = ping.tick() => {
+                if let Some(since) = unanswered_since {
+                    if since.elapsed() >= PONG_TIMEOUT {
+                        return Err(ConnectionLost(format!(
+                            "CDP connection stopped responding (no pong for {}s)",
+                            PONG_TIMEOUT.as_secs()
+                        ))
+                        .into());
+                    }
+                } else {
+                    write
+                        .send(Message::Ping(Vec::new().into()))
+                        .await
+                        .map_err(|e| ConnectionLost(format!("Failed to send CDP ping: {}", e)))?;
+                    unanswered_since = Some(tokio::time::Instant::now());
+                }
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                anyhow::bail!("CDP request timed out ({}s)", timeout.as_secs());
+            }
+        }
+    }
+}
+
 async fn execute_js_via_ws(
     ws_url: &str,
     js_code: &str,
     await_promise: bool,
     timeout_secs: u64,
 ) -> Result<String> {
-    use crate::logger::{log, LogCategory, LogLevel};
+    use crate::logger::{log, truncate_chars, LogCategory, LogLevel};
 
     let (ws_stream, _) = connect_async(ws_url)
         .await
-        .context("Failed to connect to CDP WebSocket")?;
+        .map_err(|e| ConnectionLost(format!("Failed to connect to CDP WebSocket: {}", e)))?;
     let (mut write, mut read) = ws_stream.split();
 
     let request = serde_json::json!({
@@ -827,26 +1031,10 @@ async fn execute_js_via_ws(
     write
         .send(Message::Text(request.to_string().into()))
         .await
-        .context("Failed to send CDP request")?;
+        .map_err(|e| ConnectionLost(format!("Failed to send CDP request: {}", e)))?;
 
-    let response = tokio::time::timeout(Duration::from_secs(timeout_secs), async {
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                        if json.get("id") == Some(&serde_json::json!(1)) {
-                            return Ok(json);
-                        }
-                    }
-                }
-                Ok(_) => continue,
-                Err(e) => return Err(anyhow::anyhow!("WebSocket error: {}", e)),
-            }
-        }
-        Err(anyhow::anyhow!("WebSocket closed unexpectedly"))
-    })
-    .await
-    .context(format!("CDP request timed out ({}s)", timeout_secs))??;
+    let response =
+        await_cdp_response(&mut write, &mut read, 1, Duration::from_secs(timeout_secs)).await?;
 
     let _ = write.close().await;
 
@@ -906,8 +1094,8 @@ async fn execute_js_via_ws(
         LogLevel::Debug,
         LogCategory::TokenExtraction,
         &format!(
-            "execute_js_via_cdp result: {}...",
-            &result_value.chars().take(200).collect::<String>()
+            "execute_js_via_cdp result: {}",
+            truncate_chars(&result_value, 200)
         ),
         None,
     );
@@ -1139,6 +1327,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ports_to_scan_prefers_configured_port() {
+        let ports = ports_to_scan(Some(9225));
+        assert_eq!(ports[0], 9225);
+        assert_eq!(ports.len(), CDP_PORT_SCAN_RANGE.count());
+        assert_eq!(ports.iter().filter(|p| **p == 9225).count(), 1);
+
+        let ports = ports_to_scan(Some(9300));
+        assert_eq!(ports[0], 9300);
+        assert_eq!(ports[1], 9222);
+
+        assert_eq!(ports_to_scan(None), CDP_PORT_SCAN_RANGE.collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_pick_discord_target() {
         let targets = vec![
@@ -1227,4 +1429,46 @@ mod tests {
         let fallback_none = select_discord_targets(&fallback_missing_ws);
         assert_eq!(fallback_none.len(), 0);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cdp_response_keepalive() {
+        let reply = Message::Text(r#"{"id":1,"result":{}}"#.into());
+        let mut read = futures_util::stream::iter(vec![
+            Ok(Message::Text(
+                r#"{"method":"Runtime.consoleAPICalled"}"#.into(),
+            )),
+            Ok(reply),
+        ]);
+        let response = await_cdp_response(
+            &mut futures_util::sink::drain(),
+            &mut read,
+            1,
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["id"], 1);
+
+        // Pings go unanswered: the connection is reported lost before the long timeout
+        let err = await_cdp_response(
+            &mut futures_util::sink::drain(),
+            &mut futures_util::stream::pending(),
+            1,
+            Duration::from_secs(120),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is::<ConnectionLost>(), "{}", err);
+
+        // A closed socket is a lost connection too, so the caller re-picks the target
+        let err = await_cdp_response(
+            &mut futures_util::sink::drain(),
+            &mut futures_util::stream::empty(),
+            1,
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is::<ConnectionLost>());
+    }
 }
@@ -10,10 +10,10 @@
 
 use anyhow::{Context, Result};
 use std::time::Duration;
-use tauri::Emitter;
 use tokio::time::sleep;
 
 use crate::cdp_client;
+use crate::quest_completer::QuestOutcome;
 
 const QUEST_HOME_URL: &str = "https://discord.com/quest-home";
 const QUEST_HOME_DETOUR_URL: &str = "https://discord.com/store";
@@ -950,6 +950,119 @@ fn js_warmup_quest_route(plan: &QuestRouteWarmupPlan) -> String {
     )
 }
 
+/// JavaScript code: POST `body` to `url` with Discord's internal `api` module
+///
+/// Requires `JS_INIT_QUEST_MODULES` to have run. Resolves to `{ success, body }`, or
+/// `{ success: false, status, error }` when the request fails.
+fn js_api_post(url: &str, body: &serde_json::Value) -> String {
+    format!(
+        r#"
+(async () => {{
+    const dqh = window.__dqh_cdp;
+    if (!dqh || !dqh.api) return JSON.stringify({{ success: false, error: "Discord API module not initialized" }});
+    try {{
+        const res = await Promise.race([
+            dqh.api.post({{ url: {url}, body: {body} }}),
+            new Promise((_, reject) => setTimeout(() => reject(new Error("API call timed out after 15000ms")), 15000))
+        ]);
+        return JSON.stringify({{ success: true, body: res?.body ?? null }});
+    }} catch (e) {{
+        return JSON.stringify({{
+            success: false,
+            status: e?.status ?? null,
+            error: e?.body?.message || e?.message || String(e)
+        }});
+    }}
+}})()
+"#,
+        url = serde_json::Value::from(url),
+        body = body
+    )
+}
+
+/// POST through the Discord client's own API module, returning the response body
+async fn cdp_api_post(
+    port: u16,
+    url: &str,
+    body: serde_json::Value,
+    operation: &str,
+) -> Result<serde_json::Value> {
+    cdp_init_modules(port)
+        .await
+        .with_context(|| format!("Failed to initialize CDP modules for {}", operation))?;
+
+    let raw = cdp_client::execute_js_via_primary_discord_target(
+        port,
+        &js_api_post(url, &body),
+        true,
+        20,
+        false,
+    )
+    .await?;
+    let parsed: serde_json::Value = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse CDP {} result", operation))?;
+    if !cdp_result_succeeded(&parsed) {
+        anyhow::bail!(
+            "CDP {} failed (status {}): {}",
+            operation,
+            parsed.get("status").unwrap_or(&serde_json::Value::Null),
+            parsed
+                .get("error")
+                .and_then(|value| value.as_str())
+                .unwrap_or("unknown error")
+        );
+    }
+    Ok(parsed.get("body").cloned().unwrap_or_default())
+}
+
+/// Accept (enroll in) a quest through the running Discord client, without a token
+///
+/// Mirrors `DiscordApiClient::accept_quest`, but the request is sent by Discord itself.
+pub async fn accept_quest_via_cdp(port: u16, quest_id: &str) -> Result<serde_json::Value> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let body = cdp_api_post(
+        port,
+        &format!("/quests/{}/enroll", quest_id),
+        serde_json::json!({
+            "location": 11,
+            "is_targeted": false,
+            "metadata_raw": null
+        }),
+        "quest enrollment",
+    )
+    .await?;
+    log(
+        LogLevel::Info,
+        LogCategory::Quest,
+        &format!("Accepted quest {} via CDP", quest_id),
+        None,
+    );
+    Ok(body)
+}
+
+/// Send a game heartbeat through the running Discord client, without a token
+///
+/// Mirrors `DiscordApiClient::send_game_heartbeat`; returns whether the quest is completed.
+pub async fn send_heartbeat_via_cdp(
+    port: u16,
+    quest_id: &str,
+    application_id: &str,
+    terminal: bool,
+) -> Result<bool> {
+    let body = cdp_api_post(
+        port,
+        &format!("/quests/{}/heartbeat", quest_id),
+        serde_json::json!({
+            "application_id": application_id,
+            "terminal": terminal
+        }),
+        "heartbeat",
+    )
+    .await?;
+    Ok(body.get("completed_at").is_some_and(|v| !v.is_null()))
+}
+
 fn cdp_result_succeeded(parsed: &serde_json::Value) -> bool {
     parsed
         .get("success")
@@ -1034,7 +1147,7 @@ async fn cdp_execute_json_on_all_targets(
                     "{} parse_err={} raw={}",
                     target_prefix,
                     err,
-                    raw.chars().take(200).collect::<String>()
+                    crate::logger::truncate_chars(&raw, 200)
                 ));
                 continue;
             }
@@ -1049,7 +1162,7 @@ async fn cdp_execute_json_on_all_targets(
             .get("error")
             .and_then(|value| value.as_str())
             .map(ToOwned::to_owned)
-            .unwrap_or_else(|| raw.chars().take(200).collect::<String>());
+            .unwrap_or_else(|| crate::logger::truncate_chars(&raw, 200));
         target_failures.push(format!("{} err={}", target_prefix, error));
     }
 
@@ -1123,6 +1236,7 @@ async fn cdp_warmup_quest_route(port: u16) {
         &spa_warmup_js,
         true,
         QUEST_WARMUP_NAV_TIMEOUT_SECS,
+        false,
     )
     .await
     {
@@ -1270,6 +1384,25 @@ async fn cdp_init_modules(port: u16) -> Result<()> {
     Ok(())
 }
 
+/// Ends the fire-and-forget video loop started by `js_start_video_quest`
+const JS_STOP_VIDEO_LOOP: &str = "(() => { if (window.__dqh_cdp) { window.__dqh_cdp._videoRunning = false; } return JSON.stringify({ success: true, stopped: true }); })()";
+
+/// Undo what a CDP quest left running in Discord after it hit its time limit
+///
+/// `with_time_limit` drops the completer mid-flight, so its own cleanup never runs: this
+/// stops the video loop and removes the spoofed game and stream stores.
+pub async fn stop_timed_out_quest(port: u16) {
+    let _ = cdp_execute_json_on_all_targets(
+        port,
+        JS_STOP_VIDEO_LOOP,
+        false,
+        5,
+        "video quest stop signal",
+    )
+    .await;
+    cdp_cleanup(port).await;
+}
+
 /// Cleanup spoofed stores via CDP.
 async fn cdp_cleanup(port: u16) {
     use crate::logger::{log, LogCategory, LogLevel};
@@ -1622,7 +1755,7 @@ pub async fn complete_play_quest_via_cdp(
     client: Option<crate::discord_api::DiscordApiClient>,
     app_handle: tauri::AppHandle,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     use crate::logger::{log, LogCategory, LogLevel};
 
     log(
@@ -1701,12 +1834,14 @@ pub async fn complete_play_quest_via_cdp(
 
     // 3. Poll progress using Rust API client (reliable) with CDP fallback
     let poll_interval = Duration::from_secs(15);
-    let initial_pct = if seconds_needed > 0 {
-        (initial_progress / seconds_needed as f64) * 100.0
-    } else {
-        0.0
-    };
-    let _ = app_handle.emit("quest-progress", initial_pct);
+    crate::quest_completer::emit_cdp_progress(
+        &app_handle,
+        &quest_id,
+        "PLAY_ON_DESKTOP",
+        initial_progress,
+        seconds_needed,
+        poll_interval.as_secs(),
+    );
 
     loop {
         tokio::select! {
@@ -1714,8 +1849,8 @@ pub async fn complete_play_quest_via_cdp(
             _ = cancel_rx.recv() => {
                 log(LogLevel::Info, LogCategory::TokenExtraction, "CDP play quest cancelled", None);
                 cdp_cleanup(port).await;
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                crate::quest_completer::emit_event(&app_handle, "quest-stopped", ());
+                return Ok(QuestOutcome::Cancelled);
             }
         }
 
@@ -1771,7 +1906,14 @@ pub async fn complete_play_quest_via_cdp(
             0.0
         };
 
-        let _ = app_handle.emit("quest-progress", pct);
+        crate::quest_completer::emit_cdp_progress(
+            &app_handle,
+            &quest_id,
+            "PLAY_ON_DESKTOP",
+            progress_secs,
+            seconds_needed,
+            poll_interval.as_secs(),
+        );
         log(
             LogLevel::Debug,
             LogCategory::TokenExtraction,
@@ -1790,8 +1932,10 @@ pub async fn complete_play_quest_via_cdp(
                 None,
             );
             cdp_cleanup(port).await;
-            let _ = app_handle.emit("quest-complete", ());
-            return Ok(());
+            crate::quest_completer::emit_event(&app_handle, "quest-complete", ());
+            crate::metrics::quest_completed();
+            crate::notifications::quest_completed(&app_handle, Some(&quest_id));
+            return Ok(QuestOutcome::Completed);
         }
     }
 }
@@ -1808,7 +1952,7 @@ pub async fn complete_stream_quest_via_cdp(
     client: Option<crate::discord_api::DiscordApiClient>,
     app_handle: tauri::AppHandle,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     use crate::logger::{log, LogCategory, LogLevel};
 
     log(
@@ -1862,12 +2006,14 @@ pub async fn complete_stream_quest_via_cdp(
 
     // 3. Poll progress using Rust API client (reliable) with CDP fallback
     let poll_interval = Duration::from_secs(20);
-    let initial_pct = if seconds_needed > 0 {
-        (initial_progress / seconds_needed as f64) * 100.0
-    } else {
-        0.0
-    };
-    let _ = app_handle.emit("quest-progress", initial_pct);
+    crate::quest_completer::emit_cdp_progress(
+        &app_handle,
+        &quest_id,
+        "STREAM_ON_DESKTOP",
+        initial_progress,
+        seconds_needed,
+        poll_interval.as_secs(),
+    );
 
     loop {
         tokio::select! {
@@ -1875,8 +2021,8 @@ pub async fn complete_stream_quest_via_cdp(
             _ = cancel_rx.recv() => {
                 log(LogLevel::Info, LogCategory::TokenExtraction, "CDP stream quest cancelled", None);
                 cdp_cleanup(port).await;
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                crate::quest_completer::emit_event(&app_handle, "quest-stopped", ());
+                return Ok(QuestOutcome::Cancelled);
             }
         }
 
@@ -1932,7 +2078,14 @@ pub async fn complete_stream_quest_via_cdp(
             0.0
         };
 
-        let _ = app_handle.emit("quest-progress", pct);
+        crate::quest_completer::emit_cdp_progress(
+            &app_handle,
+            &quest_id,
+            "STREAM_ON_DESKTOP",
+            progress_secs,
+            seconds_needed,
+            poll_interval.as_secs(),
+        );
         log(
             LogLevel::Debug,
             LogCategory::TokenExtraction,
@@ -1951,8 +2104,10 @@ pub async fn complete_stream_quest_via_cdp(
                 None,
             );
             cdp_cleanup(port).await;
-            let _ = app_handle.emit("quest-complete", ());
-            return Ok(());
+            crate::quest_completer::emit_event(&app_handle, "quest-complete", ());
+            crate::metrics::quest_completed();
+            crate::notifications::quest_completed(&app_handle, Some(&quest_id));
+            return Ok(QuestOutcome::Completed);
         }
     }
 }
@@ -1969,7 +2124,7 @@ pub async fn complete_video_quest_via_cdp(
     initial_progress: f64,
     app_handle: tauri::AppHandle,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     use crate::logger::{log, LogCategory, LogLevel};
 
     log(
@@ -1991,12 +2146,16 @@ pub async fn complete_video_quest_via_cdp(
         .await
         .context("Failed to initialize CDP modules for video quest")?;
 
-    let initial_pct = if seconds_needed > 0 {
-        (initial_progress / seconds_needed as f64) * 100.0
-    } else {
-        0.0
-    };
-    let _ = app_handle.emit("quest-progress", initial_pct);
+    // The run's time limit is applied by the caller (`with_time_limit`)
+    let poll_interval = Duration::from_secs(5);
+    crate::quest_completer::emit_cdp_progress(
+        &app_handle,
+        &quest_id,
+        "WATCH_VIDEO",
+        initial_progress,
+        seconds_needed,
+        poll_interval.as_secs(),
+    );
 
     // 2. Fire-and-forget: launch the async video JS loop inside Discord.
     //    The JS stores its Promise globally (prevents V8 GC) and writes progress
@@ -2022,11 +2181,6 @@ pub async fn complete_video_quest_via_cdp(
     );
 
     // 3. Poll progress until the JS loop finishes (videoRunning=false) or quest completes
-    let poll_interval = Duration::from_secs(5);
-    let max_duration = Duration::from_secs(
-        ((seconds_needed as f64 - initial_progress).max(0.0) / 7.0 * 2.0) as u64 + 300,
-    );
-    let start_time = std::time::Instant::now();
 
     loop {
         tokio::select! {
@@ -2036,28 +2190,16 @@ pub async fn complete_video_quest_via_cdp(
                 // Try to stop the JS loop
                 let _ = cdp_execute_json_on_all_targets(
                     port,
-                    "(() => { if (window.__dqh_cdp) { window.__dqh_cdp._videoRunning = false; } return JSON.stringify({ success: true, stopped: true }); })()",
+                    JS_STOP_VIDEO_LOOP,
                     false,
                     5,
                     "video quest stop signal"
                 ).await;
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                crate::quest_completer::emit_event(&app_handle, "quest-stopped", ());
+                return Ok(QuestOutcome::Cancelled);
             }
         }
 
-        // Timeout safety
-        if start_time.elapsed() > max_duration {
-            log(
-                LogLevel::Error,
-                LogCategory::TokenExtraction,
-                &format!("CDP video quest timed out after {:?}", start_time.elapsed()),
-                None,
-            );
-            let _ = app_handle.emit("quest-error", "Video quest timed out".to_string());
-            return Ok(());
-        }
-
         // Poll progress
         match cdp_poll_progress(port, &quest_id).await {
             Ok((progress_secs, completed)) => {
@@ -2067,7 +2209,14 @@ pub async fn complete_video_quest_via_cdp(
                     0.0
                 };
 
-                let _ = app_handle.emit("quest-progress", pct);
+                crate::quest_completer::emit_cdp_progress(
+                    &app_handle,
+                    &quest_id,
+                    "WATCH_VIDEO",
+                    progress_secs,
+                    seconds_needed,
+                    poll_interval.as_secs(),
+                );
                 log(
                     LogLevel::Debug,
                     LogCategory::TokenExtraction,
@@ -2085,9 +2234,18 @@ pub async fn complete_video_quest_via_cdp(
                         "CDP video quest completed!",
                         None,
                     );
-                    let _ = app_handle.emit("quest-progress", 100.0f64);
-                    let _ = app_handle.emit("quest-complete", ());
-                    return Ok(());
+                    crate::quest_completer::emit_cdp_progress(
+                        &app_handle,
+                        &quest_id,
+                        "WATCH_VIDEO",
+                        seconds_needed as f64,
+                        seconds_needed,
+                        0,
+                    );
+                    crate::quest_completer::emit_event(&app_handle, "quest-complete", ());
+                    crate::metrics::quest_completed();
+                    crate::notifications::quest_completed(&app_handle, Some(&quest_id));
+                    return Ok(QuestOutcome::Completed);
                 }
             }
             Err(e) => {
@@ -2142,16 +2300,18 @@ pub async fn complete_video_quest_via_cdp(
 
                                 // Only emit quest-complete if server confirmed completion
                                 if js_completed || store_completed {
-                                    let _ = app_handle.emit("quest-progress", 100.0f64);
-                                    let _ = app_handle.emit("quest-complete", ());
-                                } else {
-                                    log(LogLevel::Warn, LogCategory::TokenExtraction,
-                                        &format!("CDP video quest JS succeeded but server has not confirmed completion (completed={}, storeCompleted={}). Not emitting quest-complete.", js_completed, store_completed), None);
-                                    let progress_pct = store_progress.unwrap_or(0.0).min(99.0);
-                                    let _ = app_handle.emit("quest-progress", progress_pct);
-                                    let _ = app_handle.emit("quest-error", "Video quest finished but server has not confirmed completion. Please check quest status in Discord.".to_string());
+                                    crate::quest_completer::emit_cdp_progress(&app_handle, &quest_id, "WATCH_VIDEO", seconds_needed as f64, seconds_needed, 0);
+                                    crate::quest_completer::emit_event(&app_handle, "quest-complete", ());
+                                    crate::metrics::quest_completed();
+                                    crate::notifications::quest_completed(&app_handle, Some(&quest_id));
+                                    return Ok(QuestOutcome::Completed);
                                 }
-                                return Ok(());
+                                log(LogLevel::Warn, LogCategory::TokenExtraction,
+                                    &format!("CDP video quest JS succeeded but server has not confirmed completion (completed={}, storeCompleted={}). Not emitting quest-complete.", js_completed, store_completed), None);
+                                // Unconfirmed, so never shown as done
+                                let store_secs = store_progress.unwrap_or(0.0).min(seconds_needed as f64 * 0.99);
+                                crate::quest_completer::emit_cdp_progress(&app_handle, &quest_id, "WATCH_VIDEO", store_secs, seconds_needed, 0);
+                                anyhow::bail!("Video quest finished but server has not confirmed completion. Please check quest status in Discord.");
                             } else {
                                 let error = parsed.get("error")
                                     .and_then(|e| e.as_str())
@@ -2171,15 +2331,13 @@ pub async fn complete_video_quest_via_cdp(
                                     ).await;
                                 }
 
-                                let _ = app_handle.emit("quest-error", format!("Video quest failed: {}", error));
-                                return Ok(());
+                                anyhow::bail!("Video quest failed: {}", error);
                             }
                         } else {
                             // JS loop stopped but no result — check error
                             log(LogLevel::Warn, LogCategory::TokenExtraction,
                                 "CDP video quest JS stopped without result", None);
-                            let _ = app_handle.emit("quest-error", "Video quest JS stopped unexpectedly".to_string());
-                            return Ok(());
+                            anyhow::bail!("Video quest JS stopped unexpectedly");
                         }
                     }
                 }
@@ -2450,7 +2608,7 @@ pub async fn complete_activity_quest_via_cdp(
     checkpoint_times: Vec<u32>,
     app_handle: tauri::AppHandle,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     use crate::logger::{log, LogCategory, LogLevel};
 
     let total_checkpoints = checkpoint_times.len();
@@ -2509,7 +2667,14 @@ pub async fn complete_activity_quest_via_cdp(
         anyhow::bail!("Activity quest init failed: {}", error);
     }
 
-    let _ = app_handle.emit("quest-progress", 0.0f64);
+    crate::quest_completer::emit_cdp_progress(
+        &app_handle,
+        &quest_id,
+        "PLAY_ACTIVITY",
+        0.0,
+        total_seconds,
+        checkpoint_times[0] as u64,
+    );
 
     let mut elapsed_secs = 0u32;
     for (i, checkpoint_secs) in checkpoint_times.iter().enumerate() {
@@ -2530,15 +2695,23 @@ pub async fn complete_activity_quest_via_cdp(
             _ = sleep(Duration::from_secs(*checkpoint_secs as u64)) => {},
             _ = cancel_rx.recv() => {
                 log(LogLevel::Info, LogCategory::TokenExtraction, "CDP activity quest cancelled", None);
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                crate::quest_completer::emit_event(&app_handle, "quest-stopped", ());
+                return Ok(QuestOutcome::Cancelled);
             }
         }
 
         elapsed_secs += checkpoint_secs;
         let progress_pct =
             ((elapsed_secs as f64) / (total_seconds as f64) * 100.0).min(99.0);
-        let _ = app_handle.emit("quest-progress", progress_pct);
+        // Held below 100% until Discord confirms the completion
+        crate::quest_completer::emit_cdp_progress(
+            &app_handle,
+            &quest_id,
+            "PLAY_ACTIVITY",
+            (elapsed_secs as f64).min(total_seconds as f64 * 0.99),
+            total_seconds,
+            checkpoint_times.get(i + 1).copied().unwrap_or(0) as u64,
+        );
 
         if is_last {
             log(
@@ -2606,7 +2779,7 @@ pub async fn complete_activity_quest_via_cdp(
     );
 
     let verify_js = js_check_activity_quest_status();
-    match cdp_client::execute_js_on_target(&ws_url, &verify_js, true, 15).await {
+    let outcome = match cdp_client::execute_js_on_target(&ws_url, &verify_js, true, 15).await {
         Ok(result) => {
             let parsed: serde_json::Value = serde_json::from_str(&result).unwrap_or_default();
             let completed = parsed
@@ -2629,11 +2802,22 @@ pub async fn complete_activity_quest_via_cdp(
             );
 
             if completed {
-                let _ = app_handle.emit("quest-progress", 100.0f64);
-                let _ = app_handle.emit("quest-complete", ());
+                crate::quest_completer::emit_cdp_progress(
+                    &app_handle,
+                    &quest_id,
+                    "PLAY_ACTIVITY",
+                    total_seconds as f64,
+                    total_seconds,
+                    0,
+                );
+                crate::quest_completer::emit_event(&app_handle, "quest-complete", ());
+                crate::metrics::quest_completed();
+                crate::notifications::quest_completed(&app_handle, Some(&quest_id));
+                Ok(QuestOutcome::Completed)
             } else {
-                let _ = app_handle.emit("quest-error",
-                    "Activity quest completed but server has not confirmed. Please check quest status in Discord.".to_string());
+                Err(anyhow::anyhow!(
+                    "Activity quest completed but server has not confirmed. Please check quest status in Discord."
+                ))
             }
         }
         Err(e) => {
@@ -2643,12 +2827,9 @@ pub async fn complete_activity_quest_via_cdp(
                 &format!("CDP activity quest verification failed: {}", e),
                 None,
             );
-            let _ = app_handle.emit(
-                "quest-error",
-                format!("Activity quest verification failed: {}", e),
-            );
+            Err(anyhow::anyhow!("Activity quest verification failed: {}", e))
         }
-    }
+    };
 
     log(
         LogLevel::Info,
@@ -2656,7 +2837,7 @@ pub async fn complete_activity_quest_via_cdp(
         "CDP activity quest finished",
         None,
     );
-    Ok(())
+    outcome
 }
 
 #[cfg(test)]
@@ -2687,4 +2868,15 @@ mod tests {
         assert!(build_quest_route_warmup_plan("not-a-url").is_none());
         assert!(build_quest_route_warmup_plan("chrome://version").is_none());
     }
+
+    #[test]
+    fn test_js_api_post_embeds_json() {
+        let js = js_api_post(
+            "/quests/\"1/heartbeat",
+            &serde_json::json!({ "application_id": "42", "terminal": false }),
+        );
+
+        assert!(js.contains(r#"url: "/quests/\"1/heartbeat""#));
+        assert!(js.contains(r#"body: {"application_id":"42","terminal":false}"#));
+    }
 }
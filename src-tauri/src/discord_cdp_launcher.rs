@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, Instant};
 
-use crate::cdp_client::{self, DEFAULT_CDP_PORT};
+use crate::cdp_client;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -52,7 +52,7 @@ pub struct LaunchOptions {
 impl Default for LaunchOptions {
     fn default() -> Self {
         Self {
-            port: DEFAULT_CDP_PORT,
+            port: cdp_client::default_port(),
             allow_origins: true,
             channel: None,
             restart_existing: false,
@@ -61,6 +61,14 @@ impl Default for LaunchOptions {
     }
 }
 
+/// Which Discord client is running, if any
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RunningDiscord {
+    pub running: bool,
+    pub channel: Option<DiscordChannel>,
+    pub pid: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LaunchResult {
     pub launched_path: String,
@@ -87,7 +95,13 @@ pub fn find_discord_installs() -> Vec<DiscordInstall> {
     find_discord_installs_platform()
 }
 
+/// Prefers the binary of a running Discord, so pinned versions and custom install
+/// locations are relaunched as-is; the directory scan is only a fallback.
 pub fn select_preferred_install(channel: Option<DiscordChannel>) -> Result<DiscordInstall, String> {
+    if let Some(install) = find_running_install(channel) {
+        return Ok(install);
+    }
+
     let installs = find_discord_installs();
     if let Some(channel) = channel {
         installs
@@ -115,6 +129,79 @@ pub fn is_discord_running(channel: Option<DiscordChannel>) -> Result<bool, Strin
     is_discord_running_platform(channel)
 }
 
+/// Find a running Discord client, checking Stable, then PTB, then Canary
+pub fn detect_running_discord() -> Result<RunningDiscord, String> {
+    for channel in [
+        DiscordChannel::Stable,
+        DiscordChannel::Ptb,
+        DiscordChannel::Canary,
+    ] {
+        if let Some(pid) = find_process_pid(&platform_process_name(channel))? {
+            return Ok(RunningDiscord {
+                running: true,
+                channel: Some(channel),
+                pid: Some(pid),
+            });
+        }
+    }
+
+    Ok(RunningDiscord {
+        running: false,
+        channel: None,
+        pid: None,
+    })
+}
+
+/// Install backing a running Discord process, read from the process table
+fn find_running_install(channel: Option<DiscordChannel>) -> Option<DiscordInstall> {
+    let channels = match channel {
+        Some(channel) => vec![channel],
+        None => vec![
+            DiscordChannel::Stable,
+            DiscordChannel::Ptb,
+            DiscordChannel::Canary,
+        ],
+    };
+
+    // One process-table query covers every channel
+    let process_names: Vec<String> = channels
+        .iter()
+        .copied()
+        .map(platform_process_name)
+        .collect();
+    let running = running_executable_paths(&process_names);
+
+    channels
+        .into_iter()
+        .zip(process_names)
+        .find_map(|(channel, process_name)| {
+            let executable_path = running
+                .iter()
+                .find(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(&process_name))
+                })?
+                .clone();
+            let working_dir = executable_path.parent()?.to_path_buf();
+            Some(DiscordInstall {
+                channel,
+                executable_path,
+                working_dir,
+                process_names: vec![process_name],
+            })
+        })
+}
+
+/// Paths listed one per line by a process-table query that name files that exist
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+fn existing_paths(stdout: &str) -> Vec<PathBuf> {
+    stdout
+        .lines()
+        .map(|line| PathBuf::from(line.trim()))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
 pub fn terminate_discord_processes(channel: Option<DiscordChannel>) -> Result<(), String> {
     terminate_discord_processes_platform(channel)
 }
@@ -126,6 +213,9 @@ pub async fn launch_discord_with_cdp(options: LaunchOptions) -> Result<LaunchRes
         return Ok(launch_result(&install, options.port, true));
     }
 
+    // Resolve the install first: once Discord is closed its path can't be read anymore
+    let install = select_preferred_install(options.channel)?;
+
     if options.restart_existing {
         terminate_discord_processes(options.channel)?;
         wait_until_discord_exits(options.channel, Duration::from_secs(8)).await?;
@@ -138,7 +228,6 @@ pub async fn launch_discord_with_cdp(options: LaunchOptions) -> Result<LaunchRes
 
     ensure_port_available_for_cdp(options.port, &status).await?;
 
-    let install = select_preferred_install(options.channel)?;
     let mut command = Command::new(&install.executable_path);
     command
         .current_dir(&install.working_dir)
@@ -277,8 +366,13 @@ fn platform_process_name(channel: DiscordChannel) -> String {
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-fn platform_process_name(_channel: DiscordChannel) -> String {
-    String::new()
+fn platform_process_name(channel: DiscordChannel) -> String {
+    match channel {
+        DiscordChannel::Stable => "Discord",
+        DiscordChannel::Ptb => "DiscordPTB",
+        DiscordChannel::Canary => "DiscordCanary",
+    }
+    .to_string()
 }
 
 #[cfg(target_os = "windows")]
@@ -399,6 +493,51 @@ fn is_discord_running_platform(channel: Option<DiscordChannel>) -> Result<bool,
     }))
 }
 
+/// PID of the first process named `name` in `tasklist /FO CSV /NH` output
+#[cfg(target_os = "windows")]
+fn parse_tasklist_pid(stdout: &str, name: &str) -> Option<u32> {
+    stdout.lines().find_map(|line| {
+        let mut fields = line.split(',').map(|f| f.trim().trim_matches('"'));
+        let image = fields.next()?;
+        let pid = fields.next()?;
+        image.eq_ignore_ascii_case(name).then(|| pid.parse().ok())?
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn find_process_pid(name: &str) -> Result<Option<u32>, String> {
+    let filter = format!("IMAGENAME eq {}", name);
+    let output = no_window_cmd("tasklist")
+        .args(["/FI", &filter, "/FO", "CSV", "/NH"])
+        .output()
+        .map_err(|e| format!("Could not execute tasklist: {}", e))?;
+
+    Ok(parse_tasklist_pid(
+        &String::from_utf8_lossy(&output.stdout),
+        name,
+    ))
+}
+
+/// Executables of the running processes named `process_names`, in one PowerShell call
+#[cfg(target_os = "windows")]
+fn running_executable_paths(process_names: &[String]) -> Vec<PathBuf> {
+    let names: Vec<String> = process_names
+        .iter()
+        .map(|name| format!("'{}'", name.trim_end_matches(".exe")))
+        .collect();
+    let script = format!(
+        "(Get-Process -Name {} -ErrorAction SilentlyContinue).Path",
+        names.join(",")
+    );
+    match no_window_cmd("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+    {
+        Ok(output) => existing_paths(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn terminate_discord_processes_platform(channel: Option<DiscordChannel>) -> Result<(), String> {
     for name in process_names_for(channel) {
@@ -485,6 +624,16 @@ fn is_discord_running_platform(channel: Option<DiscordChannel>) -> Result<bool,
     Ok(false)
 }
 
+/// Executables of all running processes (`comm` is the full path on macOS); the caller
+/// matches them against the process names
+#[cfg(target_os = "macos")]
+fn running_executable_paths(_process_names: &[String]) -> Vec<PathBuf> {
+    match Command::new("ps").args(["-axo", "comm="]).output() {
+        Ok(output) => existing_paths(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn terminate_discord_processes_platform(channel: Option<DiscordChannel>) -> Result<(), String> {
     for name in process_names_for(channel) {
@@ -509,6 +658,19 @@ fn terminate_discord_processes_platform(channel: Option<DiscordChannel>) -> Resu
     Ok(())
 }
 
+/// `pgrep` exits with 1 when nothing matches, so only a failure to run it is an error
+#[cfg(not(target_os = "windows"))]
+fn find_process_pid(name: &str) -> Result<Option<u32>, String> {
+    let output = Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .map_err(|e| format!("Could not execute pgrep for {}: {}", name, e))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok()))
+}
+
 #[cfg(target_os = "macos")]
 fn channel_from_process_name(name: &str) -> DiscordChannel {
     match name {
@@ -523,6 +685,11 @@ fn find_discord_installs_platform() -> Vec<DiscordInstall> {
     Vec::new()
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn running_executable_paths(_process_names: &[String]) -> Vec<PathBuf> {
+    Vec::new()
+}
+
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn is_discord_running_platform(_channel: Option<DiscordChannel>) -> Result<bool, String> {
     Err("Discord CDP launcher is only supported on Windows and macOS.".to_string())
@@ -551,6 +718,19 @@ mod tests {
         assert!(parse_discord_channel(Some("nightly")).is_err());
     }
 
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn parses_tasklist_pids() {
+        let stdout = "\"Discord.exe\",\"4242\",\"Console\",\"1\",\"120,312 K\"\r\n\
+                      \"Discord.exe\",\"4300\",\"Console\",\"1\",\"80,000 K\"\r\n";
+        assert_eq!(parse_tasklist_pid(stdout, "discord.exe"), Some(4242));
+        assert_eq!(parse_tasklist_pid(stdout, "DiscordCanary.exe"), None);
+        assert_eq!(
+            parse_tasklist_pid("INFO: No tasks are running", "Discord.exe"),
+            None
+        );
+    }
+
     #[cfg(target_os = "windows")]
     #[test]
     fn parses_app_versions_numerically() {
@@ -0,0 +1,204 @@
+//! Discord Endpoint Module
+//!
+//! Single source of truth for Discord URLs used by the app.
+//! Bumping the API version or switching update channels only needs a change here.
+//! The REST API origin and version can also be overridden at startup (`DQH_API_BASE`,
+//! `DQH_API_VERSION`, see `env_config`) without a rebuild.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::RwLock;
+
+/// Discord web origin
+pub const DISCORD_ORIGIN: &str = "https://discord.com";
+
+/// Discord Canary web origin
+pub const CANARY_ORIGIN: &str = "https://canary.discord.com";
+
+/// Discord update server origin
+pub const UPDATES_ORIGIN: &str = "https://updates.discord.com";
+
+/// Discord media CDN origin
+pub const CDN_ORIGIN: &str = "https://cdn.discordapp.com";
+
+/// Default REST API version used for all requests
+pub const API_VERSION: u32 = 9;
+
+/// Startup override of `API_VERSION`; 0 means none
+static API_VERSION_OVERRIDE: AtomicU32 = AtomicU32::new(0);
+
+/// Startup override of the origin REST requests go to (`DISCORD_ORIGIN` by default)
+static API_ORIGIN_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Send REST requests to API version `version` instead of `API_VERSION`
+pub fn set_api_version(version: u32) {
+    API_VERSION_OVERRIDE.store(version, Ordering::Relaxed);
+}
+
+/// Send REST requests to `origin` (e.g. "https://discord.com") instead of `DISCORD_ORIGIN`
+pub fn set_api_origin(origin: &str) {
+    *API_ORIGIN_OVERRIDE
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        Some(origin.trim_end_matches('/').to_string());
+}
+
+/// REST API version in use
+pub fn api_version() -> u32 {
+    match API_VERSION_OVERRIDE.load(Ordering::Relaxed) {
+        0 => API_VERSION,
+        version => version,
+    }
+}
+
+/// Origin REST requests go to
+pub fn api_origin() -> String {
+    API_ORIGIN_OVERRIDE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_else(|| DISCORD_ORIGIN.to_string())
+}
+
+/// API path prefix (e.g. "/api/v9")
+pub fn api_path_prefix() -> String {
+    format!("/api/v{}", api_version())
+}
+
+/// Full API base URL (e.g. "https://discord.com/api/v9")
+pub fn api_base() -> String {
+    format!("{}{}", api_origin(), api_path_prefix())
+}
+
+fn api(path: &str) -> String {
+    format!("{}{}", api_base(), path)
+}
+
+// ============================================================================
+// REST API
+// ============================================================================
+
+pub fn users_me() -> String {
+    api("/users/@me")
+}
+
+pub fn virtual_currency_balance() -> String {
+    api("/users/@me/virtual-currency/balance")
+}
+
+pub fn quests_me() -> String {
+    api("/quests/@me")
+}
+
+pub fn quest_decision() -> String {
+    api("/quests/decision")
+}
+
+pub fn quest_decisions() -> String {
+    api("/quests/get-decisions")
+}
+
+pub fn enroll(quest_id: &str) -> String {
+    api(&format!("/quests/{}/enroll", quest_id))
+}
+
+pub fn quest_heartbeat(quest_id: &str) -> String {
+    api(&format!("/quests/{}/heartbeat", quest_id))
+}
+
+pub fn video_progress(quest_id: &str) -> String {
+    api(&format!("/quests/{}/video-progress", quest_id))
+}
+
+pub fn claim_reward(quest_id: &str) -> String {
+    api(&format!("/quests/{}/claim-reward", quest_id))
+}
+
+pub fn detectable_games() -> String {
+    api("/applications/detectable")
+}
+
+pub fn detectable_non_games() -> String {
+    api("/applications/non-games/detectable")
+}
+
+/// Detectable games as served to Canary, which sometimes lists new releases earlier
+pub fn canary_detectable_games() -> String {
+    format!(
+        "{}{}/applications/detectable",
+        CANARY_ORIGIN,
+        api_path_prefix()
+    )
+}
+
+pub fn application_rpc(application_id: &str) -> String {
+    api(&format!("/applications/{}/rpc", application_id))
+}
+
+// ============================================================================
+// Web client
+// ============================================================================
+
+/// Page used as referer for quest requests and as CDP navigation target
+pub fn quest_home() -> String {
+    format!("{}/quest-home", DISCORD_ORIGIN)
+}
+
+pub fn login_page() -> String {
+    format!("{}/login", DISCORD_ORIGIN)
+}
+
+/// Absolute URL for a web client asset path (e.g. "/assets/web.abc123.js")
+pub fn web_asset(path: &str) -> String {
+    format!("{}{}", DISCORD_ORIGIN, path)
+}
+
+// ============================================================================
+// CDN
+// ============================================================================
+
+/// 128px PNG of an application's icon
+pub fn app_icon(application_id: &str, icon_hash: &str) -> String {
+    format!(
+        "{}/app-icons/{}/{}.png?size=128",
+        CDN_ORIGIN, application_id, icon_hash
+    )
+}
+
+// ============================================================================
+// Update server
+// ============================================================================
+
+/// Latest desktop manifest for a release channel ("stable", "ptb", "canary")
+pub fn update_manifest(channel: &str, platform: &str, arch: &str) -> String {
+    format!(
+        "{}/distributions/app/manifests/latest?channel={}&platform={}&arch={}",
+        UPDATES_ORIGIN, channel, platform, arch
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_urls() {
+        assert_eq!(api_base(), "https://discord.com/api/v9");
+        assert_eq!(quests_me(), "https://discord.com/api/v9/quests/@me");
+        assert_eq!(
+            quest_heartbeat("123"),
+            "https://discord.com/api/v9/quests/123/heartbeat"
+        );
+        assert_eq!(
+            canary_detectable_games(),
+            "https://canary.discord.com/api/v9/applications/detectable"
+        );
+        assert_eq!(
+            update_manifest("stable", "win", "x64"),
+            "https://updates.discord.com/distributions/app/manifests/latest?channel=stable&platform=win&arch=x64"
+        );
+        assert_eq!(
+            app_icon("42", "abc"),
+            "https://cdn.discordapp.com/app-icons/42/abc.png?size=128"
+        );
+    }
+}
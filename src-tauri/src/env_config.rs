@@ -0,0 +1,205 @@
+//! Environment Configuration
+//!
+//! Startup settings for server and container deployments where the GUI isn't used:
+//!
+//! - `DQH_PROXY`: proxy for Discord API, gateway and website traffic
+//!   (`http://`, `https://` or `socks5://`)
+//! - `DQH_CDP_PORT`: Discord remote debugging port used when a command doesn't pass one
+//! - `DQH_LOG_LEVEL`: lowest stored log level (`debug`, `info`, `warn`, `error`)
+//! - `DQH_OFFLINE`: `1`/`true` to skip all remote build number and client info fetches
+//! - `DQH_API_BASE`: origin for Discord REST requests (default `https://discord.com`)
+//! - `DQH_API_VERSION`: Discord REST API version (default 9)
+//!
+//! Precedence is env < GUI: these only seed the initial values, and anything set from
+//! the UI at runtime (`set_proxy`, `set_min_log_level`, `set_offline_mode`, an explicit
+//! `cdp_port`) replaces them. Invalid values are logged and ignored.
+
+use crate::logger::LogLevel;
+
+const PROXY_ENV: &str = "DQH_PROXY";
+const CDP_PORT_ENV: &str = "DQH_CDP_PORT";
+const LOG_LEVEL_ENV: &str = "DQH_LOG_LEVEL";
+const OFFLINE_ENV: &str = "DQH_OFFLINE";
+const API_BASE_ENV: &str = "DQH_API_BASE";
+const API_VERSION_ENV: &str = "DQH_API_VERSION";
+
+/// Settings read from the environment at startup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvConfig {
+    pub proxy_url: Option<String>,
+    pub cdp_port: Option<u16>,
+    pub log_level: Option<LogLevel>,
+    pub offline: bool,
+    pub api_base: Option<String>,
+    pub api_version: Option<u32>,
+    /// `NAME=value: reason` for each variable that was set but unusable
+    pub invalid: Vec<String>,
+}
+
+impl EnvConfig {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut config = Self::default();
+        let var = |name: &str| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        if let Some(url) = var(PROXY_ENV) {
+            match crate::discord_api::parse_proxy_url(&url) {
+                Ok(_) => config.proxy_url = Some(url),
+                Err(e) => config.invalid.push(format!("{}: {:#}", PROXY_ENV, e)),
+            }
+        }
+
+        if let Some(port) = var(CDP_PORT_ENV) {
+            match port.parse::<u16>() {
+                Ok(port) if port != 0 => config.cdp_port = Some(port),
+                _ => config
+                    .invalid
+                    .push(format!("{}={}: not a valid port", CDP_PORT_ENV, port)),
+            }
+        }
+
+        if let Some(level) = var(LOG_LEVEL_ENV) {
+            match parse_log_level(&level) {
+                Some(level) => config.log_level = Some(level),
+                None => config.invalid.push(format!(
+                    "{}={}: expected debug, info, warn or error",
+                    LOG_LEVEL_ENV, level
+                )),
+            }
+        }
+
+        if let Some(offline) = var(OFFLINE_ENV) {
+            match offline.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => config.offline = true,
+                "0" | "false" | "no" | "off" => {}
+                _ => config.invalid.push(format!(
+                    "{}={}: expected true or false",
+                    OFFLINE_ENV, offline
+                )),
+            }
+        }
+
+        if let Some(base) = var(API_BASE_ENV) {
+            match url::Url::parse(&base) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                    config.api_base = Some(base.trim_end_matches('/').to_string())
+                }
+                _ => config.invalid.push(format!(
+                    "{}={}: expected an http(s) URL",
+                    API_BASE_ENV, base
+                )),
+            }
+        }
+
+        if let Some(version) = var(API_VERSION_ENV) {
+            match version.parse::<u32>() {
+                Ok(version) if version != 0 => config.api_version = Some(version),
+                _ => config.invalid.push(format!(
+                    "{}={}: not a valid API version",
+                    API_VERSION_ENV, version
+                )),
+            }
+        }
+
+        config
+    }
+
+    /// Apply the process-wide settings (log level, default CDP port, offline mode, API base)
+    ///
+    /// The API client's proxy lives in `AppState` and is seeded by the caller; only the
+    /// website fetches in `token_extractor` pick it up here.
+    pub fn apply_globals(&self) {
+        use crate::logger::{log, LogCategory};
+
+        if let Some(level) = self.log_level {
+            crate::logger::set_min_log_level(level);
+        }
+        if let Some(port) = self.cdp_port {
+            crate::cdp_client::set_default_port(port);
+        }
+        if self.offline {
+            crate::token_extractor::set_offline(true);
+        }
+        if self.proxy_url.is_some() {
+            crate::token_extractor::set_proxy(self.proxy_url.clone());
+        }
+        if let Some(base) = &self.api_base {
+            crate::endpoints::set_api_origin(base);
+        }
+        if let Some(version) = self.api_version {
+            crate::endpoints::set_api_version(version);
+        }
+        for problem in &self.invalid {
+            log(
+                LogLevel::Warn,
+                LogCategory::General,
+                "Ignoring invalid environment setting",
+                Some(problem),
+            );
+        }
+    }
+}
+
+fn parse_log_level(value: &str) -> Option<LogLevel> {
+    match value.to_ascii_lowercase().as_str() {
+        "debug" => Some(LogLevel::Debug),
+        "info" => Some(LogLevel::Info),
+        "warn" | "warning" => Some(LogLevel::Warn),
+        "error" => Some(LogLevel::Error),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_config_from_lookup() {
+        let config = EnvConfig::from_lookup(|name| match name {
+            PROXY_ENV => Some(" socks5://127.0.0.1:1080 ".to_string()),
+            CDP_PORT_ENV => Some("9333".to_string()),
+            LOG_LEVEL_ENV => Some("WARN".to_string()),
+            OFFLINE_ENV => Some("True".to_string()),
+            API_BASE_ENV => Some("https://discord.example/".to_string()),
+            API_VERSION_ENV => Some("10".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            config,
+            EnvConfig {
+                proxy_url: Some("socks5://127.0.0.1:1080".to_string()),
+                cdp_port: Some(9333),
+                log_level: Some(LogLevel::Warn),
+                offline: true,
+                api_base: Some("https://discord.example".to_string()),
+                api_version: Some(10),
+                invalid: Vec::new(),
+            }
+        );
+
+        assert_eq!(EnvConfig::from_lookup(|_| None), EnvConfig::default());
+
+        let config = EnvConfig::from_lookup(|name| match name {
+            CDP_PORT_ENV => Some("0".to_string()),
+            LOG_LEVEL_ENV => Some("verbose".to_string()),
+            OFFLINE_ENV => Some("sometimes".to_string()),
+            API_BASE_ENV => Some("discord.com".to_string()),
+            API_VERSION_ENV => Some("v9".to_string()),
+            _ => Some(String::new()),
+        });
+        assert_eq!(config.cdp_port, None);
+        assert_eq!(config.log_level, None);
+        assert!(!config.offline);
+        assert_eq!(config.api_base, None);
+        assert_eq!(config.api_version, None);
+        assert_eq!(config.invalid.len(), 5);
+    }
+}
@@ -0,0 +1,368 @@
+//! Typed Errors
+//!
+//! `ApiError` is raised by `DiscordApiClient` for non-success responses and travels
+//! inside `anyhow::Error`. `AppError` is what API-facing Tauri commands return: it
+//! classifies an error so the frontend can branch on its `kind` and show a specific fix.
+
+use crate::token_extractor::CredentialStoreError;
+use serde::Serialize;
+use std::fmt;
+
+/// A non-success response from the Discord API
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    /// What was being attempted, e.g. "Failed to get quest list"
+    pub action: String,
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl ApiError {
+    pub fn new(action: &str, status: reqwest::StatusCode, body: String) -> Self {
+        Self {
+            action: action.to_string(),
+            status,
+            body,
+        }
+    }
+
+    /// Seconds to wait before retrying, from the body of a 429 response
+    pub fn retry_after(&self) -> Option<f64> {
+        serde_json::from_str::<serde_json::Value>(&self.body)
+            .ok()?
+            .get("retry_after")?
+            .as_f64()
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} - {}", self.action, self.status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Error returned to the frontend, serialized as `{ "kind": ..., "message": ... }`
+/// plus the variant's fields
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotLoggedIn,
+    /// Discord rejected the token (401)
+    TokenInvalid,
+    /// The input isn't shaped like a user token; carries the reason
+    MalformedToken(String),
+    RateLimited {
+        retry_after: Option<f64>,
+    },
+    /// The request never got a response (DNS, TLS, proxy, timeout, ...)
+    Network(String),
+    /// Any other non-success response
+    DiscordApi {
+        status: u16,
+        body: String,
+        message: String,
+    },
+    /// Discord isn't running with the remote debugging port open
+    CdpUnavailable(String),
+    /// The detectable game has no executable for `os`; only `available_os` are defined
+    NoExecutableForOs {
+        game: String,
+        os: String,
+        available_os: Vec<String>,
+    },
+    /// The OS credential store refused to unlock Discord's saved token
+    CredentialStore {
+        platform: String,
+        reason: String,
+        remediation: String,
+    },
+    Other(String),
+}
+
+impl AppError {
+    /// Stable identifier the frontend branches on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::NotLoggedIn => "not_logged_in",
+            AppError::TokenInvalid => "token_invalid",
+            AppError::MalformedToken(_) => "malformed_token",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::Network(_) => "network",
+            AppError::DiscordApi { .. } => "discord_api",
+            AppError::CdpUnavailable(_) => "cdp_unavailable",
+            AppError::NoExecutableForOs { .. } => "no_executable_for_os",
+            AppError::CredentialStore { .. } => "credential_store",
+            AppError::Other(_) => "other",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotLoggedIn => write!(f, "Not logged in"),
+            AppError::TokenInvalid => {
+                write!(f, "Discord rejected the token, please log in again")
+            }
+            AppError::MalformedToken(reason) => {
+                write!(f, "This doesn't look like a Discord token ({})", reason)
+            }
+            AppError::RateLimited {
+                retry_after: Some(secs),
+            } => write!(f, "Rate limited by Discord, retry in {:.0}s", secs.ceil()),
+            AppError::RateLimited { retry_after: None } => write!(f, "Rate limited by Discord"),
+            AppError::NoExecutableForOs {
+                game, available_os, ..
+            } if !available_os.is_empty() => {
+                let platforms: Vec<&str> = available_os.iter().map(|os| os_label(os)).collect();
+                write!(
+                    f,
+                    "{} only supports {}; try the direct-heartbeat method instead",
+                    game,
+                    platforms.join(", ")
+                )
+            }
+            AppError::NoExecutableForOs { game, os, .. } => write!(
+                f,
+                "{} has no {} executable; try the direct-heartbeat method instead",
+                game,
+                os_label(os)
+            ),
+            AppError::CredentialStore {
+                reason,
+                remediation,
+                ..
+            } => write!(f, "{}. {}", reason, remediation),
+            AppError::Network(message)
+            | AppError::DiscordApi { message, .. }
+            | AppError::CdpUnavailable(message)
+            | AppError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// Display name for an `executables[].os` value
+fn os_label(os: &str) -> &str {
+    match os {
+        "win32" => "Windows",
+        "darwin" => "macOS",
+        "linux" => "Linux",
+        other => other,
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Payload<'a> {
+            kind: &'static str,
+            message: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            retry_after: Option<f64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            status: Option<u16>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            available_os: Option<&'a [String]>,
+            /// Command that can do the job instead
+            #[serde(skip_serializing_if = "Option::is_none")]
+            fallback_command: Option<&'static str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            platform: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            remediation: Option<&'a str>,
+        }
+
+        let mut payload = Payload {
+            kind: self.kind(),
+            message: self.to_string(),
+            retry_after: None,
+            status: None,
+            body: None,
+            available_os: None,
+            fallback_command: None,
+            platform: None,
+            remediation: None,
+        };
+        match self {
+            AppError::RateLimited { retry_after } => {
+                payload.retry_after = *retry_after;
+                payload.status = Some(429);
+            }
+            AppError::DiscordApi { status, body, .. } => {
+                payload.status = Some(*status);
+                payload.body = Some(body);
+            }
+            AppError::NoExecutableForOs { available_os, .. } => {
+                payload.available_os = Some(available_os);
+                // Heartbeats only need the application id, not a running executable
+                payload.fallback_command = Some("start_game_heartbeat_quest");
+            }
+            AppError::CredentialStore {
+                platform,
+                remediation,
+                ..
+            } => {
+                payload.platform = Some(platform);
+                payload.remediation = Some(remediation);
+            }
+            _ => {}
+        }
+        payload.serialize(serializer)
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        if let Some(app) = err.downcast_ref::<AppError>() {
+            return app.clone();
+        }
+
+        if let Some(store) = err
+            .chain()
+            .find_map(|e| e.downcast_ref::<CredentialStoreError>())
+        {
+            return store.clone().into();
+        }
+
+        let message = format!("{:#}", err);
+        if let Some(api) = err.chain().find_map(|e| e.downcast_ref::<ApiError>()) {
+            return match api.status.as_u16() {
+                401 => AppError::TokenInvalid,
+                429 => AppError::RateLimited {
+                    retry_after: api.retry_after(),
+                },
+                status => AppError::DiscordApi {
+                    status,
+                    body: api.body.clone(),
+                    message,
+                },
+            };
+        }
+        if err
+            .chain()
+            .any(|e| e.downcast_ref::<reqwest::Error>().is_some())
+        {
+            return AppError::Network(message);
+        }
+        AppError::Other(message)
+    }
+}
+
+impl From<CredentialStoreError> for AppError {
+    fn from(err: CredentialStoreError) -> Self {
+        AppError::CredentialStore {
+            platform: err.platform.to_string(),
+            reason: err.reason,
+            remediation: err.remediation,
+        }
+    }
+}
+
+/// Commands that still return `Result<_, String>` can use `?` on an `AppError`
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_anyhow_errors() {
+        let api = |status: u16, body: &str| -> anyhow::Error {
+            let status = reqwest::StatusCode::from_u16(status).unwrap();
+            anyhow::Error::new(ApiError::new(
+                "Failed to get quest list",
+                status,
+                body.into(),
+            ))
+        };
+
+        assert_eq!(AppError::from(api(401, "")), AppError::TokenInvalid);
+        assert_eq!(
+            AppError::from(api(429, r#"{"message":"slow down","retry_after":2.5}"#)),
+            AppError::RateLimited {
+                retry_after: Some(2.5)
+            }
+        );
+
+        let err = AppError::from(api(500, "oops").context("Failed to get quests"));
+        assert_eq!(
+            err,
+            AppError::DiscordApi {
+                status: 500,
+                body: "oops".to_string(),
+                message: "Failed to get quests: Failed to get quest list: \
+                          500 Internal Server Error - oops"
+                    .to_string(),
+            }
+        );
+
+        assert_eq!(
+            AppError::from(anyhow::anyhow!("bad input")),
+            AppError::Other("bad input".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serialize_tagged() {
+        let json = serde_json::to_value(AppError::RateLimited {
+            retry_after: Some(3.0),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "rate_limited",
+                "message": "Rate limited by Discord, retry in 3s",
+                "retry_after": 3.0,
+                "status": 429
+            })
+        );
+
+        let json = serde_json::to_value(AppError::NotLoggedIn).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "not_logged_in", "message": "Not logged in" })
+        );
+
+        let json = serde_json::to_value(AppError::NoExecutableForOs {
+            game: "Some Game".to_string(),
+            os: "darwin".to_string(),
+            available_os: vec!["win32".to_string()],
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "no_executable_for_os",
+                "message": "Some Game only supports Windows; try the direct-heartbeat method instead",
+                "available_os": ["win32"],
+                "fallback_command": "start_game_heartbeat_quest"
+            })
+        );
+
+        let store = CredentialStoreError {
+            platform: "windows",
+            reason: "DPAPI could not decrypt the key".to_string(),
+            remediation: "Log in to Discord again".to_string(),
+        };
+        let err = AppError::from(anyhow::Error::new(store).context("Token extraction failed"));
+        assert_eq!(
+            serde_json::to_value(err).unwrap(),
+            serde_json::json!({
+                "kind": "credential_store",
+                "message": "DPAPI could not decrypt the key. Log in to Discord again",
+                "platform": "windows",
+                "remediation": "Log in to Discord again"
+            })
+        );
+    }
+}
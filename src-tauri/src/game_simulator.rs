@@ -1,17 +1,36 @@
 use anyhow::{Context, Result};
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use once_cell::sync::Lazy;
 
-/// Global set that tracks image names of running simulated game processes.
+/// Running simulated game processes, keyed by application ID (or image name when
+/// no ID is given). Several games can run at once.
 /// Entries are added in `run_simulated_game` and removed in `stop_simulated_game`.
-/// Used by `cleanup_all_simulated_games` to kill orphaned children on app exit.
-static RUNNING_GAMES: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+/// Used by `stop_all_simulated_games` to kill orphaned children on app exit.
+static RUNNING_GAMES: Lazy<Mutex<HashMap<String, RunningGame>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A runner process started by `run_simulated_game`
+///
+/// Keeping the `Child` lets stop kill exactly that process, never an unrelated program
+/// that happens to share the executable name.
+struct RunningGame {
+    path: PathBuf,
+    child: Child,
+}
+
+impl AsRef<Path> for RunningGame {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
 
 // Embed the runner binary at compile time from the data/ directory.
 // build.rs ensures an empty placeholder exists if the runner hasn't been built yet,
@@ -19,12 +38,104 @@ static RUNNING_GAMES: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(Has
 #[cfg(target_os = "windows")]
 const RUNNER_BYTES: &[u8] = include_bytes!("../data/discord-quest-runner.exe");
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 const RUNNER_BYTES: &[u8] = include_bytes!("../data/discord-quest-runner");
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 const RUNNER_BYTES: &[u8] = &[];
 
+/// Env var read by the runner for the text drawn in its window
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const RUNNER_TEXT_ENV: &str = "DQH_RUNNER_TEXT";
+
+/// Runner flag: exit on its own after this many seconds, so a runner outlives its quest
+/// by at most a few minutes even if the app crashes before stopping it
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const RUNNER_TTL_ARG: &str = "--ttl-seconds";
+
+/// Lifetime on top of the quest's remaining time, for detection delay and slow progress
+const RUNNER_TTL_MARGIN_SECS: u64 = 15 * 60;
+
+/// How long a runner for a quest with `seconds_remaining` left may live
+pub fn runner_ttl_secs(seconds_remaining: u64) -> u64 {
+    seconds_remaining.saturating_add(RUNNER_TTL_MARGIN_SECS)
+}
+
+/// Env var that turns on the runner's stdio channel: it prints `ready` once its window
+/// exists, and exits on a `quit` line or when its stdin closes (the app is gone)
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const RUNNER_IPC_ENV: &str = "DQH_RUNNER_IPC";
+
+/// How long to wait for the runner's `ready` line; runners built before the channel
+/// existed never send one and are just assumed to be up after this
+const RUNNER_READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a runner gets to exit after `quit` before it's killed
+const RUNNER_QUIT_GRACE: Duration = Duration::from_secs(1);
+
+/// What the runner reported after being started
+#[derive(Debug, PartialEq, Eq)]
+enum RunnerStartup {
+    Ready,
+    /// Output ended without `ready`: the process exited (or closed stdout)
+    Exited,
+    /// No `ready` line in time; older runners don't send one
+    Silent,
+}
+
+/// Read the runner's stdout until it reports `ready`, on a helper thread so a silent
+/// runner can't block past `timeout`
+fn wait_for_ready(stdout: impl std::io::Read + Send + 'static, timeout: Duration) -> RunnerStartup {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let ready = BufReader::new(stdout)
+            .lines()
+            .map_while(|line| line.ok())
+            .any(|line| line.trim() == "ready");
+        let _ = tx.send(ready);
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(true) => RunnerStartup::Ready,
+        Ok(false) => RunnerStartup::Exited,
+        Err(_) => RunnerStartup::Silent,
+    }
+}
+
+/// Wait for a freshly spawned runner to come up
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn await_runner_ready(child: &mut Child, exe_path: &Path) -> Result<()> {
+    let Some(stdout) = child.stdout.take() else {
+        return Ok(());
+    };
+    match wait_for_ready(stdout, RUNNER_READY_TIMEOUT) {
+        RunnerStartup::Ready => Ok(()),
+        RunnerStartup::Silent => {
+            println!(
+                "Simulated game {:?} didn't report ready, assuming it's running",
+                exe_path
+            );
+            Ok(())
+        }
+        RunnerStartup::Exited => {
+            let status = child.wait().ok();
+            anyhow::bail!(
+                "Simulated game exited right after starting{}",
+                status
+                    .map(|status| format!(" ({})", status))
+                    .unwrap_or_default()
+            )
+        }
+    }
+}
+
+/// Command-line arguments passing `ttl_secs` to the runner
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn runner_args(ttl_secs: Option<u64>) -> Vec<String> {
+    ttl_secs
+        .map(|secs| vec![RUNNER_TTL_ARG.to_string(), secs.to_string()])
+        .unwrap_or_default()
+}
+
 /// Embedded runner version info (commit hash + build timestamp).
 /// Written by build-runner.js, placeholder created by build.rs if not built yet.
 const RUNNER_VERSION_INFO: &str = include_str!("../data/runner-version.txt");
@@ -60,7 +171,11 @@ pub fn get_runner_info() -> RunnerInfo {
 /// Write the embedded runner binary to the target path
 fn ensure_runner_bytes(target_path: &Path) -> Result<()> {
     if RUNNER_BYTES.is_empty() {
-        if cfg!(any(target_os = "windows", target_os = "macos")) {
+        if cfg!(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux"
+        )) {
             anyhow::bail!("Runner binary not embedded (run `npm run build:runner`)");
         } else {
             anyhow::bail!("Runner binary not available for this platform");
@@ -147,12 +262,16 @@ pub fn create_simulated_game(path: &str, executable_name: &str, _app_id: &str) -
 }
 
 /// Run the simulated game
+///
+/// With `ttl_secs` the runner exits by itself after that long (see `runner_ttl_secs`);
+/// without it, it runs until stopped.
 #[cfg(target_os = "windows")]
 pub fn run_simulated_game(
     name: &str,
     path: &str,
     executable_name: &str,
-    _app_id: &str,
+    app_id: &str,
+    ttl_secs: Option<u64>,
 ) -> Result<()> {
     let exe_to_run = PathBuf::from(path).join(executable_name);
 
@@ -170,13 +289,25 @@ pub fn run_simulated_game(
         anyhow::bail!("Executable does not exist: {:?}", exe_to_run);
     }
 
-    let _ = Command::new("cmd")
-        .args(["/C", "start", "", exe_to_run.to_str().unwrap()])
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+    const DETACHED_PROCESS: u32 = 0x00000008;
+
+    // Spawned directly (not through `cmd /C start`) so the handle is the runner itself
+    let mut child = Command::new(&exe_to_run)
+        .env(RUNNER_TEXT_ENV, name)
+        .env(RUNNER_IPC_ENV, "1")
+        .args(runner_args(ttl_secs))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS)
         .spawn()
         .context("Could not start simulated game")?;
+    await_runner_ready(&mut child, &exe_to_run)?;
 
-    // Track the running process so we can clean it up on app exit
-    track_running_game(executable_name);
+    // Track the running process so we can stop it and clean it up on app exit
+    track_running_game(app_id, &exe_to_run, child);
 
     println!("Simulated game {} started from {:?}", name, exe_to_run);
     Ok(())
@@ -187,7 +318,8 @@ pub fn run_simulated_game(
     name: &str,
     path: &str,
     executable_name: &str,
-    _app_id: &str,
+    app_id: &str,
+    ttl_secs: Option<u64>,
 ) -> Result<()> {
     let exe_to_run = PathBuf::from(path).join(executable_name);
 
@@ -202,158 +334,222 @@ pub fn run_simulated_game(
     std::fs::set_permissions(&exe_to_run, perms)?;
 
     // Launch the process in background
-    let _ = Command::new(&exe_to_run)
+    let mut child = Command::new(&exe_to_run)
+        .env(RUNNER_TEXT_ENV, name)
+        .env(RUNNER_IPC_ENV, "1")
+        .args(runner_args(ttl_secs))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
         .spawn()
         .context("Could not start simulated game")?;
+    await_runner_ready(&mut child, &exe_to_run)?;
 
-    // Track the running process so we can clean it up on app exit
-    track_running_game(executable_name);
+    // Track the running process so we can stop it and clean it up on app exit
+    track_running_game(app_id, &exe_to_run, child);
 
     println!("Simulated game {} started from {:?}", name, exe_to_run);
     Ok(())
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(target_os = "linux")]
+pub fn run_simulated_game(
+    name: &str,
+    path: &str,
+    executable_name: &str,
+    app_id: &str,
+    ttl_secs: Option<u64>,
+) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::process::CommandExt;
+
+    let exe_to_run = PathBuf::from(path).join(executable_name);
+
+    if !exe_to_run.exists() {
+        anyhow::bail!("Executable does not exist: {:?}", exe_to_run);
+    }
+
+    // Make the file executable
+    let mut perms = std::fs::metadata(&exe_to_run)?.permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&exe_to_run, perms)?;
+
+    // Launch detached: own process group and no inherited stdio, so the runner
+    // isn't tied to the app's terminal (stdin/stdout are the app's control channel)
+    let mut child = Command::new(&exe_to_run)
+        .env(RUNNER_TEXT_ENV, name)
+        .env(RUNNER_IPC_ENV, "1")
+        .args(runner_args(ttl_secs))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .context("Could not start simulated game")?;
+    await_runner_ready(&mut child, &exe_to_run)?;
+
+    // Track the running process so we can stop it and clean it up on app exit
+    track_running_game(app_id, &exe_to_run, child);
+
+    println!("Simulated game {} started from {:?}", name, exe_to_run);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn run_simulated_game(
     _name: &str,
     _path: &str,
     _executable_name: &str,
     _app_id: &str,
+    _ttl_secs: Option<u64>,
 ) -> Result<()> {
-    anyhow::bail!("Game simulation is only supported on Windows and macOS")
+    anyhow::bail!("Game simulation is only supported on Windows, macOS and Linux")
 }
 
 /// Stop the simulated game
-#[cfg(target_os = "windows")]
+///
+/// Only runners started by `run_simulated_game` are stopped, through their own process
+/// handle; a real program with the same executable name is left alone.
 pub fn stop_simulated_game(exec_name: &str) -> Result<()> {
-    // taskkill /IM needs image name (filename), not path.
-    // Robustly handle both / and \\ separators
-    let file_name = exec_name
-        .split(|c| c == '/' || c == '\\')
-        .last()
-        .unwrap_or(exec_name);
-
     println!(
         "Stopping simulated game: Input='{}' -> Image='{}'",
-        exec_name, file_name
+        exec_name,
+        image_name(exec_name)
     );
 
-    // Use taskkill command to terminate process
-    let output = Command::new("taskkill")
-        .args(["/F", "/IM", file_name])
-        .output()
-        .context("Could not execute taskkill command")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        // Don't error out, process may not exist
-        println!(
-            "taskkill returned non-zero, process may not exist: {}",
-            stderr
-        );
+    let games = untrack_running_game(exec_name);
+    if games.is_empty() {
+        println!("Simulated game {} is not running", exec_name);
+        return Ok(());
     }
 
-    // Remove from tracking set
-    untrack_running_game(exec_name);
+    for mut game in games {
+        kill_game(&mut game);
+    }
 
     println!("Simulated game {} stopped", exec_name);
     Ok(())
 }
 
-#[cfg(target_os = "macos")]
-pub fn stop_simulated_game(exec_name: &str) -> Result<()> {
-    // Extract just the filename from the path
-    let file_name = exec_name.split('/').last().unwrap_or(exec_name);
-
-    println!(
-        "Stopping simulated game: Input='{}' -> Process='{}'",
-        exec_name, file_name
-    );
+/// Stop a runner (unless it already exited) and reap it
+///
+/// Asks it to quit over its stdin first and only kills it if it doesn't exit in time,
+/// or has no channel.
+fn kill_game(game: &mut RunningGame) {
+    if let Ok(None) = game.child.try_wait() {
+        if !request_quit(&mut game.child) {
+            if let Err(e) = game.child.kill() {
+                println!("Could not kill simulated game {:?}: {}", game.path, e);
+            }
+        }
+    }
+    let _ = game.child.wait();
+}
 
-    // Use pkill to terminate process by name
-    let output = Command::new("pkill")
-        .args(["-f", file_name])
-        .output()
-        .context("Could not execute pkill command")?;
+/// Send `quit` and wait up to `RUNNER_QUIT_GRACE`; returns whether the runner exited
+fn request_quit(child: &mut Child) -> bool {
+    let Some(mut stdin) = child.stdin.take() else {
+        return false;
+    };
+    if writeln!(stdin, "quit").and_then(|_| stdin.flush()).is_err() {
+        return false;
+    }
+    drop(stdin);
 
-    // pkill returns 0 if processes were killed, 1 if no processes matched
-    if !output.status.success() && output.status.code() != Some(1) {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("pkill returned non-zero: {}", stderr);
+    let deadline = Instant::now() + RUNNER_QUIT_GRACE;
+    while Instant::now() < deadline {
+        if let Ok(Some(_)) = child.try_wait() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(50));
     }
+    false
+}
 
-    // Remove from tracking set
-    untrack_running_game(exec_name);
+/// Image name (file name) of an executable path, accepting both separators
+fn image_name(executable: &str) -> &str {
+    executable.rsplit(['/', '\\']).next().unwrap_or(executable)
+}
 
-    println!("Simulated game {} stopped", exec_name);
-    Ok(())
+/// Track `game`, returning the one it replaces
+fn track_in<T: AsRef<Path>>(games: &mut HashMap<String, T>, app_id: &str, game: T) -> Option<T> {
+    let key = if app_id.is_empty() {
+        image_name(&game.as_ref().to_string_lossy()).to_string()
+    } else {
+        app_id.to_string()
+    };
+    games.insert(key, game)
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
-pub fn stop_simulated_game(_exec_name: &str) -> Result<()> {
-    anyhow::bail!("Game simulation is only supported on Windows and macOS")
+/// Remove and return the games running `exec_name`
+fn untrack_in<T: AsRef<Path>>(games: &mut HashMap<String, T>, exec_name: &str) -> Vec<T> {
+    let file_name = image_name(exec_name);
+    let keys: Vec<String> = games
+        .iter()
+        .filter(|(_, game)| image_name(&game.as_ref().to_string_lossy()) == file_name)
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.iter().filter_map(|key| games.remove(key)).collect()
 }
 
 /// Track a newly started simulated game process.
-fn track_running_game(executable_name: &str) {
-    let file_name = executable_name
-        .split(|c: char| c == '/' || c == '\\')
-        .last()
-        .unwrap_or(executable_name)
-        .to_string();
-    if let Ok(mut set) = RUNNING_GAMES.lock() {
-        set.insert(file_name.clone());
-        println!("Tracked running game: {} (total: {})", file_name, set.len());
+fn track_running_game(app_id: &str, exe_path: &Path, child: Child) {
+    let mut games = RUNNING_GAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let game = RunningGame {
+        path: exe_path.to_path_buf(),
+        child,
+    };
+    // A game started again for the same app replaces the old runner
+    if let Some(mut previous) = track_in(&mut games, app_id, game) {
+        kill_game(&mut previous);
     }
+    println!(
+        "Tracked running game: {:?} (total: {})",
+        exe_path,
+        games.len()
+    );
 }
 
-/// Remove a game from the tracking set (called after explicit stop).
-fn untrack_running_game(executable_name: &str) {
-    let file_name = executable_name
-        .split(|c: char| c == '/' || c == '\\')
-        .last()
-        .unwrap_or(executable_name)
-        .to_string();
-    if let Ok(mut set) = RUNNING_GAMES.lock() {
-        set.remove(&file_name);
-        println!(
-            "Untracked running game: {} (remaining: {})",
-            file_name,
-            set.len()
-        );
+/// Remove the games running `executable_name` from the tracking map.
+fn untrack_running_game(executable_name: &str) -> Vec<RunningGame> {
+    let mut games = RUNNING_GAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let removed = untrack_in(&mut games, executable_name);
+    println!(
+        "Untracked running game: {} (remaining: {})",
+        image_name(executable_name),
+        games.len()
+    );
+    removed
+}
+
+/// Stop **all** tracked simulated game processes, returning how many were stopped.
+pub fn stop_all_simulated_games() -> usize {
+    let games: Vec<RunningGame> = RUNNING_GAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .drain()
+        .map(|(_, game)| game)
+        .collect();
+
+    let count = games.len();
+    for mut game in games {
+        println!("  Stopping: {:?}", game.path);
+        kill_game(&mut game);
     }
+    count
 }
 
-/// Stop **all** tracked simulated game processes.
+/// Stop every simulated game on application exit.
 ///
-/// Called on application exit to ensure no orphaned child processes are left
-/// running after the main app (and its RPC connection) closes.
+/// Ensures no orphaned child processes are left running after the main app
+/// (and its RPC connection) closes.
 pub fn cleanup_all_simulated_games() {
-    let games: Vec<String> = {
-        match RUNNING_GAMES.lock() {
-            Ok(mut set) => {
-                let list: Vec<String> = set.drain().collect();
-                list
-            }
-            Err(poisoned) => {
-                let mut set = poisoned.into_inner();
-                let list: Vec<String> = set.drain().collect();
-                list
-            }
-        }
-    };
-
-    if games.is_empty() {
-        return;
-    }
-
-    println!(
-        "Cleaning up {} simulated game process(es) on exit...",
-        games.len()
-    );
-    for name in &games {
-        println!("  Stopping: {}", name);
-        let _ = stop_simulated_game(name);
+    let stopped = stop_all_simulated_games();
+    if stopped > 0 {
+        println!("Cleaned up {} simulated game process(es) on exit", stopped);
     }
 }
 
@@ -362,6 +558,53 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn test_running_games_tracked_per_app() {
+        let mut games = HashMap::new();
+        track_in(&mut games, "1", Path::new("/games/a/GameA.exe"));
+        track_in(&mut games, "2", Path::new("/games/b/GameB.exe"));
+        track_in(&mut games, "", Path::new("/games/c/GameC.exe"));
+        assert_eq!(games.len(), 3);
+        assert!(games.contains_key("GameC.exe"));
+
+        let removed = untrack_in(&mut games, "a\\GameA.exe");
+        assert_eq!(removed, vec![Path::new("/games/a/GameA.exe")]);
+        assert_eq!(games.len(), 2);
+        assert!(!games.contains_key("1"));
+        assert!(games.contains_key("2"));
+    }
+
+    #[test]
+    fn test_wait_for_ready() {
+        let timeout = Duration::from_secs(1);
+        assert_eq!(
+            wait_for_ready(std::io::Cursor::new("starting\nready\n"), timeout),
+            RunnerStartup::Ready
+        );
+        assert_eq!(
+            wait_for_ready(std::io::Cursor::new(""), timeout),
+            RunnerStartup::Exited
+        );
+
+        // A runner that never writes (or closes) stdout doesn't hold up the start
+        let (silent, _writer) = std::io::pipe().unwrap();
+        assert_eq!(
+            wait_for_ready(silent, Duration::from_millis(50)),
+            RunnerStartup::Silent
+        );
+    }
+
+    #[test]
+    fn test_runner_ttl() {
+        assert_eq!(runner_ttl_secs(900), 900 + RUNNER_TTL_MARGIN_SECS);
+        assert_eq!(runner_ttl_secs(u64::MAX), u64::MAX);
+        assert_eq!(
+            runner_args(Some(1800)),
+            vec!["--ttl-seconds".to_string(), "1800".to_string()]
+        );
+        assert!(runner_args(None).is_empty());
+    }
+
     #[test]
     #[ignore] // Requires actual file system operations
     fn test_create_simulated_game() {
@@ -0,0 +1,371 @@
+//! Headless Mode
+//!
+//! Completes a single quest from the command line without starting the GUI:
+//!
+//! ```text
+//! discord-quest-helper --headless --quest-id <ID> [--mode video|game|stream] [--dry-run]
+//! ```
+//!
+//! The token is read from `DQH_TOKEN`, or from the first line of stdin when that isn't
+//! set, so it never shows up in the process list or shell history.
+//! Progress is printed to stdout and the process exits with a status code, so it can be
+//! scripted or run on a server. Release builds on Windows use the GUI subsystem, so run
+//! it from a shell that attaches to the console (or redirect output) to see progress.
+//! Set `DQH_PROXY` to route the API traffic through a proxy (see `env_config`).
+
+use crate::discord_api::DiscordApiClient;
+use crate::models::{QuestPlan, QuestProgressEvent, QuestThrottledEvent};
+use crate::quest_completer::{self, HttpQuestJob, HttpQuestKind, ProgressSink, QuestOutcome};
+use crate::SUPER_PROPERTIES_MANAGER;
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{BufRead, IsTerminal};
+
+/// Quest finished
+pub const EXIT_OK: i32 = 0;
+/// Login, network or quest failure
+pub const EXIT_FAILURE: i32 = 1;
+/// Bad command line
+pub const EXIT_USAGE: i32 = 2;
+/// Stopped with Ctrl+C
+pub const EXIT_CANCELLED: i32 = 130;
+
+const USAGE: &str = "Usage: discord-quest-helper --headless --quest-id <ID> \
+                     [--mode video|game|stream] [--dry-run]\n\
+                     The token is read from DQH_TOKEN or, if unset, from stdin.";
+
+/// Environment variable holding the account token
+const TOKEN_ENV: &str = "DQH_TOKEN";
+
+/// Parsed `--headless` command line
+#[derive(Debug, Clone, PartialEq)]
+struct HeadlessArgs {
+    quest_id: String,
+    /// `None` picks the first task the HTTP completers support
+    mode: Option<String>,
+    dry_run: bool,
+}
+
+/// Whether the process was started with `--headless`
+pub fn requested(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--headless")
+}
+
+fn parse_args(args: &[String]) -> Result<HeadlessArgs> {
+    let mut quest_id = None;
+    let mut mode = None;
+    let mut dry_run = false;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("{} needs a value", name))
+        };
+        match arg.as_str() {
+            "--headless" => {}
+            "--token" => bail!(
+                "--token is not accepted, set {} or pipe the token on stdin",
+                TOKEN_ENV
+            ),
+            "--quest-id" => quest_id = Some(value("--quest-id")?),
+            "--mode" => {
+                let m = value("--mode")?;
+                if !matches!(m.as_str(), "video" | "game" | "stream") {
+                    bail!("Unknown mode '{}', expected video, game or stream", m);
+                }
+                mode = Some(m);
+            }
+            "--dry-run" => dry_run = true,
+            other => bail!("Unknown argument '{}'", other),
+        }
+    }
+
+    Ok(HeadlessArgs {
+        quest_id: quest_id.context("--quest-id is required")?,
+        mode,
+        dry_run,
+    })
+}
+
+/// The token from `env_token` (the value of `DQH_TOKEN`), else the first line of `input`
+fn read_token(env_token: Option<String>, input: &mut impl BufRead) -> Result<String> {
+    if let Some(token) = env_token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+    {
+        return Ok(token);
+    }
+
+    let mut line = String::new();
+    input
+        .read_line(&mut line)
+        .context("Failed to read the token from stdin")?;
+    let token = line.trim();
+    if token.is_empty() {
+        bail!("No token given, set {} or pipe it on stdin", TOKEN_ENV);
+    }
+    Ok(token.to_string())
+}
+
+/// Turn a quest task into a job for the HTTP completers, if `mode` allows it
+fn job_for_plan(quest_id: &str, plan: &QuestPlan, mode: Option<&str>) -> Option<HttpQuestJob> {
+    let kind = if plan.supports_video {
+        HttpQuestKind::Video
+    } else if plan.task_type == "PLAY_ON_DESKTOP" {
+        HttpQuestKind::Game {
+            application_id: plan.application_id.clone()?,
+        }
+    } else if plan.task_type == "STREAM_ON_DESKTOP" {
+        HttpQuestKind::Stream
+    } else {
+        return None;
+    };
+
+    if mode.is_some_and(|m| m != kind.mode()) {
+        return None;
+    }
+
+    Some(HttpQuestJob {
+        quest_id: quest_id.to_string(),
+        kind,
+        seconds_needed: plan.seconds_needed,
+        initial_progress: plan.initial_progress,
+    })
+}
+
+/// Prints completer updates to stdout/stderr
+struct StdoutSink;
+
+impl ProgressSink for StdoutSink {
+    fn progress(&self, evt: QuestProgressEvent) {
+        println!(
+            "[progress] {:.1}% ({:.0}/{}s, ~{:.0}s left)",
+            evt.percent, evt.current_seconds, evt.seconds_needed, evt.eta_seconds
+        );
+    }
+
+    fn complete(&self, quest_id: &str) {
+        println!("[complete] Quest {} completed", quest_id);
+    }
+
+    fn stopped(&self, last_progress: Option<QuestProgressEvent>) {
+        match last_progress {
+            Some(evt) => println!(
+                "[stopped] Quest stopped at {:.1}% ({:.0}/{}s)",
+                evt.percent, evt.current_seconds, evt.seconds_needed
+            ),
+            None => println!("[stopped] Quest stopped"),
+        }
+    }
+
+    fn error(&self, msg: &str) {
+        eprintln!("[error] {}", msg);
+    }
+
+    fn warning(&self, msg: &str) {
+        eprintln!("[warning] {}", msg);
+    }
+
+    fn throttled(&self, evt: QuestThrottledEvent) {
+        eprintln!(
+            "[throttled] Discord recorded {:.0}s of {:.0}s sent; slowing to {:.1}x",
+            evt.accepted_seconds, evt.sent_seconds, evt.speed_multiplier
+        );
+    }
+}
+
+async fn run_quest(args: HeadlessArgs, token: String) -> Result<QuestOutcome> {
+    crate::token_extractor::validate_token_shape(&token)?;
+    let env = crate::env_config::EnvConfig::from_env();
+    for problem in &env.invalid {
+        eprintln!("[warning] Ignoring {}", problem);
+    }
+    env.apply_globals();
+    let client =
+        DiscordApiClient::new(token, env.proxy_url).context("Failed to create API client")?;
+    let user = client
+        .get_current_user()
+        .await
+        .context("Failed to validate token")?;
+    println!("Logged in as {}", user.username);
+
+    // Best effort: stale build numbers still work, just like the GUI fallback
+    if let Ok(build_number) = crate::token_extractor::fetch_build_number_from_discord().await {
+        SUPER_PROPERTIES_MANAGER
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .set_from_remote_js(build_number);
+    }
+
+    let quests = client
+        .get_quests_raw()
+        .await
+        .context("Failed to get quest list")?;
+    let quest = quests
+        .get("quests")
+        .and_then(|q| q.as_array())
+        .and_then(|list| {
+            list.iter()
+                .find(|q| q.get("id").and_then(|v| v.as_str()) == Some(args.quest_id.as_str()))
+        })
+        .ok_or_else(|| anyhow!("Quest {} not found", args.quest_id))?;
+
+    let enrolled = quest
+        .pointer("/user_status/enrolled_at")
+        .is_some_and(|v| !v.is_null());
+    if !enrolled && !args.dry_run {
+        client
+            .accept_quest(&args.quest_id)
+            .await
+            .context("Failed to enroll in quest")?;
+        println!("Enrolled in quest {}", args.quest_id);
+    }
+
+    let plans = quest_completer::quest_plans(quest);
+    let job = plans
+        .iter()
+        .find_map(|plan| job_for_plan(&args.quest_id, plan, args.mode.as_deref()))
+        .ok_or_else(|| {
+            let wants_video = args.mode.as_deref().is_none_or(|mode| mode == "video");
+            if wants_video && quest_completer::has_mobile_video_task(&plans) {
+                return anyhow!(
+                    "Quest {}: {}",
+                    args.quest_id,
+                    quest_completer::MOBILE_VIDEO_UNSUPPORTED
+                );
+            }
+            match &args.mode {
+                Some(mode) => anyhow!("Quest {} has no {} task", args.quest_id, mode),
+                None => anyhow!("Quest {} has no task this mode can complete", args.quest_id),
+            }
+        })?;
+
+    println!(
+        "Starting {} for quest {} ({}s needed, {:.1}% done)",
+        job.kind.label(),
+        job.quest_id,
+        job.seconds_needed,
+        job.initial_progress
+    );
+
+    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(()).await;
+        }
+    });
+
+    let options = quest_completer::RunOptions {
+        dry_run: args.dry_run,
+        ..Default::default()
+    };
+    quest_completer::run_http_job(&client, &job, options, &StdoutSink, cancel_rx).await
+}
+
+/// Run headless mode with the process arguments and return the exit code
+pub fn run(args: &[String]) -> i32 {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let stdin = std::io::stdin();
+    let env_token = std::env::var(TOKEN_ENV).ok();
+    if env_token.is_none() && stdin.is_terminal() {
+        eprintln!("Paste the account token and press Enter:");
+    }
+    let token = match read_token(env_token, &mut stdin.lock()) {
+        Ok(token) => token,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start async runtime: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+
+    match runtime.block_on(run_quest(args, token)) {
+        Ok(QuestOutcome::Completed) => EXIT_OK,
+        Ok(QuestOutcome::Cancelled) => EXIT_CANCELLED,
+        Ok(QuestOutcome::TimedOut) => EXIT_FAILURE,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("app")
+            .chain(list.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args(&[
+            "--headless",
+            "--quest-id",
+            "123",
+            "--mode",
+            "video",
+            "--dry-run",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            HeadlessArgs {
+                quest_id: "123".to_string(),
+                mode: Some("video".to_string()),
+                dry_run: true,
+            }
+        );
+
+        assert!(parse_args(&args(&["--headless"])).is_err());
+        assert!(parse_args(&args(&["--headless", "--quest-id"])).is_err());
+        assert!(parse_args(&args(&["--headless", "--quest-id", "1", "--token", "abc"])).is_err());
+        assert!(parse_args(&args(&[
+            "--headless",
+            "--quest-id",
+            "1",
+            "--mode",
+            "achievement"
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_token() {
+        let mut empty = std::io::Cursor::new("");
+        assert_eq!(
+            read_token(Some(" abc ".to_string()), &mut empty).unwrap(),
+            "abc"
+        );
+
+        let mut piped = std::io::Cursor::new("from-stdin\nrest");
+        assert_eq!(read_token(None, &mut piped).unwrap(), "from-stdin");
+
+        let mut piped = std::io::Cursor::new("from-stdin\n");
+        assert_eq!(
+            read_token(Some(String::new()), &mut piped).unwrap(),
+            "from-stdin"
+        );
+
+        assert!(read_token(None, &mut std::io::Cursor::new("\n")).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! JSON Store
+//!
+//! Small JSON files in the app data dir (quest profiles, the quest schedule, saved
+//! progress, the build number cache). Writes go through a temp file and a rename, so a
+//! crash mid-write never leaves a truncated file behind.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Path of `file_name` inside the app data dir
+pub fn path(app_handle: &tauri::AppHandle, file_name: &str) -> Result<PathBuf> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .context("Could not resolve app data dir")?;
+    Ok(dir.join(file_name))
+}
+
+/// Read a value saved with `save_atomic`; `None` if the file is missing or unreadable
+pub fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Replace the file at `path` with `value` as pretty-printed JSON
+pub fn save_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create app data dir")?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(value)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_save_atomic() {
+        let dir = std::env::temp_dir().join(format!("dqh-json-store-{}", std::process::id()));
+        let path = dir.join("nested").join("store.json");
+
+        assert_eq!(load::<Vec<u32>>(&path), None);
+
+        save_atomic(&path, &[1u32, 2, 3]).unwrap();
+        assert_eq!(load::<Vec<u32>>(&path), Some(vec![1, 2, 3]));
+
+        save_atomic(&path, &[4u32]).unwrap();
+        assert_eq!(load::<Vec<u32>>(&path), Some(vec![4]));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        // Unparseable content reads as missing
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
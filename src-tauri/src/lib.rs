@@ -1,23 +1,38 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod build_cache;
 mod cdp_client;
 mod cdp_quest;
 mod discord_api;
 pub mod discord_cdp_launcher;
 mod discord_gateway;
+mod endpoints;
+mod env_config;
+mod error;
 mod game_simulator;
+mod headless;
+mod json_store;
+#[cfg(feature = "live-stream")]
+mod live_stream;
 mod logger;
+mod metrics;
 mod models;
+mod notifications;
+mod progress_store;
 mod quest_completer;
+mod quest_profiles;
+mod scheduler;
 mod stealth;
 mod super_properties;
 mod token_extractor;
 
+use anyhow::Context;
 use discord_api::DiscordApiClient;
+use error::AppError;
 use models::*;
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use super_properties::XSuperPropertiesManager;
 use tauri::{Emitter, Listener, Manager, State};
 
@@ -26,15 +41,57 @@ use tauri::{Emitter, Listener, Manager, State};
 static SUPER_PROPERTIES_MANAGER: Lazy<Mutex<XSuperPropertiesManager>> =
     Lazy::new(|| Mutex::new(XSuperPropertiesManager::new()));
 
+/// Background task that periodically re-reads SuperProperties over CDP (if enabled)
+static SUPER_PROPERTIES_REFRESH: Lazy<Mutex<Option<tokio::task::JoinHandle<()>>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Shortest allowed interval for the SuperProperties refresh task
+const MIN_SUPER_PROPERTIES_REFRESH_SECS: u64 = 60;
+
+/// How often the build number we send is compared to Discord's latest
+const BUILD_STALENESS_CHECK_SECS: u64 = 6 * 60 * 60;
+
 /// Global state: Discord API client
 struct AppState {
     client: Mutex<Option<DiscordApiClient>>,
     quest_state: Mutex<Option<QuestState>>,
+    default_mode_gate: Mutex<DefaultModeGate>,
+    proxy_url: Mutex<Option<String>>,
+    /// Gateway session `is_game_detected` answers from, opened on first use
+    game_watch: Mutex<Option<Arc<discord_gateway::GatewayPresence>>>,
+    /// Set when Discord flags the logged-in account; cleared by the next login
+    account_flagged: Mutex<bool>,
+}
+
+/// Additional Discord data directories passed from the UI, blanks dropped
+fn parse_extra_paths(extra_paths: Option<Vec<String>>) -> Vec<std::path::PathBuf> {
+    extra_paths
+        .unwrap_or_default()
+        .iter()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from)
+        .collect()
+}
+
+/// Which Discord clients have data on this machine and how many tokens each holds
+#[tauri::command]
+async fn list_discord_installations(
+    extra_paths: Option<Vec<String>>,
+) -> Vec<token_extractor::DiscordInstallation> {
+    token_extractor::list_installations(&parse_extra_paths(extra_paths))
 }
 
 /// Auto-detect Discord tokens (returns all valid accounts found)
+///
+/// `extra_paths` are additional Discord data directories to scan, for portable installs
+/// or relocated profiles. `channel` limits detection to one client.
 #[tauri::command]
-async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedAccount>, String> {
+async fn auto_detect_token(
+    state: State<'_, AppState>,
+    extra_paths: Option<Vec<String>>,
+    channel: Option<token_extractor::DiscordClient>,
+) -> Result<Vec<ExtractedAccount>, AppError> {
     use crate::logger::{log, LogCategory, LogLevel};
 
     log(
@@ -45,16 +102,30 @@ async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedA
     );
 
     // Extract tokens
-    let tokens = token_extractor::extract_tokens().map_err(|e| {
+    let extra_paths = parse_extra_paths(extra_paths);
+    let extracted = token_extractor::extract_tokens(&extra_paths, channel).map_err(|e| {
         log(
             LogLevel::Error,
             LogCategory::TokenExtraction,
             "Token extraction failed",
             Some(&e.to_string()),
         );
-        format!("Token extraction failed: {}", e)
+        AppError::from(e.context("Token extraction failed"))
     })?;
 
+    // Group sources by raw token so each account is validated once
+    let mut tokens: Vec<(String, Vec<TokenSource>)> = Vec::new();
+    for item in extracted.tokens {
+        let source = TokenSource {
+            client: item.client,
+            file_path_redacted: item.file_path_redacted,
+        };
+        match tokens.iter_mut().find(|(token, _)| *token == item.token) {
+            Some((_, sources)) => sources.push(source),
+            None => tokens.push((item.token, vec![source])),
+        }
+    }
+
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
@@ -72,15 +143,45 @@ async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedA
         None,
     );
 
-    for (index, token) in tokens.iter().enumerate() {
+    for (index, (token, sources)) in tokens.iter().enumerate() {
         log(
             LogLevel::Debug,
             LogCategory::TokenExtraction,
             &format!("Validating token {}/{}", index + 1, tokens.len()),
             None,
         );
+
+        // Skip obviously malformed tokens before touching the network
+        let metadata = match token_extractor::parse_token_metadata(token) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                log(
+                    LogLevel::Debug,
+                    LogCategory::TokenExtraction,
+                    &format!("Skipping malformed token {}", index + 1),
+                    Some(&e.to_string()),
+                );
+                continue;
+            }
+        };
+        log(
+            LogLevel::Debug,
+            LogCategory::TokenExtraction,
+            &format!(
+                "Token {} belongs to user {} (issued: {})",
+                index + 1,
+                metadata.user_id,
+                metadata
+                    .created_at
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "unknown".to_string())
+            ),
+            None,
+        );
+
         // Create API client
-        if let Ok(client) = DiscordApiClient::new(token.clone()) {
+        let proxy_url = state.proxy_url.lock().unwrap().clone();
+        if let Ok(client) = DiscordApiClient::new(token.clone(), proxy_url) {
             // Validate token
             match client.get_current_user().await {
                 Ok(user) => {
@@ -93,6 +194,7 @@ async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedA
                     valid_accounts.push(ExtractedAccount {
                         token: token.clone(),
                         user,
+                        source: sources.clone(),
                     });
                 }
                 Err(e) => {
@@ -120,10 +222,17 @@ async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedA
     );
 
     if valid_accounts.is_empty() {
-        return Err(if !last_error.is_empty() {
-            format!("No valid accounts found. Last error: {}", last_error)
+        // A client whose key couldn't be decrypted is the likelier reason than the
+        // stale tokens found elsewhere
+        return Err(if let Some(store_error) = extracted.credential_error {
+            store_error.into()
+        } else if !last_error.is_empty() {
+            AppError::Other(format!(
+                "No valid accounts found. Last error: {}",
+                last_error
+            ))
         } else {
-            "No valid accounts found".to_string()
+            AppError::Other("No valid accounts found".to_string())
         });
     }
 
@@ -132,26 +241,42 @@ async fn auto_detect_token(_state: State<'_, AppState>) -> Result<Vec<ExtractedA
     Ok(valid_accounts)
 }
 
+/// Clone of the logged-in API client
+fn current_client(state: &State<'_, AppState>) -> Result<DiscordApiClient, AppError> {
+    state
+        .client
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(AppError::NotLoggedIn)
+}
+
 /// Login with provided token
 #[tauri::command]
-async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordUser, String> {
+async fn set_token(
+    token: String,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<DiscordUser, AppError> {
     use crate::logger::{log, LogCategory, LogLevel};
 
+    token_extractor::validate_token_shape(&token)?;
+
     // Create API client
-    let client =
-        DiscordApiClient::new(token).map_err(|e| format!("Failed to create API client: {}", e))?;
+    let proxy_url = state.proxy_url.lock().unwrap().clone();
+    let client = DiscordApiClient::new(token, proxy_url).context("Failed to create API client")?;
 
     // Validate token
     let user = client
         .get_current_user()
         .await
-        .map_err(|e| format!("Failed to validate token: {}", e))?;
+        .context("Failed to validate token")?;
 
     // Fetch latest build_number and client info before returning (so frontend await can rely on completion)
 
     // Priority 1: Try CDP
     let mut cdp_success = false;
-    let cdp_port = cdp_client::DEFAULT_CDP_PORT;
+    let cdp_port = cdp_client::default_port();
 
     log(
         LogLevel::Info,
@@ -163,7 +288,12 @@ async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordU
         None,
     );
 
-    if let Ok(cdp_result) = cdp_client::fetch_super_properties_via_cdp(cdp_port).await {
+    if let Ok(cdp_result) = cdp_client::fetch_super_properties_via_cdp(
+        cdp_port,
+        cdp_client::SUPER_PROPERTIES_TIMEOUT_SECS,
+    )
+    .await
+    {
         log(
             LogLevel::Info,
             LogCategory::TokenExtraction,
@@ -180,6 +310,13 @@ async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordU
         if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
             manager.set_from_cdp(&cdp_result.base64, &cdp_result.decoded);
         }
+        if let Some(build_number) = cdp_result
+            .decoded
+            .get("client_build_number")
+            .and_then(|v| v.as_u64())
+        {
+            build_cache::save(&app_handle, build_number);
+        }
         cdp_success = true;
     } else {
         log(
@@ -190,8 +327,35 @@ async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordU
         );
     }
 
+    // A build number cached by an earlier launch is recent enough to skip the download
+    let mode = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_mode();
+    let cache_fresh =
+        mode == super_properties::SourceMode::Cached && build_cache::is_fresh(&app_handle);
+    let offline = token_extractor::is_offline();
+    if !cdp_success && offline {
+        log(
+            LogLevel::Info,
+            LogCategory::TokenExtraction,
+            &format!(
+                "Offline mode, skipping remote fetches (SuperProperties source: {})",
+                mode.as_str()
+            ),
+            None,
+        );
+    } else if !cdp_success && cache_fresh {
+        log(
+            LogLevel::Info,
+            LogCategory::TokenExtraction,
+            "Using cached build number, skipping JS scraping",
+            None,
+        );
+    }
+
     // Priority 2: Remote JS (Fallback)
-    if !cdp_success {
+    if !cdp_success && !cache_fresh && !offline {
         // Get build_number
         match token_extractor::fetch_build_number_from_discord().await {
             Ok(build_number) => {
@@ -207,6 +371,7 @@ async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordU
                 if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
                     manager.set_from_remote_js(build_number);
                 }
+                build_cache::save(&app_handle, build_number);
             }
             Err(e) => {
                 log(
@@ -220,80 +385,185 @@ async fn set_token(token: String, state: State<'_, AppState>) -> Result<DiscordU
     }
 
     // Get client info (native_build_number and version)
-    match token_extractor::fetch_discord_client_info().await {
-        Ok(info) => {
-            log(
-                LogLevel::Info,
-                LogCategory::TokenExtraction,
-                &format!(
-                    "Successfully fetched client info: version={}, native_build={}",
-                    info.client_version(),
-                    info.native_build_number
-                ),
-                None,
-            );
-            if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
-                manager.set_client_info(info.client_version(), info.native_build_number);
-            }
-        }
-        Err(e) => {
-            log(
-                LogLevel::Warn,
-                LogCategory::TokenExtraction,
-                &format!("Failed to fetch client info: {}", e),
-                None,
-            );
-        }
+    if !offline {
+        refresh_discord_client_info().await;
     }
 
     // Save client AFTER initializing SuperProperties to avoid race conditions
     // where other commands might use the client with stale properties
     *state.client.lock().unwrap() = Some(client);
+    // The watch session and any flag belong to the previous login
+    *state.game_watch.lock().unwrap() = None;
+    *state.account_flagged.lock().unwrap() = false;
 
     Ok(user)
 }
 
-/// Get quest list (via HTTP API /quests/@me endpoint)
+/// Get quest list (via HTTP API /quests/@me endpoint), parsed into the typed model
 #[tauri::command]
-async fn get_quests(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let client = {
-        let guard = state.client.lock().unwrap();
-        guard
-            .as_ref()
-            .ok_or_else(|| "Not logged in".to_string())?
-            .clone()
-    };
+async fn get_quests(state: State<'_, AppState>) -> Result<Vec<Quest>, AppError> {
+    let client = current_client(&state)?;
+
+    Ok(client
+        .get_quests()
+        .await
+        .context("Failed to get quest list")?)
+}
+
+/// Work out which quest flows can complete a quest, one plan per task
+///
+/// Fails with `MOBILE_VIDEO_UNSUPPORTED` if the quest's only tasks are mobile video ones.
+#[tauri::command]
+async fn get_quest_plan(
+    quest_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<QuestPlan>, AppError> {
+    let client = current_client(&state)?;
 
     let quests = client
         .get_quests_raw()
         .await
-        .map_err(|e| format!("Failed to get quest list: {}", e))?;
-
-    // Return the "quests" array directly
-    Ok(quests
+        .context("Failed to get quest list")?;
+    let quest = quests
         .get("quests")
-        .cloned()
-        .unwrap_or(serde_json::Value::Array(vec![])))
+        .and_then(|q| q.as_array())
+        .and_then(|list| {
+            list.iter()
+                .find(|q| q.get("id").and_then(|v| v.as_str()) == Some(quest_id.as_str()))
+        })
+        .ok_or_else(|| AppError::Other(format!("Quest {} not found", quest_id)))?;
+
+    let plans = quest_completer::quest_plans(quest);
+    let runnable = plans
+        .iter()
+        .any(|plan| plan.supports_video || plan.supports_heartbeat);
+    if !runnable && quest_completer::has_mobile_video_task(&plans) {
+        return Err(AppError::Other(
+            quest_completer::MOBILE_VIDEO_UNSUPPORTED.to_string(),
+        ));
+    }
+    Ok(plans)
+}
+
+/// Get the unmodified /quests/@me response (for debugging)
+#[tauri::command]
+async fn get_quests_raw(state: State<'_, AppState>) -> Result<serde_json::Value, AppError> {
+    let client = current_client(&state)?;
+
+    Ok(client
+        .get_quests_raw()
+        .await
+        .context("Failed to get quest list")?)
 }
 
 /// Get full quest list response, preserving excluded quests and enrollment block status.
 #[tauri::command]
-async fn get_quests_full(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let client = {
-        let guard = state.client.lock().unwrap();
-        guard
-            .as_ref()
-            .ok_or_else(|| "Not logged in".to_string())?
-            .clone()
-    };
+async fn get_quests_full(state: State<'_, AppState>) -> Result<serde_json::Value, AppError> {
+    let client = current_client(&state)?;
 
-    client
+    let quests = client
         .get_quests_raw()
         .await
-        .map_err(|e| format!("Failed to get quest list: {}", e))
+        .context("Failed to get quest list")?;
+    notifications::remember_quest_names(&quests);
+    Ok(quests)
+}
+
+/// Refuse to start quests once Discord flagged the account, until the user logs in again
+fn check_account_not_flagged(state: &AppState) -> Result<(), String> {
+    if *state.account_flagged.lock().unwrap() {
+        return Err(
+            "Discord flagged this account, so quests are paused. Log in again to continue."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Check the SuperProperties source before starting an HTTP quest
+///
+/// Quests started while still in `SourceMode::Default` send a stale build number,
+/// which is easy to tell apart from a real client. Depending on the configured gate
+/// this warns (via `quest-warning`) or refuses to start.
+fn check_default_mode_gate(
+    state: &State<'_, AppState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let gate = *state.default_mode_gate.lock().unwrap();
+    if gate == DefaultModeGate::Off {
+        return Ok(());
+    }
+
+    let mode = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_mode();
+    if mode != super_properties::SourceMode::Default {
+        return Ok(());
+    }
+
+    let message = "SuperProperties are still using built-in defaults. Enable CDP or retry fetching super properties before running quests.";
+    log(LogLevel::Warn, LogCategory::Quest, message, None);
+
+    if gate == DefaultModeGate::Block {
+        return Err(message.to_string());
+    }
+    quest_completer::emit_event(app_handle, "quest-warning", message);
+    Ok(())
+}
+
+/// Get the default-mode safety gate setting
+#[tauri::command]
+fn get_default_mode_gate(state: State<'_, AppState>) -> DefaultModeGate {
+    *state.default_mode_gate.lock().unwrap()
+}
+
+/// Set the default-mode safety gate setting
+#[tauri::command]
+fn set_default_mode_gate(gate: DefaultModeGate, state: State<'_, AppState>) {
+    *state.default_mode_gate.lock().unwrap() = gate;
+}
+
+/// Set (or clear with `None`) the proxy used for Discord API, gateway and website traffic
+#[tauri::command]
+fn set_proxy(proxy_url: Option<String>, state: State<'_, AppState>) -> Result<(), String> {
+    let proxy_url = proxy_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+
+    if let Some(url) = &proxy_url {
+        discord_api::parse_proxy_url(url).map_err(|e| format!("{:#}", e))?;
+    }
+
+    if let Some(client) = state.client.lock().unwrap().as_ref() {
+        client
+            .set_proxy(proxy_url.clone())
+            .map_err(|e| format!("Failed to apply proxy: {:#}", e))?;
+    }
+
+    token_extractor::set_proxy(proxy_url.clone());
+    *state.proxy_url.lock().unwrap() = proxy_url;
+    // Reconnect the watch session through the new proxy on next use
+    *state.game_watch.lock().unwrap() = None;
+    Ok(())
+}
+
+/// `max_duration_secs` from a start command; 0 is rejected since it would stop at once
+fn max_duration_option(
+    max_duration_secs: Option<u64>,
+) -> Result<Option<std::time::Duration>, String> {
+    match max_duration_secs {
+        Some(0) => Err("max_duration_secs must be greater than 0".to_string()),
+        secs => Ok(secs.map(std::time::Duration::from_secs)),
+    }
 }
 
 /// Start video quest
+///
+/// `max_duration_secs` caps the run's wall-clock time (default: three times the
+/// expected runtime); hitting it stops the quest and emits `quest-timeout`.
 #[tauri::command]
 async fn start_video_quest(
     quest_id: String,
@@ -301,59 +571,77 @@ async fn start_video_quest(
     initial_progress: f64,
     speed_multiplier: f64,
     heartbeat_interval: u64,
+    watch_ahead_secs: Option<f64>,
+    on_complete: Option<CompletionPolicy>,
+    dry_run: Option<bool>,
+    max_duration_secs: Option<u64>,
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    // Stop current quest (if any)
-    stop_quest_internal(&state).await;
-
-    let client = state.client.lock().unwrap();
-    let client = client
-        .as_ref()
-        .ok_or_else(|| "Not logged in".to_string())?
-        .clone();
-
-    // Create cancel channel
-    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel::<()>(1);
+    let max_duration = max_duration_option(max_duration_secs)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        check_default_mode_gate(&state, &app_handle)?;
+    }
 
-    // Save quest state
-    *state.quest_state.lock().unwrap() = Some(QuestState {
-        quest_id: quest_id.clone(),
-        cancel_flag: cancel_tx,
-    });
+    let client = {
+        let guard = state.client.lock().unwrap();
+        guard
+            .as_ref()
+            .ok_or_else(|| "Not logged in".to_string())?
+            .clone()
+    };
 
-    // Run in background task
-    tokio::spawn(async move {
-        let result = quest_completer::complete_video_quest(
-            &client,
-            quest_id,
-            seconds_needed,
-            initial_progress,
-            speed_multiplier,
-            heartbeat_interval,
-            app_handle.clone(),
-            cancel_rx,
-        )
-        .await;
+    let job = quest_completer::HttpQuestJob {
+        quest_id,
+        kind: quest_completer::HttpQuestKind::Video,
+        seconds_needed,
+        initial_progress,
+    };
+    let video = quest_completer::VideoSettings {
+        speed_multiplier,
+        watch_ahead_secs: watch_ahead_secs.unwrap_or(0.0),
+        heartbeat_interval,
+    };
 
-        if let Err(e) = result {
-            let _ = app_handle.emit("quest-error", format!("Video quest failed: {}", e));
-        }
-    });
+    let options = quest_completer::RunOptions {
+        video,
+        dry_run,
+        max_duration,
+        ..Default::default()
+    };
 
-    Ok(())
+    start_http_quest(
+        &state,
+        client,
+        job,
+        options,
+        on_complete.unwrap_or_default(),
+        app_handle,
+        true,
+    )
+    .await
 }
 
-/// Start stream quest
+/// Start stream quest (`max_duration_secs` and `on_complete` as for `start_video_quest`)
 #[tauri::command]
 async fn start_stream_quest(
     quest_id: String,
     stream_key: String,
     seconds_needed: u32,
     initial_progress: f64,
+    on_complete: Option<CompletionPolicy>,
+    dry_run: Option<bool>,
+    max_duration_secs: Option<u64>,
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
+    let max_duration = max_duration_option(max_duration_secs)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        check_default_mode_gate(&state, &app_handle)?;
+    }
+
     // Stop current quest (if any)
     stop_quest_internal(&state).await;
 
@@ -374,21 +662,43 @@ async fn start_stream_quest(
         cancel_flag: cancel_tx,
     });
 
+    let limit = max_duration.unwrap_or_else(|| {
+        let job = quest_completer::HttpQuestJob {
+            quest_id: quest_id.clone(),
+            kind: quest_completer::HttpQuestKind::Stream,
+            seconds_needed,
+            initial_progress,
+        };
+        quest_completer::default_max_duration(&job, &Default::default())
+    });
+
     // Run in background task
     tokio::spawn(async move {
-        let result = quest_completer::complete_stream_quest(
+        let run = quest_completer::complete_stream_quest(
             &client,
-            quest_id,
+            quest_id.clone(),
             stream_key,
             seconds_needed,
             initial_progress,
-            app_handle.clone(),
+            dry_run,
+            &app_handle,
             cancel_rx,
-        )
-        .await;
+        );
+        let result = quest_completer::with_time_limit(&quest_id, limit, &app_handle, run).await;
 
-        if let Err(e) = result {
-            let _ = app_handle.emit("quest-error", format!("Stream quest failed: {}", e));
+        match result {
+            Ok(quest_completer::QuestOutcome::Completed) if !dry_run => {
+                let policy = on_complete.unwrap_or_default();
+                continue_after_completion(client, &quest_id, policy, app_handle).await;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                quest_completer::emit_event(
+                    &app_handle,
+                    "quest-error",
+                    format!("Stream quest failed: {}", e),
+                );
+            }
         }
     });
 
@@ -396,17 +706,27 @@ async fn start_stream_quest(
 }
 
 /// Start game quest via direct heartbeat (without running simulated game)
+///
+/// `max_duration_secs` as for `start_video_quest`.
 #[tauri::command]
 async fn start_game_heartbeat_quest(
     quest_id: String,
     application_id: String,
     seconds_needed: u32,
     initial_progress: f64,
+    on_complete: Option<CompletionPolicy>,
+    dry_run: Option<bool>,
+    detection_timeout_secs: Option<u64>,
+    gateway_presence: Option<bool>,
+    max_duration_secs: Option<u64>,
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    // Stop current quest (if any)
-    stop_quest_internal(&state).await;
+    let max_duration = max_duration_option(max_duration_secs)?;
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        check_default_mode_gate(&state, &app_handle)?;
+    }
 
     let client = {
         let guard = state.client.lock().unwrap();
@@ -416,39 +736,39 @@ async fn start_game_heartbeat_quest(
             .clone()
     };
 
-    // Create cancel channel
-    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel::<()>(1);
-
-    // Save quest state
-    *state.quest_state.lock().unwrap() = Some(QuestState {
-        quest_id: quest_id.clone(),
-        cancel_flag: cancel_tx,
-    });
-
-    // Run in background task
-    tokio::spawn(async move {
-        let result = quest_completer::complete_game_quest_via_heartbeat(
-            &client,
-            quest_id,
-            application_id,
-            seconds_needed,
-            initial_progress,
-            app_handle.clone(),
-            cancel_rx,
-        )
-        .await;
-
-        if let Err(e) = result {
-            let _ = app_handle.emit("quest-error", format!("Game heartbeat quest failed: {}", e));
-        }
-    });
+    let job = quest_completer::HttpQuestJob {
+        quest_id,
+        kind: quest_completer::HttpQuestKind::Game { application_id },
+        seconds_needed,
+        initial_progress,
+    };
+    let options = quest_completer::RunOptions {
+        dry_run,
+        detection_timeout: detection_timeout_secs.map(std::time::Duration::from_secs),
+        gateway_presence: gateway_presence.unwrap_or(false),
+        max_duration,
+        ..Default::default()
+    };
 
-    Ok(())
+    start_http_quest(
+        &state,
+        client,
+        job,
+        options,
+        on_complete.unwrap_or_default(),
+        app_handle,
+        true,
+    )
+    .await
 }
 
 /// Start a quest via CDP injection
 ///
 /// Dispatches to the appropriate CDP completion function based on quest_type.
+/// `on_complete` is applied through the logged-in API client; a `ClaimAndNext` chain
+/// continues with the HTTP completers. Without `cdp_port` the default port is used.
+/// `max_duration_secs` as for `start_video_quest`; a timed-out run also has its
+/// injected state in Discord cleaned up.
 #[tauri::command]
 async fn start_cdp_quest(
     quest_id: String,
@@ -457,11 +777,27 @@ async fn start_cdp_quest(
     application_name: String,
     seconds_needed: u32,
     initial_progress: f64,
-    cdp_port: u16,
+    cdp_port: Option<u16>,
     checkpoint_times: Option<Vec<u32>>,
+    on_complete: Option<CompletionPolicy>,
+    max_duration_secs: Option<u64>,
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
+    let max_duration = max_duration_option(max_duration_secs)?;
+    let cdp_port = cdp_port.unwrap_or_else(cdp_client::default_port);
+    let checkpoint_times = checkpoint_times
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| vec![180, 180, 180]);
+    let limit = max_duration.unwrap_or_else(|| {
+        let expected = if quest_type == "activity" {
+            checkpoint_times.iter().sum::<u32>() as f64
+        } else {
+            seconds_needed as f64 - initial_progress
+        };
+        quest_completer::max_duration_for(expected)
+    });
+
     // Stop current quest (if any)
     stop_quest_internal(&state).await;
 
@@ -481,132 +817,1017 @@ async fn start_cdp_quest(
 
     // Run in background task
     tokio::spawn(async move {
-        let result = match quest_type_clone.as_str() {
-            "play" => {
-                cdp_quest::complete_play_quest_via_cdp(
-                    cdp_port,
-                    quest_id,
-                    application_id,
-                    application_name,
-                    seconds_needed,
-                    initial_progress,
-                    client,
-                    app_handle.clone(),
-                    cancel_rx,
-                )
-                .await
-            }
-            "stream" => {
-                cdp_quest::complete_stream_quest_via_cdp(
-                    cdp_port,
-                    quest_id,
-                    application_id,
-                    seconds_needed,
-                    initial_progress,
-                    client,
-                    app_handle.clone(),
-                    cancel_rx,
-                )
-                .await
-            }
-            "video" => {
-                cdp_quest::complete_video_quest_via_cdp(
-                    cdp_port,
-                    quest_id,
-                    seconds_needed,
-                    initial_progress,
-                    app_handle.clone(),
-                    cancel_rx,
-                )
-                .await
-            }
-            "activity" => {
-                let times = checkpoint_times
-                    .filter(|v| !v.is_empty())
-                    .unwrap_or_else(|| vec![180, 180, 180]);
-                cdp_quest::complete_activity_quest_via_cdp(
-                    cdp_port,
-                    quest_id,
-                    times,
-                    app_handle.clone(),
-                    cancel_rx,
-                )
-                .await
+        let run = async {
+            match quest_type_clone.as_str() {
+                "play" => {
+                    cdp_quest::complete_play_quest_via_cdp(
+                        cdp_port,
+                        quest_id.clone(),
+                        application_id,
+                        application_name,
+                        seconds_needed,
+                        initial_progress,
+                        client.clone(),
+                        app_handle.clone(),
+                        cancel_rx,
+                    )
+                    .await
+                }
+                "stream" => {
+                    cdp_quest::complete_stream_quest_via_cdp(
+                        cdp_port,
+                        quest_id.clone(),
+                        application_id,
+                        seconds_needed,
+                        initial_progress,
+                        client.clone(),
+                        app_handle.clone(),
+                        cancel_rx,
+                    )
+                    .await
+                }
+                "video" => {
+                    cdp_quest::complete_video_quest_via_cdp(
+                        cdp_port,
+                        quest_id.clone(),
+                        seconds_needed,
+                        initial_progress,
+                        app_handle.clone(),
+                        cancel_rx,
+                    )
+                    .await
+                }
+                "activity" => {
+                    cdp_quest::complete_activity_quest_via_cdp(
+                        cdp_port,
+                        quest_id.clone(),
+                        checkpoint_times,
+                        app_handle.clone(),
+                        cancel_rx,
+                    )
+                    .await
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Unknown CDP quest type: {}",
+                    quest_type_clone
+                )),
             }
-            _ => Err(anyhow::anyhow!(
-                "Unknown CDP quest type: {}",
-                quest_type_clone
-            )),
         };
+        let result = quest_completer::with_time_limit(&quest_id, limit, &app_handle, run).await;
+        if let Ok(quest_completer::QuestOutcome::TimedOut) = result {
+            cdp_quest::stop_timed_out_quest(cdp_port).await;
+        }
 
-        if let Err(e) = result {
-            let _ = app_handle.emit("quest-error", format!("CDP quest failed: {:#}", e));
+        match result {
+            Ok(quest_completer::QuestOutcome::Completed) => {
+                let policy = on_complete.unwrap_or_default();
+                match client {
+                    Some(client) => {
+                        continue_after_completion(client, &quest_id, policy, app_handle).await
+                    }
+                    None if policy != CompletionPolicy::Stop => {
+                        quest_completer::emit_event(
+                            &app_handle,
+                            "quest-warning",
+                            "Not logged in, so the completion policy was skipped",
+                        );
+                    }
+                    None => {}
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                quest_completer::emit_event(
+                    &app_handle,
+                    "quest-error",
+                    format!("CDP quest failed: {:#}", e),
+                );
+            }
         }
     });
 
     Ok(())
 }
 
-/// Stop current quest
-#[tauri::command]
-async fn stop_quest(state: State<'_, AppState>) -> Result<(), String> {
+/// Whether the active quest's task is still running
+fn quest_running(quest_state: &Option<QuestState>) -> bool {
+    // The cancel channel closes once the running quest's task has ended
+    quest_state
+        .as_ref()
+        .is_some_and(|quest| !quest.cancel_flag.is_closed())
+}
+
+/// Make `job` the active quest and run it in the background via `run_http_quest_chain`
+///
+/// With `replace` a running quest is stopped first; without it nothing is started while
+/// another quest is still running. The check and the claim happen under one lock, so a
+/// quest started in the meantime is never overwritten.
+async fn start_http_quest(
+    state: &AppState,
+    client: DiscordApiClient,
+    job: quest_completer::HttpQuestJob,
+    options: quest_completer::RunOptions,
+    policy: CompletionPolicy,
+    app_handle: tauri::AppHandle,
+    replace: bool,
+) -> Result<(), String> {
+    check_account_not_flagged(state)?;
+
+    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel::<()>(1);
+    let previous = {
+        let mut quest_state = state.quest_state.lock().unwrap();
+        if !replace && quest_running(&quest_state) {
+            return Err("Another quest is running".to_string());
+        }
+        quest_state.replace(QuestState {
+            quest_id: job.quest_id.clone(),
+            cancel_flag: cancel_tx,
+        })
+    };
+    if let Some(previous) = previous {
+        let _ = previous.cancel_flag.send(()).await;
+    }
+
+    tokio::spawn(run_http_quest_chain(
+        client, job, options, policy, app_handle, cancel_rx,
+    ));
+
+    Ok(())
+}
+
+/// Run an HTTP-driven quest, then follow the completion policy
+///
+/// With `ClaimAndNext` this keeps going with the next eligible quest until none are
+/// left, the user stops it, or another quest is started in the meantime. Dry runs
+/// never claim rewards, so the policy is ignored for them.
+async fn run_http_quest_chain(
+    client: DiscordApiClient,
+    mut job: quest_completer::HttpQuestJob,
+    options: quest_completer::RunOptions,
+    policy: CompletionPolicy,
+    app_handle: tauri::AppHandle,
+    mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
+) {
+    loop {
+        let result =
+            quest_completer::run_http_job(&client, &job, options, &app_handle, cancel_rx).await;
+
+        match result {
+            Ok(quest_completer::QuestOutcome::Completed) => {}
+            Ok(
+                quest_completer::QuestOutcome::Cancelled | quest_completer::QuestOutcome::TimedOut,
+            ) => return,
+            Err(e) => {
+                quest_completer::emit_event(
+                    &app_handle,
+                    "quest-error",
+                    format!("{} failed: {}", job.kind.label(), e),
+                );
+                return;
+            }
+        }
+
+        if options.dry_run {
+            return;
+        }
+
+        let Some((next, next_rx)) =
+            next_in_chain(&client, &job.quest_id, policy, &app_handle).await
+        else {
+            return;
+        };
+        cancel_rx = next_rx;
+        job = next;
+    }
+}
+
+/// Apply `policy` to a completed quest and, for `ClaimAndNext`, make the next quest active
+///
+/// Returns the next job with its cancel receiver, or `None` when the chain ends here.
+async fn next_in_chain(
+    client: &DiscordApiClient,
+    quest_id: &str,
+    policy: CompletionPolicy,
+    app_handle: &tauri::AppHandle,
+) -> Option<(
+    quest_completer::HttpQuestJob,
+    tokio::sync::mpsc::Receiver<()>,
+)> {
+    let next =
+        quest_completer::apply_completion_policy(client, quest_id, policy, app_handle).await?;
+
+    // Only continue if this chain is still the active quest (not stopped or replaced)
+    let next_rx = hand_off_quest_state(app_handle, quest_id, &next.quest_id)?;
+
+    quest_completer::emit_event(app_handle, "quest-next", &next.quest_id);
+    Some((next, next_rx))
+}
+
+/// Follow the completion policy after a stream or CDP quest completed
+///
+/// Those flows don't run in `run_http_quest_chain`, so a `ClaimAndNext` chain continues
+/// there from the next quest, with default pacing.
+async fn continue_after_completion(
+    client: DiscordApiClient,
+    quest_id: &str,
+    policy: CompletionPolicy,
+    app_handle: tauri::AppHandle,
+) {
+    if let Some((next, next_rx)) = next_in_chain(&client, quest_id, policy, &app_handle).await {
+        run_http_quest_chain(
+            client,
+            next,
+            Default::default(),
+            policy,
+            app_handle,
+            next_rx,
+        )
+        .await;
+    }
+}
+
+/// Move the active quest state from `current_id` to `next_id`
+///
+/// Returns the cancel receiver for the next quest, or `None` if `current_id` is no
+/// longer the active quest (it was stopped or another quest was started).
+fn hand_off_quest_state(
+    app_handle: &tauri::AppHandle,
+    current_id: &str,
+    next_id: &str,
+) -> Option<tokio::sync::mpsc::Receiver<()>> {
+    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel::<()>(1);
+    let state = app_handle.state::<AppState>();
+    let mut quest_state = state.quest_state.lock().unwrap();
+    match quest_state.as_ref() {
+        Some(active) if active.quest_id == current_id => {}
+        _ => return None,
+    }
+    *quest_state = Some(QuestState {
+        quest_id: next_id.to_string(),
+        cancel_flag: cancel_tx,
+    });
+    Some(cancel_rx)
+}
+
+/// How often the schedule is checked for due quests
+const SCHEDULER_TICK_SECS: u64 = 15;
+
+/// Schedule a quest to start at `start_at_unix` (seconds); kept across restarts
+///
+/// `mode` is `video`, `game` or `stream` and defaults to whatever task the quest has.
+#[tauri::command]
+async fn schedule_quest(
+    quest_id: String,
+    mode: Option<String>,
+    start_at_unix: i64,
+    speed_multiplier: Option<f64>,
+    heartbeat_interval: Option<u64>,
+    app_handle: tauri::AppHandle,
+) -> Result<scheduler::ScheduledQuest, String> {
+    let entry = scheduler::ScheduledQuest::new(
+        quest_id,
+        mode,
+        start_at_unix,
+        speed_multiplier,
+        heartbeat_interval,
+    )
+    .map_err(|e| e.to_string())?;
+    scheduler::add(&app_handle, entry.clone()).map_err(|e| format!("{:#}", e))?;
+    Ok(entry)
+}
+
+/// Scheduled quests, soonest first
+#[tauri::command]
+async fn list_scheduled_quests(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<scheduler::ScheduledQuest>, String> {
+    scheduler::list(&app_handle).map_err(|e| format!("{:#}", e))
+}
+
+/// Cancel a scheduled quest; returns whether it was still pending
+#[tauri::command]
+async fn cancel_scheduled_quest(id: String, app_handle: tauri::AppHandle) -> Result<bool, String> {
+    scheduler::remove(&app_handle, &id).map_err(|e| format!("{:#}", e))
+}
+
+/// Built-in and saved quest profiles
+#[tauri::command]
+async fn list_quest_profiles(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<quest_profiles::QuestProfile>, String> {
+    quest_profiles::list(&app_handle).map_err(|e| format!("{:#}", e))
+}
+
+/// Save the current start settings under `name`, replacing a profile with that name
+#[tauri::command]
+async fn save_quest_profile(
+    name: String,
+    settings: quest_profiles::QuestProfileSettings,
+    app_handle: tauri::AppHandle,
+) -> Result<quest_profiles::QuestProfile, String> {
+    quest_profiles::save(&app_handle, &name, settings).map_err(|e| format!("{:#}", e))
+}
+
+/// Settings of profile `name`, for prefilling the start commands
+#[tauri::command]
+async fn apply_quest_profile(
+    name: String,
+    app_handle: tauri::AppHandle,
+) -> Result<quest_profiles::QuestProfileSettings, String> {
+    quest_profiles::get(&app_handle, &name)
+        .map(|profile| profile.settings)
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Delete a saved profile; returns whether it existed
+#[tauri::command]
+async fn delete_quest_profile(name: String, app_handle: tauri::AppHandle) -> Result<bool, String> {
+    quest_profiles::remove(&app_handle, &name).map_err(|e| format!("{:#}", e))
+}
+
+/// Start due scheduled quests
+///
+/// A due quest waits until the user is logged in and no other quest is running, then
+/// starts like `start_video_quest` / `start_game_heartbeat_quest` / `start_stream_quest`
+/// would and emits `quest-scheduled-start` (or `quest-error` if it can't be started).
+async fn scheduler_loop(app_handle: tauri::AppHandle) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(SCHEDULER_TICK_SECS)).await;
+
+        let entry = match scheduler::next_due(&app_handle, chrono::Utc::now().timestamp()) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(e) => {
+                log(
+                    LogLevel::Debug,
+                    LogCategory::Quest,
+                    "Failed to read quest schedule",
+                    Some(&format!("{:#}", e)),
+                );
+                continue;
+            }
+        };
+
+        let (client, busy, flagged) = {
+            let state = app_handle.state::<AppState>();
+            let client = state.client.lock().unwrap().clone();
+            let busy = quest_running(&state.quest_state.lock().unwrap());
+            let flagged = *state.account_flagged.lock().unwrap();
+            (client, busy, flagged)
+        };
+        let Some(client) = client else {
+            continue;
+        };
+        // Due entries stay scheduled until the user logs in again
+        if busy || flagged {
+            continue;
+        }
+
+        if let Err(e) = scheduler::remove(&app_handle, &entry.id) {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                "Failed to remove scheduled quest, not starting it",
+                Some(&format!("{:#}", e)),
+            );
+            continue;
+        }
+
+        log(
+            LogLevel::Info,
+            LogCategory::Quest,
+            &format!("Starting scheduled quest {}", entry.quest_id),
+            None,
+        );
+        match start_scheduled_quest(client, &entry, &app_handle).await {
+            Ok(()) => {
+                quest_completer::emit_event(&app_handle, "quest-scheduled-start", &entry);
+            }
+            Err(e) => {
+                quest_completer::emit_event(
+                    &app_handle,
+                    "quest-error",
+                    format!("Scheduled quest {} failed to start: {}", entry.quest_id, e),
+                );
+            }
+        }
+    }
+}
+
+async fn start_scheduled_quest(
+    client: DiscordApiClient,
+    entry: &scheduler::ScheduledQuest,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    check_default_mode_gate(&state, app_handle)?;
+
+    let quests = client
+        .get_quests_raw()
+        .await
+        .map_err(|e| format!("Failed to fetch quests: {}", e))?;
+    let candidate =
+        quest_completer::find_quest_candidate(&quests, &entry.quest_id).ok_or_else(|| {
+            let mobile_only =
+                quest_completer::find_quest(&quests, &entry.quest_id).is_some_and(|quest| {
+                    quest_completer::has_mobile_video_task(&quest_completer::quest_plans(quest))
+                });
+            if mobile_only {
+                quest_completer::MOBILE_VIDEO_UNSUPPORTED.to_string()
+            } else {
+                "Quest is completed, expired or has no supported task".to_string()
+            }
+        })?;
+    if let Some(mode) = &entry.mode {
+        if candidate.job.kind.mode() != mode {
+            return Err(format!("Quest has no {} task", mode));
+        }
+    }
+    quest_completer::ensure_enrolled(&client, &candidate)
+        .await
+        .map_err(|e| format!("Failed to enroll: {}", e))?;
+
+    let defaults = quest_completer::VideoSettings::default();
+    let video = quest_completer::VideoSettings {
+        speed_multiplier: entry.speed_multiplier.unwrap_or(defaults.speed_multiplier),
+        heartbeat_interval: entry
+            .heartbeat_interval
+            .unwrap_or(defaults.heartbeat_interval),
+        ..defaults
+    };
+    let options = quest_completer::RunOptions {
+        video,
+        ..Default::default()
+    };
+
+    // The user may have started a quest while this one was being fetched and enrolled
+    start_http_quest(
+        &state,
+        client,
+        candidate.job,
+        options,
+        CompletionPolicy::default(),
+        app_handle.clone(),
+        false,
+    )
+    .await
+}
+
+/// Progress of a `complete_all_quests` batch
+#[derive(Clone, serde::Serialize)]
+struct BatchProgress {
+    /// Zero-based position of the quest being started
+    index: usize,
+    total: usize,
+    quest_id: String,
+}
+
+/// Run every eligible quest in `/quests/@me` one after another
+///
+/// Enrolls quests that aren't accepted yet, emits `quest-batch-progress` before each
+/// quest, and returns the number of queued quests. Stopping the current quest stops the
+/// whole batch.
+#[tauri::command]
+async fn complete_all_quests(
+    speed_multiplier: Option<f64>,
+    heartbeat_interval: Option<u64>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize, String> {
+    check_account_not_flagged(&state)?;
+    check_default_mode_gate(&state, &app_handle)?;
+
+    // Stop current quest (if any)
+    stop_quest_internal(&state).await;
+
+    let client = {
+        let guard = state.client.lock().unwrap();
+        guard
+            .as_ref()
+            .ok_or_else(|| "Not logged in".to_string())?
+            .clone()
+    };
+
+    let quests = client
+        .get_quests_raw()
+        .await
+        .map_err(|e| format!("Failed to fetch quests: {}", e))?;
+    let batch = quest_completer::find_batch_quests(&quests);
+    if batch.is_empty() {
+        return Err("No eligible quests to complete".to_string());
+    }
+
+    // Create cancel channel for the first quest; later quests get theirs on hand-off
+    let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel::<()>(1);
+    *state.quest_state.lock().unwrap() = Some(QuestState {
+        quest_id: batch[0].job.quest_id.clone(),
+        cancel_flag: cancel_tx,
+    });
+
+    let defaults = quest_completer::VideoSettings::default();
+    let video = quest_completer::VideoSettings {
+        speed_multiplier: speed_multiplier.unwrap_or(defaults.speed_multiplier),
+        heartbeat_interval: heartbeat_interval.unwrap_or(defaults.heartbeat_interval),
+        ..defaults
+    };
+
+    let total = batch.len();
+    tokio::spawn(run_quest_batch(client, batch, video, app_handle, cancel_rx));
+
+    Ok(total)
+}
+
+async fn run_quest_batch(
+    client: DiscordApiClient,
+    batch: Vec<quest_completer::HttpQuestCandidate>,
+    video: quest_completer::VideoSettings,
+    app_handle: tauri::AppHandle,
+    cancel_rx: tokio::sync::mpsc::Receiver<()>,
+) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let total = batch.len();
+    let mut first_rx = Some(cancel_rx);
+    for (index, candidate) in batch.iter().enumerate() {
+        let quest_id = &candidate.job.quest_id;
+        let cancel_rx = match first_rx.take() {
+            Some(rx) => rx,
+            None => {
+                let previous = &batch[index - 1].job.quest_id;
+                match hand_off_quest_state(&app_handle, previous, quest_id) {
+                    Some(rx) => rx,
+                    None => return,
+                }
+            }
+        };
+
+        quest_completer::emit_event(
+            &app_handle,
+            "quest-batch-progress",
+            BatchProgress {
+                index,
+                total,
+                quest_id: quest_id.clone(),
+            },
+        );
+
+        if let Err(e) = quest_completer::ensure_enrolled(&client, candidate).await {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                &format!("Batch: skipping quest {} (enroll failed)", quest_id),
+                Some(&e.to_string()),
+            );
+            continue;
+        }
+
+        let result = quest_completer::run_http_job(
+            &client,
+            &candidate.job,
+            quest_completer::RunOptions {
+                video,
+                ..Default::default()
+            },
+            &app_handle,
+            cancel_rx,
+        )
+        .await;
+
+        match result {
+            // A timed-out quest was already reported; carry on with the rest
+            Ok(
+                quest_completer::QuestOutcome::Completed | quest_completer::QuestOutcome::TimedOut,
+            ) => {}
+            Ok(quest_completer::QuestOutcome::Cancelled) => return,
+            Err(e) => {
+                log(
+                    LogLevel::Warn,
+                    LogCategory::Quest,
+                    &format!("Batch: {} {} failed", candidate.job.kind.label(), quest_id),
+                    Some(&e.to_string()),
+                );
+                quest_completer::emit_event(
+                    &app_handle,
+                    "quest-error",
+                    format!("{} failed: {}", candidate.job.kind.label(), e),
+                );
+            }
+        }
+    }
+}
+
+/// List interrupted quests that can be resumed from their saved progress
+///
+/// Drops saved entries for quests Discord now reports as completed or expired. The frontend
+/// restarts a quest by passing the returned `initial_progress` to its start command.
+#[tauri::command]
+async fn resume_quests(
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<quest_completer::HttpQuestJob>, String> {
+    check_account_not_flagged(&state)?;
+
+    let saved = progress_store::load(&app_handle);
+    if saved.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = {
+        let guard = state.client.lock().unwrap();
+        guard
+            .as_ref()
+            .ok_or_else(|| "Not logged in".to_string())?
+            .clone()
+    };
+
+    let quests = client
+        .get_quests_raw()
+        .await
+        .map_err(|e| format!("Failed to fetch quests: {}", e))?;
+
+    let (resumable, stale) = quest_completer::match_saved_progress(&saved, &quests);
+    for quest_id in &stale {
+        progress_store::clear(&app_handle, quest_id);
+    }
+
+    Ok(resumable)
+}
+
+/// Stop current quest
+#[tauri::command]
+async fn stop_quest(state: State<'_, AppState>) -> Result<(), String> {
     stop_quest_internal(&state).await;
     Ok(())
 }
 
-async fn stop_quest_internal(state: &State<'_, AppState>) {
+/// Cancel the running quest, returning whether there was one
+async fn stop_quest_internal(state: &State<'_, AppState>) -> bool {
     let quest = {
         let mut quest_state = state.quest_state.lock().unwrap();
         quest_state.take()
     };
 
-    if let Some(quest) = quest {
-        let _ = quest.cancel_flag.send(()).await;
-        println!("Quest stopped");
+    match quest {
+        Some(quest) => {
+            let _ = quest.cancel_flag.send(()).await;
+            println!("Quest stopped");
+            true
+        }
+        None => false,
+    }
+}
+
+/// What `emergency_stop` shut down
+#[derive(Clone, serde::Serialize)]
+struct EmergencyStopReport {
+    quest_stopped: bool,
+    games_stopped: usize,
+    rpc_disconnected: bool,
+}
+
+async fn stop_everything(
+    app_handle: &tauri::AppHandle,
+    state: &State<'_, AppState>,
+) -> EmergencyStopReport {
+    let quest_stopped = stop_quest_internal(state).await;
+
+    // Also aborts an RPC connection that is still being set up
+    quest_completer::emit_event(app_handle, "event_disconnect", ());
+    let rpc_disconnected = disconnect_discord_rpc();
+
+    // Quitting runners waits on each process, so keep it off the async workers
+    let games_stopped = tokio::task::spawn_blocking(game_simulator::stop_all_simulated_games)
+        .await
+        .unwrap_or(0);
+
+    EmergencyStopReport {
+        quest_stopped,
+        games_stopped,
+        rpc_disconnected,
     }
 }
 
+/// Stop everything as soon as Discord reports a problem with the logged-in account
+///
+/// Runs for the app's lifetime; emits `account-flagged` with Discord's reason after
+/// stopping quests, RPC and simulated games. No quest starts again, scheduled ones
+/// included, until the user logs in again.
+async fn account_flag_watch(app_handle: tauri::AppHandle) {
+    use crate::logger::{log, LogCategory, LogLevel};
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut flags = discord_api::subscribe_account_flags();
+    loop {
+        let flag = match flags.recv().await {
+            Ok(flag) => flag,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return,
+        };
+
+        let state = app_handle.state::<AppState>();
+        // Latch first so nothing queued starts again while everything is being stopped
+        *state.account_flagged.lock().unwrap() = true;
+        let report = stop_everything(&app_handle, &state).await;
+        log(
+            LogLevel::Warn,
+            LogCategory::Quest,
+            &format!("Account flagged ({}), stopped all activity", flag.reason),
+            Some(&format!(
+                "quest_stopped={}, games_stopped={}, rpc_disconnected={}",
+                report.quest_stopped, report.games_stopped, report.rpc_disconnected
+            )),
+        );
+        quest_completer::emit_event(&app_handle, "account-flagged", flag);
+    }
+}
+
+/// Stop the running quest, disconnect RPC and kill every simulated game in one go
+///
+/// Safe to call when nothing is running. Emits `emergency-stopped` with the report.
+#[tauri::command]
+async fn emergency_stop(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<EmergencyStopReport, String> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let report = stop_everything(&app_handle, &state).await;
+    log(
+        LogLevel::Info,
+        LogCategory::Quest,
+        "Emergency stop",
+        Some(&format!(
+            "quest_stopped={}, games_stopped={}, rpc_disconnected={}",
+            report.quest_stopped, report.games_stopped, report.rpc_disconnected
+        )),
+    );
+    quest_completer::emit_event(&app_handle, "emergency-stopped", report.clone());
+    Ok(report)
+}
+
+/// Accept a quest through the running Discord client via CDP (no token needed)
+#[tauri::command]
+async fn accept_quest_cdp(
+    quest_id: String,
+    cdp_port: Option<u16>,
+) -> Result<serde_json::Value, AppError> {
+    let port = cdp_port.unwrap_or_else(cdp_client::default_port);
+    cdp_quest::accept_quest_via_cdp(port, &quest_id)
+        .await
+        .map_err(|e| AppError::CdpUnavailable(format!("{:#}", e)))
+}
+
+/// Send a game heartbeat through the running Discord client via CDP (no token needed)
+///
+/// Returns whether Discord reports the quest as completed.
+#[tauri::command]
+async fn send_heartbeat_cdp(
+    quest_id: String,
+    application_id: String,
+    terminal: bool,
+    cdp_port: Option<u16>,
+) -> Result<bool, AppError> {
+    let port = cdp_port.unwrap_or_else(cdp_client::default_port);
+    cdp_quest::send_heartbeat_via_cdp(port, &quest_id, &application_id, terminal)
+        .await
+        .map_err(|e| AppError::CdpUnavailable(format!("{:#}", e)))
+}
+
 /// Navigate Discord client SPA to a specific path (no reload)
 #[tauri::command]
-async fn navigate_discord_spa(target_path: String, cdp_port: u16) -> Result<(), String> {
-    cdp_quest::navigate_discord_spa(cdp_port, &target_path)
+async fn navigate_discord_spa(target_path: String, cdp_port: Option<u16>) -> Result<(), String> {
+    let port = cdp_port.unwrap_or_else(cdp_client::default_port);
+    cdp_quest::navigate_discord_spa(port, &target_path)
         .await
         .map_err(|e| format!("Failed to navigate Discord SPA: {}", e))
 }
 
-/// Create simulated game
+/// Create simulated game. Without `executable_name` the one Discord expects for `app_id`
+/// on this platform is used; returns the executable name that was created
 #[tauri::command]
 async fn create_simulated_game(
     path: String,
-    executable_name: String,
+    executable_name: Option<String>,
     app_id: String,
-) -> Result<(), String> {
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let executable_name = match executable_name.filter(|name| !name.trim().is_empty()) {
+        Some(name) => name,
+        None => resolve_game_executable(&state, &app_id, None).await?,
+    };
     game_simulator::create_simulated_game(&path, &executable_name, &app_id)
-        .map_err(|e| format!("Failed to create simulated game: {}", e))
+        .context("Failed to create simulated game")?;
+    Ok(executable_name)
 }
 
 /// Run simulated game
+///
+/// With `seconds_remaining` (the quest time left) the runner exits by itself shortly
+/// after the quest could have finished, so it can't be left behind if the app crashes.
 #[tauri::command]
 async fn run_simulated_game(
     name: String,
     path: String,
     executable_name: String,
     app_id: String,
+    seconds_remaining: Option<u64>,
 ) -> Result<(), String> {
-    game_simulator::run_simulated_game(&name, &path, &executable_name, &app_id)
-        .map_err(|e| format!("Failed to run simulated game: {}", e))
+    let ttl_secs = seconds_remaining.map(game_simulator::runner_ttl_secs);
+    // Waiting for the runner to report ready blocks, so keep it off the async workers
+    tokio::task::spawn_blocking(move || {
+        game_simulator::run_simulated_game(&name, &path, &executable_name, &app_id, ttl_secs)
+    })
+    .await
+    .map_err(|e| format!("Failed to run simulated game: {}", e))?
+    .map_err(|e| format!("Failed to run simulated game: {}", e))
+}
+
+/// Whether Discord currently reports `application_id` as a running game
+///
+/// For the UI to poll after `run_simulated_game`, so the user sees the game was picked
+/// up before starting a heartbeat quest. Polls share one long-lived gateway session
+/// and answer from the sessions it has seen; `timeout_secs` (default 8) bounds the
+/// wait for the game to show up.
+#[tauri::command]
+async fn is_game_detected(
+    application_id: String,
+    timeout_secs: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let client = current_client(&state)?;
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(8).clamp(1, 30));
+
+    let watch = {
+        let mut guard = state.game_watch.lock().unwrap();
+        match guard.as_ref() {
+            Some(watch) if !watch.is_finished() => watch.clone(),
+            _ => guard
+                .insert(Arc::new(client.connect_gateway_presence()))
+                .clone(),
+        }
+    };
+
+    Ok(watch.wait_for_application(&application_id, timeout).await)
 }
 
 /// Stop simulated game
 #[tauri::command]
 async fn stop_simulated_game(exec_name: String) -> Result<(), String> {
-    game_simulator::stop_simulated_game(&exec_name)
+    tokio::task::spawn_blocking(move || game_simulator::stop_simulated_game(&exec_name))
+        .await
+        .map_err(|e| format!("Failed to stop simulated game: {}", e))?
         .map_err(|e| format!("Failed to stop simulated game: {}", e))
 }
 
+/// Stop every running simulated game, returning how many were stopped
+#[tauri::command]
+async fn stop_all_simulated_games() -> Result<usize, String> {
+    tokio::task::spawn_blocking(game_simulator::stop_all_simulated_games)
+        .await
+        .map_err(|e| format!("Failed to stop simulated games: {}", e))
+}
+
 /// Get detectable games list (works with or without login)
+///
+/// The merged list is cached for `ttl_secs` (default one hour); `force` refetches it.
+#[tauri::command]
+async fn fetch_detectable_games(
+    force: Option<bool>,
+    ttl_secs: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DetectableGame>, String> {
+    load_detectable_games(&state, force.unwrap_or(false), ttl_secs).await
+}
+
+/// What `refresh_detectable_games` found
+#[derive(Clone, serde::Serialize)]
+struct DetectableGamesRefresh {
+    total: usize,
+    /// Entries not in the previously fetched list (everything, if there was none)
+    new_entries: usize,
+    /// Entries only the Canary endpoint listed
+    canary_only: usize,
+}
+
+/// Refetch the detectable games list now, bypassing the cache, and merge in entries
+/// the Canary endpoint lists but stable doesn't yet (for newly released games)
+#[tauri::command]
+async fn refresh_detectable_games(
+    state: State<'_, AppState>,
+) -> Result<DetectableGamesRefresh, String> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let previous_ids: std::collections::HashSet<String> =
+        discord_api::cached_detectable_games(std::time::Duration::MAX)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|game| game.id)
+            .collect();
+
+    let fetched = fetch_detectable_games_uncached(&state).await?;
+    let mut games = fetched.games;
+
+    // Canary is best effort; the stable list alone is still a useful refresh
+    let canary = match fetch_canary_detectable_games(&state).await {
+        Ok(canary) => canary,
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::General,
+                "Canary detectable games fetch failed",
+                Some(&e),
+            );
+            Vec::new()
+        }
+    };
+    let canary_only = discord_api::merge_detectable_games(&mut games, canary);
+    discord_api::cache_detectable_games(&games, fetched.complete);
+
+    let new_entries = games
+        .iter()
+        .filter(|game| !previous_ids.contains(&game.id))
+        .count();
+    log(
+        LogLevel::Info,
+        LogCategory::General,
+        &format!(
+            "Refreshed detectable games: {} total, {} new, {} from Canary only",
+            games.len(),
+            new_entries,
+            canary_only
+        ),
+        None,
+    );
+    Ok(DetectableGamesRefresh {
+        total: games.len(),
+        new_entries,
+        canary_only,
+    })
+}
+
+/// Search the (cached) detectable games list by name or alias, best matches first
+#[tauri::command]
+async fn search_detectable_games(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DetectableGame>, String> {
+    let games = load_detectable_games(&state, false, None).await?;
+    Ok(discord_api::search_detectable_games(
+        &games,
+        &query,
+        limit.unwrap_or(50),
+    ))
+}
+
+/// Executable name Discord expects for a detectable game, for `os` (`win32`, `darwin`,
+/// `linux`) or the current platform. Games without one fail with `NoExecutableForOs`,
+/// which points the UI at `start_game_heartbeat_quest`
 #[tauri::command]
-async fn fetch_detectable_games(state: State<'_, AppState>) -> Result<Vec<DetectableGame>, String> {
+async fn get_game_executable(
+    app_id: String,
+    os: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    resolve_game_executable(&state, &app_id, os.as_deref()).await
+}
+
+async fn resolve_game_executable(
+    state: &State<'_, AppState>,
+    app_id: &str,
+    os: Option<&str>,
+) -> Result<String, AppError> {
+    let os = os.unwrap_or_else(|| super_properties::Os::current().detectable_os());
+    let games = load_detectable_games(state, false, None)
+        .await
+        .map_err(AppError::Other)?;
+    discord_api::game_executable_name(&games, app_id, os)
+}
+
+async fn load_detectable_games(
+    state: &State<'_, AppState>,
+    force: bool,
+    ttl_secs: Option<u64>,
+) -> Result<Vec<DetectableGame>, String> {
+    let ttl = ttl_secs
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(discord_api::DETECTABLE_GAMES_TTL);
+    if !force {
+        if let Some(games) = discord_api::cached_detectable_games(ttl) {
+            return Ok(games);
+        }
+    }
+
+    let fetched = fetch_detectable_games_uncached(state).await?;
+    discord_api::cache_detectable_games(&fetched.games, fetched.complete);
+    Ok(fetched.games)
+}
+
+async fn fetch_detectable_games_uncached(
+    state: &State<'_, AppState>,
+) -> Result<discord_api::DetectableGamesFetch, String> {
     // Use the authenticated client when available (carries auth headers + super-properties).
     // When not logged in, fall back to a plain public HTTP request — the detectable-games
     // endpoints require no authentication.
@@ -623,45 +1844,76 @@ async fn fetch_detectable_games(state: State<'_, AppState>) -> Result<Vec<Detect
     }
 
     // ── Unauthenticated fallback ──────────────────────────────────────────
-    let http = reqwest::Client::builder()
-        .user_agent(super_properties::discord_user_agent(super_properties::DEFAULT_CLIENT_VERSION))
-        .connect_timeout(std::time::Duration::from_secs(8))
-        .timeout(std::time::Duration::from_secs(20))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let http = public_http_client(state)?;
 
-    const API_BASE: &str = "https://discord.com/api/v9";
-    let games_url = format!("{}/applications/detectable", API_BASE);
-    let apps_url = format!("{}/applications/non-games/detectable", API_BASE);
+    let games_url = endpoints::detectable_games();
+    let apps_url = endpoints::detectable_non_games();
 
     let (games_res, apps_res) =
         tokio::join!(http.get(&games_url).send(), http.get(&apps_url).send());
 
     let mut all_items: Vec<DetectableGame> = Vec::new();
+    let mut complete = true;
 
-    if let Ok(resp) = games_res {
-        if resp.status().is_success() {
-            if let Ok(mut list) = resp.json::<Vec<DetectableGame>>().await {
-                for g in &mut list {
-                    g.type_name = Some("Game".to_string());
+    for (res, type_name) in [(games_res, "Game"), (apps_res, "App")] {
+        let list = match res {
+            Ok(resp) if resp.status().is_success() => resp.json::<Vec<DetectableGame>>().await.ok(),
+            _ => None,
+        };
+        match list {
+            Some(mut list) => {
+                for item in &mut list {
+                    item.type_name = Some(type_name.to_string());
                 }
                 all_items.extend(list);
             }
+            None => complete = false,
         }
     }
 
-    if let Ok(resp) = apps_res {
-        if resp.status().is_success() {
-            if let Ok(mut list) = resp.json::<Vec<DetectableGame>>().await {
-                for a in &mut list {
-                    a.type_name = Some("App".to_string());
-                }
-                all_items.extend(list);
-            }
-        }
+    Ok(discord_api::DetectableGamesFetch {
+        games: all_items,
+        complete,
+    })
+}
+
+/// Plain client for the public detectable-games endpoints, through the configured proxy
+fn public_http_client(state: &AppState) -> Result<reqwest::Client, String> {
+    // Same User-Agent the logged-in client sends, tracking the fetched client version
+    let user_agent = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_user_agent_string();
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(std::time::Duration::from_secs(8))
+        .timeout(std::time::Duration::from_secs(20));
+    if let Some(proxy_url) = state.proxy_url.lock().unwrap().as_deref() {
+        builder =
+            builder.proxy(discord_api::parse_proxy_url(proxy_url).map_err(|e| format!("{:#}", e))?);
     }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
 
-    Ok(all_items)
+async fn fetch_canary_detectable_games(state: &AppState) -> Result<Vec<DetectableGame>, String> {
+    let response = public_http_client(state)?
+        .get(endpoints::canary_detectable_games())
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let mut games = response
+        .json::<Vec<DetectableGame>>()
+        .await
+        .map_err(|e| format!("Invalid response: {}", e))?;
+    for game in &mut games {
+        game.type_name = Some("Game".to_string());
+    }
+    Ok(games)
 }
 
 /// Accept quest
@@ -669,39 +1921,73 @@ async fn fetch_detectable_games(state: State<'_, AppState>) -> Result<Vec<Detect
 async fn accept_quest(
     quest_id: String,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let client = {
-        let guard = state.client.lock().unwrap();
-        guard
-            .as_ref()
-            .ok_or_else(|| "Not logged in".to_string())?
-            .clone()
-    };
+) -> Result<serde_json::Value, AppError> {
+    let client = current_client(&state)?;
+
+    let result = client
+        .accept_quest(&quest_id)
+        .await
+        .context("Failed to accept quest")?;
+
+    Ok(result)
+}
+
+/// Leave an accepted quest; completed quests are refused
+#[tauri::command]
+async fn abandon_quest(quest_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let client = current_client(&state)?;
+    Ok(client.abandon_quest(&quest_id).await?)
+}
+
+#[tauri::command]
+async fn get_virtual_currency_balance(
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, AppError> {
+    let client = current_client(&state)?;
+
+    Ok(client
+        .get_virtual_currency_balance()
+        .await
+        .context("Failed to get virtual currency balance")?)
+}
 
-    let result = client
-        .accept_quest(&quest_id)
+/// Check the full request path with a read-only `GET /users/@me`
+#[tauri::command]
+async fn test_connection(
+    state: State<'_, AppState>,
+) -> Result<discord_api::ConnectionReport, AppError> {
+    let client = current_client(&state)?;
+
+    Ok(client
+        .test_connection()
         .await
-        .map_err(|e| format!("Failed to accept quest: {}", e))?;
+        .context("Connection test failed")?)
+}
 
-    Ok(result)
+/// Summary of what the app did this session (quests, heartbeats, API errors, uptime)
+#[tauri::command]
+fn get_session_stats() -> metrics::SessionStats {
+    metrics::session_stats()
 }
 
+/// Get request latency per Discord API endpoint
 #[tauri::command]
-async fn get_virtual_currency_balance(
+fn get_api_stats(state: State<'_, AppState>) -> Result<Vec<discord_api::EndpointStats>, String> {
+    let guard = state.client.lock().unwrap();
+    let client = guard.as_ref().ok_or_else(|| "Not logged in".to_string())?;
+
+    Ok(client.get_api_stats())
+}
+
+/// Get the tightest Discord rate-limit bucket (None when nothing is limited)
+#[tauri::command]
+fn get_rate_limit_status(
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let client = {
-        let guard = state.client.lock().unwrap();
-        guard
-            .as_ref()
-            .ok_or_else(|| "Not logged in".to_string())?
-            .clone()
-    };
+) -> Result<Option<discord_api::RateLimitStatus>, String> {
+    let guard = state.client.lock().unwrap();
+    let client = guard.as_ref().ok_or_else(|| "Not logged in".to_string())?;
 
-    client
-        .get_virtual_currency_balance()
-        .await
-        .map_err(|e| format!("Failed to get virtual currency balance: {}", e))
+    Ok(client.get_rate_limit_status())
 }
 
 #[tauri::command]
@@ -748,19 +2034,13 @@ async fn claim_quest_reward(
     quest_id: String,
     platform: Option<String>,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let client = {
-        let guard = state.client.lock().unwrap();
-        guard
-            .as_ref()
-            .ok_or_else(|| "Not logged in".to_string())?
-            .clone()
-    };
+) -> Result<serde_json::Value, AppError> {
+    let client = current_client(&state)?;
 
-    client
+    Ok(client
         .claim_quest_reward(&quest_id, platform)
         .await
-        .map_err(|e| format!("Failed to claim quest reward: {}", e))
+        .context("Failed to claim quest reward")?)
 }
 
 mod rpc;
@@ -773,6 +2053,26 @@ fn get_discord_rpc_client() -> &'static Mutex<Option<rpc::Client>> {
     DISCORD_RPC_CLIENT.get_or_init(|| Mutex::new(None))
 }
 
+/// Drop the RPC client and disconnect it in the background, returning whether one
+/// was connected
+fn disconnect_discord_rpc() -> bool {
+    let client_option = {
+        let mut guard = get_discord_rpc_client().lock().unwrap();
+        guard.take()
+    };
+    match client_option {
+        Some(client) => {
+            // Fire-and-forget async disconnect
+            tauri::async_runtime::spawn(async move {
+                client.discord.disconnect().await;
+                println!("Discord RPC disconnected");
+            });
+            true
+        }
+        None => false,
+    }
+}
+
 #[tauri::command(rename_all = "snake_case")]
 fn connect_to_discord_rpc(handle: tauri::AppHandle, activity_json: String, action: String) {
     let _ = action;
@@ -878,6 +2178,12 @@ async fn open_in_explorer(path: String) -> Result<(), String> {
 ///
 /// This is the new entry point that replaces direct run() call
 pub fn ensure_stealth_and_run() {
+    // Headless mode completes one quest and exits; no GUI, stealth copy or tray
+    let args: Vec<String> = std::env::args().collect();
+    if headless::requested(&args) {
+        std::process::exit(headless::run(&args));
+    }
+
     // Try to enter stealth mode
     stealth::ensure_stealth_mode();
 
@@ -937,15 +2243,30 @@ pub fn ensure_stealth_and_run() {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Environment seeds the defaults; settings changed in the UI replace them
+    let env = env_config::EnvConfig::from_env();
+    env.apply_globals();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState {
             client: Mutex::new(None),
             quest_state: Mutex::new(None),
+            default_mode_gate: Mutex::new(DefaultModeGate::default()),
+            proxy_url: Mutex::new(env.proxy_url),
+            game_watch: Mutex::new(None),
+            account_flagged: Mutex::new(false),
         })
         .setup(|app| {
+            restore_file_logging(app.handle());
+            if let Ok(dir) = app.handle().path().app_log_dir() {
+                stealth::set_console_log_dir(&dir);
+            }
+            build_cache::restore(app.handle());
+
             // Set random window title in stealth mode
             if stealth::is_stealth_mode() {
                 if let Some(window) = app.get_webview_window("main") {
@@ -959,12 +2280,36 @@ pub fn run() {
                     }
                 }
             }
+
+            tauri::async_runtime::spawn(build_staleness_loop(app.handle().clone()));
+            tauri::async_runtime::spawn(scheduler_loop(app.handle().clone()));
+            tauri::async_runtime::spawn(account_flag_watch(app.handle().clone()));
+
+            #[cfg(feature = "metrics")]
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = metrics::serve().await {
+                    eprintln!("[Metrics] {:#}", e);
+                }
+            });
+
+            #[cfg(feature = "live-stream")]
+            {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = live_stream::start(handle).await {
+                        eprintln!("[LiveStream] Failed to start: {:#}", e);
+                    }
+                });
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             auto_detect_token,
+            list_discord_installations,
             set_token,
             get_quests,
+            get_quests_raw,
+            get_quest_plan,
             get_quests_full,
             start_video_quest,
             start_stream_quest,
@@ -973,9 +2318,16 @@ pub fn run() {
             stop_quest,
             create_simulated_game,
             run_simulated_game,
+            is_game_detected,
             stop_simulated_game,
+            stop_all_simulated_games,
+            emergency_stop,
             fetch_detectable_games,
+            refresh_detectable_games,
+            search_detectable_games,
+            get_game_executable,
             accept_quest,
+            abandon_quest,
             get_virtual_currency_balance,
             get_quest_decision_debug,
             get_quest_decisions_debug,
@@ -984,21 +2336,52 @@ pub fn run() {
             open_in_explorer,
             force_video_progress,
             export_logs,
+            export_logs_to_file,
+            set_min_log_level,
+            set_offline_mode,
+            get_offline_mode,
+            set_notifications_enabled,
+            set_file_logging,
+            get_file_logging_dir,
             get_debug_info,
             get_runner_info,
             check_cdp_status,
             fetch_super_properties_cdp,
+            fetch_current_user_cdp,
+            accept_quest_cdp,
+            send_heartbeat_cdp,
             is_discord_running,
+            detect_running_discord,
             launch_discord_cdp,
             restart_discord_cdp,
             install_discord_cdp_launcher,
             create_discord_cdp_launcher_shortcut,
             create_discord_debug_shortcut,
             get_super_properties_mode,
+            decode_super_properties,
+            get_live_stream_info,
             auto_fetch_super_properties,
             retry_super_properties,
+            refresh_client_headers,
+            set_super_properties_refresh,
             capture_discord_headers_cdp,
-            navigate_discord_spa
+            navigate_discord_spa,
+            get_default_mode_gate,
+            set_default_mode_gate,
+            set_proxy,
+            complete_all_quests,
+            schedule_quest,
+            list_scheduled_quests,
+            cancel_scheduled_quest,
+            list_quest_profiles,
+            save_quest_profile,
+            apply_quest_profile,
+            delete_quest_profile,
+            resume_quests,
+            get_rate_limit_status,
+            get_api_stats,
+            get_session_stats,
+            test_connection
         ])
         .on_window_event(|_window, event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event {
@@ -1008,19 +2391,7 @@ pub fn run() {
                 game_simulator::cleanup_all_simulated_games();
 
                 // Disconnect Discord RPC client (if connected)
-                {
-                    let client_option = {
-                        let mut guard = get_discord_rpc_client().lock().unwrap();
-                        guard.take()
-                    };
-                    if let Some(client) = client_option {
-                        // Fire-and-forget async disconnect
-                        tauri::async_runtime::spawn(async move {
-                            client.discord.disconnect().await;
-                            println!("Discord RPC disconnected on app exit");
-                        });
-                    }
-                }
+                disconnect_discord_rpc();
 
                 // Clean up stealth mode artifacts
                 stealth::cleanup_on_exit();
@@ -1055,8 +2426,106 @@ async fn force_video_progress(
 
 /// Export application logs as JSON
 #[tauri::command]
-async fn export_logs() -> Result<String, String> {
-    logger::export_logs().map_err(|e| format!("Failed to export logs: {}", e))
+async fn export_logs(
+    categories: Option<Vec<logger::LogCategory>>,
+    level_at_least: Option<logger::LogLevel>,
+) -> Result<String, String> {
+    logger::export_logs(categories.as_deref(), level_at_least)
+        .map_err(|e| format!("Failed to export logs: {}", e))
+}
+
+/// Show a desktop notification when a quest completes
+#[tauri::command]
+fn set_notifications_enabled(enabled: bool) {
+    notifications::set_enabled(enabled);
+}
+
+/// Skip all remote build number and client info fetches (for isolated networks)
+#[tauri::command]
+fn set_offline_mode(offline: bool) {
+    token_extractor::set_offline(offline);
+}
+
+#[tauri::command]
+fn get_offline_mode() -> bool {
+    token_extractor::is_offline()
+}
+
+/// Set the lowest log level kept for export (console output always shows everything)
+#[tauri::command]
+fn set_min_log_level(level: logger::LogLevel) {
+    logger::set_min_log_level(level);
+}
+
+/// Write application logs to a JSON file (defaults to a timestamped file on the desktop)
+///
+/// Returns the path that was written.
+#[tauri::command]
+async fn export_logs_to_file(
+    path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app_handle
+            .path()
+            .desktop_dir()
+            .map_err(|e| format!("Could not find the desktop folder: {}", e))?
+            .join(logger::default_export_file_name()),
+    };
+
+    logger::export_logs_to_file(&path).map_err(|e| format!("Failed to export logs: {:#}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Marker in the app log dir that keeps file logging on across restarts
+const FILE_LOGGING_MARKER: &str = "file-logging.enabled";
+
+/// Turn on file logging at startup when it was left enabled
+fn restore_file_logging(app_handle: &tauri::AppHandle) {
+    let Ok(dir) = app_handle.path().app_log_dir() else {
+        return;
+    };
+    if dir.join(FILE_LOGGING_MARKER).exists() {
+        if let Err(e) = logger::enable_file_logging(&dir) {
+            eprintln!("[Logger] {:#}", e);
+        }
+    }
+}
+
+/// Enable or disable writing logs to files in the app log dir (kept across restarts)
+///
+/// Returns the log directory while file logging is on.
+#[tauri::command]
+async fn set_file_logging(
+    enabled: bool,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<String>, String> {
+    let dir = app_handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Could not find the log folder: {}", e))?;
+    let marker = dir.join(FILE_LOGGING_MARKER);
+
+    if !enabled {
+        logger::disable_file_logging();
+        if marker.exists() {
+            std::fs::remove_file(&marker)
+                .map_err(|e| format!("Failed to disable file logging: {}", e))?;
+        }
+        return Ok(None);
+    }
+
+    logger::enable_file_logging(&dir)
+        .map_err(|e| format!("Failed to enable file logging: {:#}", e))?;
+    std::fs::write(&marker, b"").map_err(|e| format!("Failed to enable file logging: {}", e))?;
+    Ok(Some(dir.to_string_lossy().to_string()))
+}
+
+/// Directory log files are written to, or `None` when file logging is off
+#[tauri::command]
+fn get_file_logging_dir() -> Option<String> {
+    logger::file_logging_dir().map(|dir| dir.to_string_lossy().to_string())
 }
 
 /// Get debug info including X-Super-Properties
@@ -1066,6 +2535,28 @@ async fn get_debug_info() -> Result<super_properties::DebugInfo, String> {
     Ok(manager.get_debug_info())
 }
 
+/// Port, token and URL of the local live progress WebSocket, or `None` when the app
+/// was built without the `live-stream` feature
+#[tauri::command]
+fn get_live_stream_info() -> Option<serde_json::Value> {
+    #[cfg(feature = "live-stream")]
+    return live_stream::info().and_then(|info| serde_json::to_value(info).ok());
+    #[cfg(not(feature = "live-stream"))]
+    None
+}
+
+/// Decode a captured X-Super-Properties value and diff it against what the app sends
+#[tauri::command]
+fn decode_super_properties(
+    base64: String,
+) -> Result<super_properties::DecodedSuperProperties, String> {
+    let current = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_super_properties();
+    super_properties::decode_super_properties(&base64, &current).map_err(|e| format!("{:#}", e))
+}
+
 /// Get embedded runner version information
 #[tauri::command]
 async fn get_runner_info() -> game_simulator::RunnerInfo {
@@ -1075,19 +2566,25 @@ async fn get_runner_info() -> game_simulator::RunnerInfo {
 /// Check CDP status
 #[tauri::command]
 async fn check_cdp_status(port: Option<u16>) -> cdp_client::CdpStatus {
-    let port = port.unwrap_or(cdp_client::DEFAULT_CDP_PORT);
+    let port = port.unwrap_or_else(cdp_client::default_port);
     cdp_client::check_cdp_available(port).await
 }
 
 /// Fetch SuperProperties via CDP
+///
+/// `timeout_secs` overrides the evaluation timeout for slow clients.
 #[tauri::command]
 async fn fetch_super_properties_cdp(
     port: Option<u16>,
-) -> Result<cdp_client::CdpSuperProperties, String> {
-    let port = port.unwrap_or(cdp_client::DEFAULT_CDP_PORT);
-    let result = cdp_client::fetch_super_properties_via_cdp(port)
+    timeout_secs: Option<u64>,
+) -> Result<cdp_client::CdpSuperProperties, AppError> {
+    let port = port.unwrap_or_else(cdp_client::default_port);
+    let timeout_secs = timeout_secs
+        .unwrap_or(cdp_client::SUPER_PROPERTIES_TIMEOUT_SECS)
+        .clamp(1, 120);
+    let result = cdp_client::fetch_super_properties_via_cdp(port, timeout_secs)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AppError::CdpUnavailable(e.to_string()))?;
 
     // Update global SuperProperties Manager
     if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
@@ -1097,13 +2594,24 @@ async fn fetch_super_properties_cdp(
     Ok(result)
 }
 
+/// Identify the account signed in to the running Discord client via CDP
+///
+/// Needs no token and returns none, so the result can't be used to log in.
+#[tauri::command]
+async fn fetch_current_user_cdp(port: Option<u16>) -> Result<DiscordUser, AppError> {
+    let port = port.unwrap_or_else(cdp_client::default_port);
+    cdp_client::fetch_current_user_via_cdp(port)
+        .await
+        .map_err(|e| AppError::CdpUnavailable(e.to_string()))
+}
+
 /// Capture Discord API request headers via CDP Network interception
 #[tauri::command]
 async fn capture_discord_headers_cdp(
     port: Option<u16>,
     duration_secs: Option<u64>,
 ) -> Result<cdp_client::CdpCapturedHeaders, String> {
-    let port = port.unwrap_or(cdp_client::DEFAULT_CDP_PORT);
+    let port = port.unwrap_or_else(cdp_client::default_port);
     let duration = duration_secs.unwrap_or(30);
     let captured = cdp_client::capture_discord_headers_via_cdp(port, duration)
         .await
@@ -1128,26 +2636,107 @@ fn get_super_properties_mode() -> serde_json::Value {
     serde_json::json!({
         "mode": manager.get_mode().as_str(),
         "mode_display": manager.get_mode().display_name(),
-        "build_number": manager.get_build_number()
+        "build_number": manager.get_build_number(),
+        "latest_build_number": manager.get_latest_build_number(),
+        "is_stale": manager.is_build_stale()
     })
 }
 
+/// Periodically compare the build number we send with Discord's latest, emitting
+/// `build-number-stale` when it has fallen too far behind
+///
+/// The first check runs right away, so a stale build is reported at startup.
+async fn build_staleness_loop(app_handle: tauri::AppHandle) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let mut checks =
+        tokio::time::interval(std::time::Duration::from_secs(BUILD_STALENESS_CHECK_SECS));
+    checks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        checks.tick().await;
+
+        let latest = match token_extractor::fetch_build_number_from_discord().await {
+            Ok(latest) => {
+                build_cache::save(&app_handle, latest);
+                latest
+            }
+            Err(e) => {
+                log(
+                    LogLevel::Debug,
+                    LogCategory::TokenExtraction,
+                    "Build staleness check failed",
+                    Some(&format!("{:#}", e)),
+                );
+                continue;
+            }
+        };
+
+        let (current, stale) = {
+            let mut manager = SUPER_PROPERTIES_MANAGER
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            manager.set_latest_build_number(latest);
+            (manager.get_build_number(), manager.is_build_stale())
+        };
+        if stale {
+            log(
+                LogLevel::Warn,
+                LogCategory::TokenExtraction,
+                &format!(
+                    "Build number {:?} is far behind Discord's latest {}, refresh SuperProperties",
+                    current, latest
+                ),
+                None,
+            );
+            quest_completer::emit_event(
+                &app_handle,
+                "build-number-stale",
+                serde_json::json!({ "build_number": current, "latest_build_number": latest }),
+            );
+        }
+    }
+}
+
 /// Auto-fetch SuperProperties with fallback: CDP -> Remote JS -> Default
 #[tauri::command]
 async fn auto_fetch_super_properties(cdp_port: Option<u16>) -> serde_json::Value {
     use crate::logger::{log, LogCategory, LogLevel};
 
-    let port = cdp_port.unwrap_or(cdp_client::DEFAULT_CDP_PORT);
+    let preferred = cdp_port.unwrap_or_else(cdp_client::default_port);
 
-    // Priority 1: Try CDP
+    // Priority 1: Try CDP, on whichever port Discord is actually listening
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
-        &format!("Auto-fetching SuperProperties, trying CDP on port {}", port),
+        &format!(
+            "Auto-fetching SuperProperties, looking for CDP (port {} first)",
+            preferred
+        ),
         None,
     );
 
-    if let Ok(cdp_result) = cdp_client::fetch_super_properties_via_cdp(port).await {
+    let cdp_result = match cdp_client::find_cdp_port(Some(preferred)).await {
+        Some(port) => cdp_client::fetch_super_properties_via_cdp(
+            port,
+            cdp_client::SUPER_PROPERTIES_TIMEOUT_SECS,
+        )
+        .await
+        .map(|result| (port, result)),
+        None => Err(anyhow::anyhow!("No Discord CDP target found")),
+    };
+
+    if let Ok((port, cdp_result)) = cdp_result {
+        if port != preferred {
+            log(
+                LogLevel::Info,
+                LogCategory::TokenExtraction,
+                &format!(
+                    "Discord CDP found on port {} instead of {}",
+                    port, preferred
+                ),
+                None,
+            );
+        }
         if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
             manager.set_from_cdp(&cdp_result.base64, &cdp_result.decoded);
             log(
@@ -1162,20 +2751,31 @@ async fn auto_fetch_super_properties(cdp_port: Option<u16>) -> serde_json::Value
             return serde_json::json!({
                 "success": true,
                 "mode": "cdp",
-                "build_number": manager.get_build_number()
+                "build_number": manager.get_build_number(),
+                "cdp_port": port
             });
         }
     }
 
+    let offline = token_extractor::is_offline();
     log(
         LogLevel::Debug,
         LogCategory::TokenExtraction,
-        "CDP failed, falling back to Remote JS",
+        if offline {
+            "CDP failed, offline mode skips Remote JS"
+        } else {
+            "CDP failed, falling back to Remote JS"
+        },
         None,
     );
 
     // Priority 2: Try Remote JS
-    if let Ok(build_number) = token_extractor::fetch_build_number_from_discord().await {
+    let remote = if offline {
+        Err(anyhow::anyhow!("Offline mode"))
+    } else {
+        token_extractor::fetch_build_number_from_discord().await
+    };
+    if let Ok(build_number) = remote {
         if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
             manager.set_from_remote_js(build_number);
             log(
@@ -1190,32 +2790,94 @@ async fn auto_fetch_super_properties(cdp_port: Option<u16>) -> serde_json::Value
             return serde_json::json!({
                 "success": true,
                 "mode": "remote_js",
-                "build_number": build_number
+                "build_number": build_number,
+                "cdp_port": null
             });
         }
     }
 
+    // Priority 3: Use cached or default values
+    let manager = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
     log(
-        LogLevel::Warn,
+        if offline {
+            LogLevel::Info
+        } else {
+            LogLevel::Warn
+        },
         LogCategory::TokenExtraction,
-        "All fetch methods failed, using default values",
+        &format!(
+            "No live SuperProperties source, using {} values",
+            manager.get_mode().as_str()
+        ),
         None,
     );
 
-    // Priority 3: Use default values
-    let build_number = if let Ok(manager) = SUPER_PROPERTIES_MANAGER.lock() {
-        manager.get_build_number()
-    } else {
-        None
-    };
-
     serde_json::json!({
         "success": false,
-        "mode": "default",
-        "build_number": build_number
+        "mode": manager.get_mode().as_str(),
+        "build_number": manager.get_build_number(),
+        "cdp_port": null,
+        "offline": offline
     })
 }
 
+/// Fetch the Discord client version and native build and store them in the manager
+async fn refresh_discord_client_info() {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    match token_extractor::fetch_discord_client_info(None, None, None).await {
+        Ok(info) => {
+            log(
+                LogLevel::Info,
+                LogCategory::TokenExtraction,
+                &format!(
+                    "Successfully fetched client info: version={}, native_build={}, channel={}",
+                    info.client_version(),
+                    info.native_build_number
+                        .map_or_else(|| "none".to_string(), |n| n.to_string()),
+                    info.release_channel
+                ),
+                None,
+            );
+            if let Ok(mut manager) = SUPER_PROPERTIES_MANAGER.lock() {
+                manager.set_client_info(
+                    info.client_version(),
+                    info.native_build_number,
+                    &info.release_channel,
+                );
+            }
+        }
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::TokenExtraction,
+                &format!("Failed to fetch client info: {}", e),
+                None,
+            );
+        }
+    }
+}
+
+/// Re-read SuperProperties (CDP -> Remote JS -> Default) and the client version in one go
+///
+/// Meant for after Discord updated while the app is running. Requests build their
+/// User-Agent and X-Super-Properties from the manager each time, so the logged-in
+/// client picks up the new identity immediately.
+#[tauri::command]
+async fn refresh_client_headers(cdp_port: Option<u16>) -> serde_json::Value {
+    let mut result = auto_fetch_super_properties(cdp_port).await;
+    refresh_discord_client_info().await;
+
+    let identity = SUPER_PROPERTIES_MANAGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_client_identity_snapshot();
+    result["client_identity"] = serde_json::to_value(identity).unwrap_or_default();
+    result
+}
+
 /// Retry fetching SuperProperties (resets and tries again)
 #[tauri::command]
 async fn retry_super_properties(cdp_port: Option<u16>) -> serde_json::Value {
@@ -1228,12 +2890,125 @@ async fn retry_super_properties(cdp_port: Option<u16>) -> serde_json::Value {
     auto_fetch_super_properties(cdp_port).await
 }
 
+/// Enable (with an interval in seconds) or disable (`None`) periodic SuperProperties refresh
+///
+/// Discord can update itself mid-session; re-reading SuperProperties over CDP keeps
+/// the build number we send in sync with the running client.
+#[tauri::command]
+async fn set_super_properties_refresh(
+    interval_secs: Option<u64>,
+    cdp_port: Option<u16>,
+) -> Result<(), String> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    if let Some(secs) = interval_secs {
+        if secs < MIN_SUPER_PROPERTIES_REFRESH_SECS {
+            return Err(format!(
+                "Refresh interval must be at least {} seconds",
+                MIN_SUPER_PROPERTIES_REFRESH_SECS
+            ));
+        }
+    }
+
+    let mut task = SUPER_PROPERTIES_REFRESH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(handle) = task.take() {
+        handle.abort();
+    }
+
+    let Some(secs) = interval_secs else {
+        log(
+            LogLevel::Info,
+            LogCategory::TokenExtraction,
+            "SuperProperties refresh disabled",
+            None,
+        );
+        return Ok(());
+    };
+
+    log(
+        LogLevel::Info,
+        LogCategory::TokenExtraction,
+        &format!("SuperProperties refresh enabled every {}s", secs),
+        None,
+    );
+    *task = Some(tokio::spawn(refresh_super_properties_loop(
+        std::time::Duration::from_secs(secs),
+        cdp_port.unwrap_or_else(cdp_client::default_port),
+    )));
+    Ok(())
+}
+
+async fn refresh_super_properties_loop(interval: std::time::Duration, preferred_port: u16) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let Some(port) = cdp_client::find_cdp_port(Some(preferred_port)).await else {
+            log(
+                LogLevel::Debug,
+                LogCategory::TokenExtraction,
+                "SuperProperties refresh skipped: Discord CDP not reachable",
+                None,
+            );
+            continue;
+        };
+
+        let cdp_result = match cdp_client::fetch_super_properties_via_cdp(
+            port,
+            cdp_client::SUPER_PROPERTIES_TIMEOUT_SECS,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                log(
+                    LogLevel::Debug,
+                    LogCategory::TokenExtraction,
+                    "SuperProperties refresh failed",
+                    Some(&format!("{:#}", e)),
+                );
+                continue;
+            }
+        };
+
+        let fetched = cdp_result
+            .decoded
+            .get("client_build_number")
+            .and_then(|v| v.as_u64());
+        let mut manager = SUPER_PROPERTIES_MANAGER
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let current = manager.get_build_number();
+        if fetched.is_some() && fetched != current {
+            manager.set_from_cdp(&cdp_result.base64, &cdp_result.decoded);
+            log(
+                LogLevel::Info,
+                LogCategory::TokenExtraction,
+                &format!(
+                    "Discord build changed: {:?} -> {:?}, SuperProperties updated",
+                    current, fetched
+                ),
+                None,
+            );
+        }
+    }
+}
+
 #[tauri::command]
 fn is_discord_running(channel: Option<String>) -> Result<bool, String> {
     let channel = discord_cdp_launcher::parse_discord_channel(channel.as_deref())?;
     discord_cdp_launcher::is_discord_running(channel)
 }
 
+/// Whether a Discord client is running, and which channel and process
+#[tauri::command]
+fn detect_running_discord() -> Result<discord_cdp_launcher::RunningDiscord, String> {
+    discord_cdp_launcher::detect_running_discord()
+}
+
 #[tauri::command]
 async fn launch_discord_cdp(
     port: Option<u16>,
@@ -1241,7 +3016,7 @@ async fn launch_discord_cdp(
 ) -> Result<discord_cdp_launcher::LaunchResult, String> {
     let channel = discord_cdp_launcher::parse_discord_channel(channel.as_deref())?;
     discord_cdp_launcher::launch_discord_with_cdp(discord_cdp_launcher::LaunchOptions {
-        port: port.unwrap_or(cdp_client::DEFAULT_CDP_PORT),
+        port: port.unwrap_or_else(cdp_client::default_port),
         channel,
         restart_existing: false,
         ..Default::default()
@@ -1256,7 +3031,7 @@ async fn restart_discord_cdp(
 ) -> Result<discord_cdp_launcher::LaunchResult, String> {
     let channel = discord_cdp_launcher::parse_discord_channel(channel.as_deref())?;
     discord_cdp_launcher::restart_discord_with_cdp(discord_cdp_launcher::LaunchOptions {
-        port: port.unwrap_or(cdp_client::DEFAULT_CDP_PORT),
+        port: port.unwrap_or_else(cdp_client::default_port),
         channel,
         ..Default::default()
     })
@@ -1277,7 +3052,7 @@ async fn create_discord_cdp_launcher_shortcut(
     channel: Option<String>,
 ) -> Result<String, String> {
     let channel = discord_cdp_launcher::parse_discord_channel(channel.as_deref())?;
-    let port = port.unwrap_or(cdp_client::DEFAULT_CDP_PORT);
+    let port = port.unwrap_or_else(cdp_client::default_port);
     create_discord_cdp_launcher_shortcut_internal(&app_handle, port, channel).await
 }
 
@@ -1289,7 +3064,7 @@ async fn create_discord_debug_shortcut(
 ) -> Result<String, String> {
     create_discord_cdp_launcher_shortcut_internal(
         &app_handle,
-        port.unwrap_or(cdp_client::DEFAULT_CDP_PORT),
+        port.unwrap_or_else(cdp_client::default_port),
         None,
     )
     .await
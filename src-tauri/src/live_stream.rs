@@ -0,0 +1,231 @@
+//! Live Progress Stream
+//!
+//! Optional local WebSocket server (`live-stream` feature) that mirrors quest events,
+//! the rate-limit state and SuperProperties mode changes as JSON frames, so dashboards
+//! or scripts outside the webview can follow what the app is doing.
+//!
+//! Each frame is `{ "type": <event name>, "data": <payload> }`. Event frames use the
+//! same names as the Tauri events (`quest-progress`, `quest-complete`, ...).
+//!
+//! Clients connect to `ws://127.0.0.1:<port>/?token=<token>`; the token is random per
+//! run and shown in the app. Browser connections from other origins are refused, so
+//! a web page can't read the stream even if it guesses the port.
+
+use crate::AppState;
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Frames buffered per client before a slow one starts missing them
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How often the rate-limit state and SuperProperties mode are checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+static FRAMES: Lazy<broadcast::Sender<String>> =
+    Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+/// Port the server listens on, once started
+static PORT: Mutex<Option<u16>> = Mutex::new(None);
+
+/// Secret clients pass as `?token=`, so other local users and programs can't subscribe
+static TOKEN: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().simple().to_string());
+
+/// Browser origins allowed to connect: the app's own webview (release and `tauri dev`)
+const ALLOWED_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+];
+
+/// Where to connect to the running server
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveStreamInfo {
+    pub port: u16,
+    pub token: String,
+    /// `ws://` URL including the token
+    pub url: String,
+}
+
+/// Send a frame to every connected client (dropped when nobody is listening)
+pub fn publish(kind: &str, data: impl Serialize) {
+    let frame = serde_json::json!({ "type": kind, "data": data });
+    let _ = FRAMES.send(frame.to_string());
+}
+
+/// Port of the running server
+pub fn port() -> Option<u16> {
+    *PORT.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Connection details of the running server
+pub fn info() -> Option<LiveStreamInfo> {
+    let port = port()?;
+    Some(LiveStreamInfo {
+        port,
+        token: TOKEN.clone(),
+        url: format!("ws://127.0.0.1:{}/?token={}", port, *TOKEN),
+    })
+}
+
+/// Check a handshake's `Origin` header and `token` query parameter
+fn authorize(request: &Request, token: &str) -> Result<(), (StatusCode, &'static str)> {
+    if let Some(origin) = request.headers().get("origin") {
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| ALLOWED_ORIGINS.contains(&origin));
+        if !allowed {
+            return Err((StatusCode::FORBIDDEN, "Origin not allowed"));
+        }
+    }
+
+    let query = request.uri().query().unwrap_or_default();
+    let token_ok = url::form_urlencoded::parse(query.as_bytes())
+        .any(|(key, value)| key == "token" && value == token);
+    if !token_ok {
+        return Err((StatusCode::UNAUTHORIZED, "Missing or invalid token"));
+    }
+    Ok(())
+}
+
+/// Bind to a random port on 127.0.0.1 and serve clients in the background
+pub async fn start(app_handle: tauri::AppHandle) -> Result<u16> {
+    if let Some(port) = port() {
+        return Ok(port);
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .context("Failed to bind live stream socket")?;
+    let port = listener.local_addr()?.port();
+    *PORT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(port);
+    println!(
+        "[LiveStream] Listening on ws://127.0.0.1:{} (token required)",
+        port
+    );
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_client(stream));
+        }
+    });
+    tokio::spawn(watch_state(app_handle));
+
+    Ok(port)
+}
+
+async fn serve_client(stream: TcpStream) {
+    // The error type is fixed by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let check = |request: &Request, response: Response| match authorize(request, &TOKEN) {
+        Ok(()) => Ok(response),
+        Err((status, reason)) => {
+            let mut error = ErrorResponse::new(Some(reason.to_string()));
+            *error.status_mut() = status;
+            Err(error)
+        }
+    };
+    let Ok(ws) = tokio_tungstenite::accept_hdr_async(stream, check).await else {
+        return;
+    };
+    let (mut tx, mut rx) = ws.split();
+    let mut frames = FRAMES.subscribe();
+
+    loop {
+        tokio::select! {
+            frame = frames.recv() => match frame {
+                Ok(frame) => {
+                    if tx.send(Message::Text(frame.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Incoming frames are ignored; the stream is one-way
+            incoming = rx.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Publish `rate-limit` and `super-properties-mode` frames whenever they change
+async fn watch_state(app_handle: tauri::AppHandle) {
+    let mut last_rate_limit = None;
+    let mut last_mode = None;
+
+    loop {
+        let rate_limit = app_handle
+            .state::<AppState>()
+            .client
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .and_then(|client| client.get_rate_limit_status());
+        let rate_limit = serde_json::to_value(rate_limit).unwrap_or_default();
+        if last_rate_limit.as_ref() != Some(&rate_limit) {
+            publish("rate-limit", &rate_limit);
+            last_rate_limit = Some(rate_limit);
+        }
+
+        let mode = {
+            let manager = crate::SUPER_PROPERTIES_MANAGER
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            serde_json::json!({
+                "mode": manager.get_mode().as_str(),
+                "build_number": manager.get_build_number(),
+            })
+        };
+        if last_mode.as_ref() != Some(&mode) {
+            publish("super-properties-mode", &mode);
+            last_mode = Some(mode);
+        }
+
+        tokio::time::sleep(WATCH_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str, origin: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(origin) = origin {
+            builder = builder.header("Origin", origin);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn test_authorize_handshake() {
+        let ok = authorize(&request("/?token=secret", None), "secret");
+        assert!(ok.is_ok());
+        let webview = request("/?token=secret", Some("tauri://localhost"));
+        assert!(authorize(&webview, "secret").is_ok());
+
+        let foreign = request("/?token=secret", Some("https://example.com"));
+        assert_eq!(
+            authorize(&foreign, "secret").unwrap_err().0,
+            StatusCode::FORBIDDEN
+        );
+
+        for uri in ["/", "/?token=wrong", "/?other=secret"] {
+            let err = authorize(&request(uri, None), "secret").unwrap_err();
+            assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -2,13 +2,18 @@
 //!
 //! Provides structured logging throughout the application with automatic
 //! sanitization of sensitive data (tokens, user IDs, paths, etc.)
-//! Logs are session-only and automatically cleared on app restart.
+//! Logs are session-only and automatically cleared on app restart, unless file
+//! logging is enabled with `enable_file_logging`: entries are then also appended to
+//! dated files, so they survive a crash.
 
 use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Mutex;
 
 /// Maximum number of log entries to store (FIFO)
@@ -17,12 +22,26 @@ const MAX_LOG_ENTRIES: usize = 1000;
 /// Session start time (set once when app starts)
 static SESSION_START: Lazy<DateTime<Utc>> = Lazy::new(Utc::now);
 
+/// When this session (process) started
+pub fn session_start() -> DateTime<Utc> {
+    *SESSION_START
+}
+
 /// Thread-safe in-memory log storage
 static LOG_STORAGE: Lazy<Mutex<VecDeque<LogEntry>>> =
     Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_LOG_ENTRIES)));
 
-/// Log level
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Size at which the current log file is rotated
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept per day (`.1.log` is the newest)
+const MAX_ROTATED_FILES: usize = 3;
+
+/// Directory log files are written to, when file logging is enabled
+static LOG_FILE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Log level (ordered from least to most severe)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum LogLevel {
     Debug,
@@ -31,6 +50,34 @@ pub enum LogLevel {
     Error,
 }
 
+impl LogLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Debug,
+            1 => LogLevel::Info,
+            2 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+/// Lowest level kept in `LOG_STORAGE`; Debug builds keep everything
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(if cfg!(debug_assertions) {
+    LogLevel::Debug as u8
+} else {
+    LogLevel::Info as u8
+});
+
+/// Set the lowest level that is stored (console output is unaffected)
+pub fn set_min_log_level(level: LogLevel) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Current lowest stored level
+pub fn min_log_level() -> LogLevel {
+    LogLevel::from_u8(MIN_LEVEL.load(Ordering::Relaxed))
+}
+
 impl std::fmt::Display for LogLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -149,7 +196,6 @@ pub fn sanitize_path(path: &str) -> String {
 
 /// Sanitize an email address (show only domain)
 /// Example: "user@gmail.com" -> "***@gmail.com"
-#[allow(dead_code)]
 pub fn sanitize_email(email: &str) -> String {
     if let Some(at_pos) = email.find('@') {
         format!("***{}", &email[at_pos..])
@@ -158,6 +204,15 @@ pub fn sanitize_email(email: &str) -> String {
     }
 }
 
+/// Keep the first `max_chars` characters of `text` without splitting a code point
+/// Longer text gets an ellipsis and its full size, e.g. "abc...(1234 bytes total)"
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...({} bytes total)", &text[..end], text.len()),
+        None => text.to_string(),
+    }
+}
+
 // ============================================================================
 // Logging Functions
 // ============================================================================
@@ -172,15 +227,51 @@ static USER_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
     // Match Discord user IDs (17-19 digit numbers)
     Regex::new(r"\b\d{17,19}\b").expect("Invalid user ID regex")
 });
+static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("Invalid email regex")
+});
+static BASE64_BLOB_REGEX: Lazy<Regex> = Lazy::new(|| {
+    // Long standard-alphabet base64 runs, e.g. an X-Super-Properties value
+    Regex::new(r"[A-Za-z0-9+/]{40,}={0,2}").expect("Invalid base64 regex")
+});
+
+/// Whether `candidate` is base64-encoded SuperProperties JSON
+fn is_super_properties_blob(candidate: &str) -> bool {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+    use base64::Engine;
+
+    STANDARD
+        .decode(candidate)
+        .or_else(|_| STANDARD_NO_PAD.decode(candidate))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+        .is_some_and(|json| json.get("client_build_number").is_some())
+}
 
 /// Sanitize a message string by removing/masking sensitive patterns
-fn sanitize_message(message: &str) -> String {
+pub fn sanitize_message(message: &str) -> String {
     // Apply path sanitization
     let result = sanitize_path(message);
 
     // Mask any Discord tokens
     let result = TOKEN_REGEX.replace_all(&result, "[TOKEN]").to_string();
 
+    // Mask base64-encoded SuperProperties (e.g. from a CDP fetch)
+    let result = BASE64_BLOB_REGEX
+        .replace_all(&result, |caps: &regex::Captures| {
+            if is_super_properties_blob(&caps[0]) {
+                "[SUPER_PROPS]".to_string()
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string();
+
+    // Mask email addresses (keep the domain)
+    let result = EMAIL_REGEX
+        .replace_all(&result, |caps: &regex::Captures| sanitize_email(&caps[0]))
+        .to_string();
+
     // Mask Discord user IDs
     USER_ID_REGEX.replace_all(&result, "[USER_ID]").to_string()
 }
@@ -214,6 +305,12 @@ pub fn log(level: LogLevel, category: LogCategory, message: &str, details: Optio
         println!("[{}] [{}] {}", entry.level, entry.category, entry.message);
     }
 
+    if entry.level < min_log_level() {
+        return;
+    }
+
+    append_to_file(&entry);
+
     // Store in memory
     if let Ok(mut storage) = LOG_STORAGE.lock() {
         if storage.len() >= MAX_LOG_ENTRIES {
@@ -223,6 +320,94 @@ pub fn log(level: LogLevel, category: LogCategory, message: &str, details: Optio
     }
 }
 
+// ============================================================================
+// File Logging
+// ============================================================================
+
+/// Also append every stored entry to `dir`, one JSON object per line
+///
+/// Files are named `discord-quest-helper-YYYYMMDD.log` and rotated once they reach
+/// `MAX_LOG_FILE_BYTES`. Entries are sanitized exactly like the in-memory ones.
+pub fn enable_file_logging(dir: &Path) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    std::fs::create_dir_all(dir).with_context(|| {
+        format!(
+            "Failed to create log directory {}",
+            sanitize_path(&dir.to_string_lossy())
+        )
+    })?;
+    *LOG_FILE_DIR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(dir.to_path_buf());
+    Ok(())
+}
+
+/// Stop writing log files (existing files are kept)
+pub fn disable_file_logging() {
+    *LOG_FILE_DIR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Directory log files are written to, if file logging is enabled
+pub fn file_logging_dir() -> Option<PathBuf> {
+    LOG_FILE_DIR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Log file for `date` (`YYYYMMDD`), or its `index`-th rotation
+fn log_file_path(dir: &Path, date: &str, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("discord-quest-helper-{}.log", date))
+    } else {
+        dir.join(format!("discord-quest-helper-{}.{}.log", date, index))
+    }
+}
+
+/// Shift `.N.log` files up by one, dropping the oldest, and move the current file to `.1.log`
+fn rotate_log_files(dir: &Path, date: &str) -> std::io::Result<()> {
+    let _ = std::fs::remove_file(log_file_path(dir, date, MAX_ROTATED_FILES));
+    for index in (0..MAX_ROTATED_FILES).rev() {
+        let from = log_file_path(dir, date, index);
+        if from.exists() {
+            std::fs::rename(&from, log_file_path(dir, date, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_entry(dir: &Path, entry: &LogEntry) -> std::io::Result<()> {
+    let date = Utc::now().format("%Y%m%d").to_string();
+    let path = log_file_path(dir, &date, 0);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_LOG_FILE_BYTES) {
+        rotate_log_files(dir, &date)?;
+    }
+
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())
+}
+
+fn append_to_file(entry: &LogEntry) {
+    // Held while writing so rotation and appends from several threads don't interleave
+    let dir = LOG_FILE_DIR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(dir) = dir.as_deref() {
+        // Not logged through `log` again, which would recurse into the failing write
+        if let Err(e) = write_entry(dir, entry) {
+            eprintln!("[Logger] Failed to write log file: {}", e);
+        }
+    }
+}
+
 /// Convenience macros for different log levels
 #[macro_export]
 macro_rules! log_debug {
@@ -318,11 +503,28 @@ fn get_os_info() -> String {
     }
 }
 
-/// Export all logs as a JSON string
+/// Whether an entry passes the optional export filters
+fn entry_matches(
+    entry: &LogEntry,
+    categories: Option<&[LogCategory]>,
+    level_at_least: Option<LogLevel>,
+) -> bool {
+    categories.is_none_or(|categories| categories.contains(&entry.category))
+        && level_at_least.is_none_or(|level| entry.level >= level)
+}
+
+/// Export logs as a JSON string, optionally limited to some categories and/or a minimum level
 /// Returns sanitized log data suitable for sharing with developers
-pub fn export_logs() -> anyhow::Result<String> {
+pub fn export_logs(
+    categories: Option<&[LogCategory]>,
+    level_at_least: Option<LogLevel>,
+) -> anyhow::Result<String> {
     let entries = if let Ok(storage) = LOG_STORAGE.lock() {
-        storage.iter().cloned().collect()
+        storage
+            .iter()
+            .filter(|entry| entry_matches(entry, categories, level_at_least))
+            .cloned()
+            .collect()
     } else {
         Vec::new()
     };
@@ -339,6 +541,23 @@ pub fn export_logs() -> anyhow::Result<String> {
         .map_err(|e| anyhow::anyhow!("Failed to serialize logs: {}", e))
 }
 
+/// Default file name for a log export, e.g. `discord-quest-helper-logs-20250101-120000.json`
+pub fn default_export_file_name() -> String {
+    format!(
+        "discord-quest-helper-logs-{}.json",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Write the same JSON as `export_logs` to `path`
+pub fn export_logs_to_file(path: &std::path::Path) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let json = export_logs(None, None)?;
+    std::fs::write(path, json)
+        .with_context(|| format!("Failed to write {}", sanitize_path(&path.to_string_lossy())))
+}
+
 /// Get the number of log entries currently stored
 #[allow(dead_code)]
 pub fn log_count() -> usize {
@@ -383,8 +602,107 @@ mod tests {
         assert!(!sanitized.contains("Masterain"));
     }
 
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("exact", 5), "exact");
+        assert_eq!(truncate_chars("abcdef", 3), "abc...(6 bytes total)");
+        // Multi-byte characters are kept whole
+        assert_eq!(truncate_chars("héllo wörld", 2), "hé...(13 bytes total)");
+    }
+
+    #[test]
+    fn test_export_logs_to_file() {
+        let name = default_export_file_name();
+        assert!(name.starts_with("discord-quest-helper-logs-"));
+        assert!(name.ends_with(".json"));
+
+        let path = std::env::temp_dir().join(format!("dqh_{}_{}", std::process::id(), name));
+        export_logs_to_file(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"entries\""));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_log_level_order() {
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Warn < LogLevel::Error);
+        for level in [
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+        ] {
+            assert_eq!(LogLevel::from_u8(level as u8), level);
+        }
+    }
+
+    #[test]
+    fn test_entry_matches_filters() {
+        let entry = LogEntry {
+            timestamp: String::new(),
+            level: LogLevel::Warn,
+            category: LogCategory::TokenExtraction,
+            message: String::new(),
+            details: None,
+        };
+
+        assert!(entry_matches(&entry, None, None));
+        assert!(entry_matches(
+            &entry,
+            Some(&[LogCategory::TokenExtraction, LogCategory::Quest]),
+            Some(LogLevel::Info)
+        ));
+        assert!(!entry_matches(&entry, Some(&[LogCategory::Api]), None));
+        assert!(!entry_matches(&entry, None, Some(LogLevel::Error)));
+    }
+
+    #[test]
+    fn test_rotate_log_files() {
+        let dir = std::env::temp_dir().join(format!("dqh-log-rotate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for index in 0..=MAX_ROTATED_FILES {
+            std::fs::write(log_file_path(&dir, "20250101", index), index.to_string()).unwrap();
+        }
+
+        rotate_log_files(&dir, "20250101").unwrap();
+        assert!(!log_file_path(&dir, "20250101", 0).exists());
+        for index in 1..=MAX_ROTATED_FILES {
+            let content = std::fs::read_to_string(log_file_path(&dir, "20250101", index)).unwrap();
+            assert_eq!(content, (index - 1).to_string());
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_sanitize_email() {
         assert_eq!(sanitize_email("user@gmail.com"), "***@gmail.com");
+        assert_eq!(
+            sanitize_message("400 Bad Request: {\"email\": \"some.user+tag@example.com\"}"),
+            "400 Bad Request: {\"email\": \"***@example.com\"}"
+        );
+    }
+
+    #[test]
+    fn test_exported_logs_mask_super_properties() {
+        let props = crate::super_properties::XSuperPropertiesManager::new();
+        let base64 = props.get_super_properties_base64();
+
+        log(
+            LogLevel::Error,
+            LogCategory::TokenExtraction,
+            &format!("CDP returned {{\"base64\":\"{}\"}}", base64),
+            Some(&format!("X-Super-Properties: {}", base64)),
+        );
+
+        let export = export_logs(Some(&[LogCategory::TokenExtraction]), None).unwrap();
+        assert!(!export.contains(&base64));
+        assert!(export.contains("[SUPER_PROPS]"));
+
+        // Other long base64 values are left alone
+        let other = "QUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFB";
+        assert_eq!(sanitize_message(other), other);
     }
 }
@@ -0,0 +1,207 @@
+//! Metrics
+//!
+//! Process-local counters for quest throughput. `session_stats` summarizes them for the
+//! `get_session_stats` command. Built with the `metrics` feature, they are also served in
+//! the Prometheus text format on `http://127.0.0.1:9464/metrics` (port overridable with
+//! `DQH_METRICS_PORT`).
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+static QUESTS_COMPLETED: AtomicU64 = AtomicU64::new(0);
+static HEARTBEATS_SENT: AtomicU64 = AtomicU64::new(0);
+static ACTIVE_QUESTS: AtomicI64 = AtomicI64::new(0);
+static TOKENS_DETECTED: AtomicU64 = AtomicU64::new(0);
+/// Non-success Discord API responses by status code
+static API_ERRORS: Mutex<BTreeMap<u16, u64>> = Mutex::new(BTreeMap::new());
+
+/// A quest finished (dry runs aren't counted)
+pub fn quest_completed() {
+    QUESTS_COMPLETED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A heartbeat or video progress update was accepted by Discord
+pub fn heartbeat_sent() {
+    HEARTBEATS_SENT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Token auto-detection found `count` distinct tokens
+pub fn tokens_detected(count: usize) {
+    TOKENS_DETECTED.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// Discord answered with a non-success `status`
+pub fn api_error(status: u16) {
+    *API_ERRORS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(status)
+        .or_default() += 1;
+}
+
+/// Counts a quest as active until dropped
+pub struct ActiveQuest(());
+
+impl Drop for ActiveQuest {
+    fn drop(&mut self) {
+        ACTIVE_QUESTS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub fn track_active_quest() -> ActiveQuest {
+    ACTIVE_QUESTS.fetch_add(1, Ordering::Relaxed);
+    ActiveQuest(())
+}
+
+/// What the app did since it started
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionStats {
+    /// RFC 3339
+    pub session_start: String,
+    pub uptime_secs: u64,
+    pub quests_completed: u64,
+    pub active_quests: i64,
+    pub tokens_detected: u64,
+    pub heartbeats_sent: u64,
+    pub api_errors: u64,
+    /// Non-success responses by HTTP status
+    pub api_errors_by_status: BTreeMap<u16, u64>,
+}
+
+pub fn session_stats() -> SessionStats {
+    let session_start = crate::logger::session_start();
+    let api_errors_by_status = API_ERRORS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    SessionStats {
+        session_start: session_start.to_rfc3339(),
+        uptime_secs: (chrono::Utc::now() - session_start).num_seconds().max(0) as u64,
+        quests_completed: QUESTS_COMPLETED.load(Ordering::Relaxed),
+        active_quests: ACTIVE_QUESTS.load(Ordering::Relaxed),
+        tokens_detected: TOKENS_DETECTED.load(Ordering::Relaxed),
+        heartbeats_sent: HEARTBEATS_SENT.load(Ordering::Relaxed),
+        api_errors: api_errors_by_status.values().sum(),
+        api_errors_by_status,
+    }
+}
+
+/// All metrics in the Prometheus text exposition format
+#[cfg(any(feature = "metrics", test))]
+pub fn render() -> String {
+    use std::fmt::Write;
+
+    fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+    }
+
+    let completed = QUESTS_COMPLETED.load(Ordering::Relaxed);
+    let heartbeats = HEARTBEATS_SENT.load(Ordering::Relaxed);
+    let active = ACTIVE_QUESTS.load(Ordering::Relaxed);
+
+    let mut out = String::new();
+    header(
+        &mut out,
+        "quests_completed_total",
+        "counter",
+        "Quests completed.",
+    );
+    let _ = writeln!(out, "quests_completed_total {}", completed);
+    header(
+        &mut out,
+        "heartbeats_sent_total",
+        "counter",
+        "Heartbeats and video progress updates sent.",
+    );
+    let _ = writeln!(out, "heartbeats_sent_total {}", heartbeats);
+    header(
+        &mut out,
+        "api_errors_total",
+        "counter",
+        "Non-success Discord API responses.",
+    );
+    for (status, count) in API_ERRORS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+    {
+        let _ = writeln!(out, "api_errors_total{{status=\"{}\"}} {}", status, count);
+    }
+    header(
+        &mut out,
+        "active_quests",
+        "gauge",
+        "Quests currently running.",
+    );
+    let _ = writeln!(out, "active_quests {}", active);
+    out
+}
+
+/// Default port of the metrics endpoint
+#[cfg(feature = "metrics")]
+const DEFAULT_PORT: u16 = 9464;
+
+/// Serve `render()` to every HTTP request on 127.0.0.1 until the listener fails
+#[cfg(feature = "metrics")]
+pub async fn serve() -> anyhow::Result<()> {
+    use anyhow::Context;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let port = std::env::var("DQH_METRICS_PORT")
+        .ok()
+        .and_then(|p| p.trim().parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to bind metrics endpoint on port {}", port))?;
+    println!("[Metrics] Serving on http://127.0.0.1:{}/metrics", port);
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            // The request itself doesn't matter, every path gets the metrics
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let body = render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters() {
+        quest_completed();
+        heartbeat_sent();
+        api_error(429);
+        api_error(429);
+        let active = track_active_quest();
+
+        let text = render();
+        assert!(text.contains("# TYPE quests_completed_total counter"));
+        assert!(text.contains("api_errors_total{status=\"429\"} 2"));
+        assert!(text.contains("active_quests 1"));
+
+        drop(active);
+        assert!(render().contains("active_quests 0"));
+
+        tokens_detected(2);
+        let stats = session_stats();
+        assert!(stats.quests_completed >= 1);
+        assert!(stats.tokens_detected >= 2);
+        assert!(stats.api_errors >= 2);
+        assert!(stats.api_errors_by_status.get(&429) >= Some(&2));
+    }
+}
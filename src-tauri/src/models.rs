@@ -21,9 +21,13 @@ pub struct Quest {
     pub description: String,
     pub progress: f64,
     pub seconds_needed: u32,
+    /// Discord task type; unrecognized types are passed through as-is
     pub task_type: String,
+    /// A completer can run this task (false for console tasks and unknown types)
+    pub supported: bool,
     pub application_id: String,
     pub application_name: String,
+    /// CDN URL of the application icon
     pub application_icon: Option<String>,
     pub expires_at: Option<String>,
     pub enrolled: bool,
@@ -34,16 +38,45 @@ pub struct Quest {
 pub struct DetectableGame {
     pub id: String,
     pub name: String,
+    #[serde(default)]
     pub executables: Vec<GameExecutable>,
     #[serde(alias = "icon_hash")]
     pub icon: Option<String>,
     pub type_name: Option<String>,
+    /// Other names Discord matches the game by
+    #[serde(default)]
+    pub aliases: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameExecutable {
+    /// File name (or trailing path) Discord matches, e.g. `game.exe` or `bin/win64/game.exe`
     pub name: String,
+    /// `win32`, `darwin` or `linux`
     pub os: String,
+    /// Launchers/updaters Discord sees but doesn't count as playing
+    #[serde(default)]
+    pub is_launcher: bool,
+}
+
+impl DetectableGame {
+    /// The executable Discord expects on `os`, preferring the game over its launcher
+    pub fn executable_for_os(&self, os: &str) -> Option<&GameExecutable> {
+        let mut candidates = self.executables.iter().filter(|exe| exe.os == os);
+        let first = candidates.clone().next()?;
+        Some(candidates.find(|exe| !exe.is_launcher).unwrap_or(first))
+    }
+
+    /// Platforms this game has an executable for, in list order
+    pub fn executable_os_list(&self) -> Vec<String> {
+        let mut list: Vec<String> = Vec::new();
+        for exe in &self.executables {
+            if !list.contains(&exe.os) {
+                list.push(exe.os.clone());
+            }
+        }
+        list
+    }
 }
 
 // Discord API response types (legacy, kept for reference)
@@ -69,15 +102,116 @@ pub struct GameHeartbeatPayload {
     pub terminal: bool,
 }
 
+/// Payload of the `quest-progress` event emitted by the HTTP completers
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestProgressEvent {
+    pub quest_id: String,
+    pub current_seconds: f64,
+    pub seconds_needed: u32,
+    /// Progress percentage (0-100)
+    pub percent: f64,
+    /// Estimated real seconds until completion
+    pub eta_seconds: f64,
+    /// Discord task type, e.g. "WATCH_VIDEO"
+    pub task_type: String,
+    /// Seconds until the next progress update, for smooth UI interpolation
+    pub heartbeat_interval: u64,
+}
+
+/// Payload of the `quest-throttled` event, sent when Discord records less video
+/// progress than was reported
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestThrottledEvent {
+    pub quest_id: String,
+    /// Timestamp that was sent
+    pub sent_seconds: f64,
+    /// Progress Discord recorded instead
+    pub accepted_seconds: f64,
+    /// Speed multiplier used from now on
+    pub speed_multiplier: f64,
+}
+
+/// Payload of the `quest-timeout` event, sent when a completer hits its wall-clock cap
+/// without Discord reporting the quest as completed
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestTimeoutEvent {
+    pub quest_id: String,
+    /// The cap that was reached
+    pub limit_secs: u64,
+}
+
+/// Payload of the `account-flagged` event, sent when Discord reports a problem with
+/// the logged-in account itself
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountFlaggedEvent {
+    pub status: u16,
+    /// Discord JSON error code, if the body had one
+    pub code: Option<u64>,
+    /// `token_revoked`, `password_reset_required` or `verification_required`
+    pub reason: String,
+    /// Discord's error message
+    pub message: String,
+}
+
+/// One runnable task of a quest, as returned by `get_quest_plan`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuestPlan {
+    /// Discord task type, e.g. "WATCH_VIDEO"
+    pub task_type: String,
+    pub seconds_needed: u32,
+    /// Progress percentage (0-100) already made on this task
+    pub initial_progress: f64,
+    pub application_id: Option<String>,
+    /// Can be completed with `start_game_heartbeat_quest` / `start_stream_quest`
+    pub supports_heartbeat: bool,
+    /// Can be completed with `start_video_quest`
+    pub supports_video: bool,
+}
+
+/// What to do after a quest finishes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionPolicy {
+    /// Emit completion and stop (previous behavior)
+    #[default]
+    Stop,
+    /// Claim the reward automatically
+    Claim,
+    /// Claim the reward, then start the next incomplete enrolled quest
+    ClaimAndNext,
+}
+
+/// Behavior when a quest is started while SuperProperties are still the built-in defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultModeGate {
+    /// Start without checking
+    Off,
+    /// Start, but emit a `quest-warning` event
+    #[default]
+    Warn,
+    /// Refuse to start until CDP or remote values are available
+    Block,
+}
+
 // Internal state
 pub struct QuestState {
-    #[allow(dead_code)]
     pub quest_id: String,
     pub cancel_flag: tokio::sync::mpsc::Sender<()>,
 }
 
+/// Where an extracted token was found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenSource {
+    pub client: crate::token_extractor::DiscordClient,
+    pub file_path_redacted: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedAccount {
     pub token: String,
     pub user: DiscordUser,
+    /// Every client the token was found in
+    #[serde(default)]
+    pub source: Vec<TokenSource>,
 }
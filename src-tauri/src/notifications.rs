@@ -0,0 +1,101 @@
+//! Desktop Notifications
+//!
+//! Optional native OS notification when a quest completes, for users who start a long
+//! quest and leave the (possibly minimized) window alone. Off until the frontend turns
+//! it on with `set_notifications_enabled`.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri_plugin_notification::NotificationExt;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Quest names by ID, from the last quest list fetch
+static QUEST_NAMES: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Remember quest names from a `/quests/@me` response so notifications can show them
+pub fn remember_quest_names(quests: &serde_json::Value) {
+    let Some(list) = quests.get("quests").and_then(|q| q.as_array()) else {
+        return;
+    };
+    let mut names = QUEST_NAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for quest in list {
+        let id = quest.get("id").and_then(|v| v.as_str());
+        let name = quest
+            .pointer("/config/messages/quest_name")
+            .or_else(|| quest.pointer("/config/messages/questName"))
+            .and_then(|v| v.as_str())
+            .filter(|name| !name.trim().is_empty());
+        if let (Some(id), Some(name)) = (id, name) {
+            names.insert(id.to_string(), name.trim().to_string());
+        }
+    }
+}
+
+fn completion_message(quest_id: Option<&str>) -> String {
+    let name = quest_id.and_then(|id| {
+        QUEST_NAMES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(id)
+            .cloned()
+    });
+    match name {
+        Some(name) => format!("Quest '{}' completed", name),
+        None => "Quest completed".to_string(),
+    }
+}
+
+/// Show a notification for a completed quest, if notifications are enabled
+pub fn quest_completed(app_handle: &tauri::AppHandle, quest_id: Option<&str>) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let result = app_handle
+        .notification()
+        .builder()
+        .title("Discord Quest Helper")
+        .body(completion_message(quest_id))
+        .show();
+    if let Err(e) = result {
+        log(
+            LogLevel::Warn,
+            LogCategory::General,
+            "Failed to show quest completion notification",
+            Some(&e.to_string()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_message_uses_quest_name() {
+        remember_quest_names(&serde_json::json!({
+            "quests": [
+                { "id": "1", "config": { "messages": { "quest_name": " Watch the Trailer " } } },
+                { "id": "2", "config": { "messages": { "quest_name": "" } } }
+            ]
+        }));
+
+        assert_eq!(
+            completion_message(Some("1")),
+            "Quest 'Watch the Trailer' completed"
+        );
+        assert_eq!(completion_message(Some("2")), "Quest completed");
+        assert_eq!(completion_message(None), "Quest completed");
+    }
+}
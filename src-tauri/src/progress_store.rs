@@ -0,0 +1,230 @@
+//! Quest Progress Store
+//!
+//! Persists the last reported progress of HTTP-driven quests to a JSON file in the
+//! app data dir, so a quest interrupted by a crash or restart can be resumed from
+//! where it left off instead of from zero.
+//!
+//! Writes are throttled per quest to one every `SAVE_INTERVAL`, so a video quest
+//! reporting every few seconds doesn't rewrite the file on each tick. The newest
+//! held-back entry is written when the quest stops.
+
+use crate::json_store;
+use crate::quest_completer::HttpQuestKind;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// File name inside the app data dir
+const STORE_FILE: &str = "quest_progress.json";
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Minimum time between two saves of the same quest
+const SAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Save throttling state of one quest
+#[derive(Debug, Default)]
+struct Throttle {
+    last_saved: Option<Instant>,
+    /// Newest entry held back because the last save was too recent
+    pending: Option<SavedQuestProgress>,
+}
+
+static THROTTLE: Lazy<Mutex<HashMap<String, Throttle>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The entry to write now, or `None` if it was held back as pending
+fn throttle(entry: SavedQuestProgress, now: Instant) -> Option<SavedQuestProgress> {
+    let mut throttle = THROTTLE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let state = throttle.entry(entry.quest_id.clone()).or_default();
+    if state
+        .last_saved
+        .is_some_and(|at| now.duration_since(at) < SAVE_INTERVAL)
+    {
+        state.pending = Some(entry);
+        return None;
+    }
+    state.last_saved = Some(now);
+    state.pending = None;
+    Some(entry)
+}
+
+/// Take the entry held back for `quest_id`, if any
+fn take_pending(quest_id: &str) -> Option<SavedQuestProgress> {
+    THROTTLE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_mut(quest_id)?
+        .pending
+        .take()
+}
+
+/// Last known progress of a quest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuestProgress {
+    pub quest_id: String,
+    #[serde(flatten)]
+    pub kind: HttpQuestKind,
+    pub seconds_needed: u32,
+    /// Last reported progress in seconds
+    pub current_seconds: f64,
+    /// Last reported progress percentage (0-100)
+    pub progress: f64,
+    /// RFC 3339 timestamp of the last update
+    pub updated_at: String,
+}
+
+fn store_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    json_store::path(app_handle, STORE_FILE)
+}
+
+fn load_from(path: &Path) -> BTreeMap<String, SavedQuestProgress> {
+    json_store::load(path).unwrap_or_default()
+}
+
+fn update<F>(app_handle: &tauri::AppHandle, f: F) -> Result<()>
+where
+    F: FnOnce(&mut BTreeMap<String, SavedQuestProgress>) -> bool,
+{
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = store_path(app_handle)?;
+    let mut entries = load_from(&path);
+    if f(&mut entries) {
+        json_store::save_atomic(&path, &entries)?;
+    }
+    Ok(())
+}
+
+fn log_failure(action: &str, err: &anyhow::Error) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    log(
+        LogLevel::Warn,
+        LogCategory::Quest,
+        &format!("Failed to {} saved quest progress", action),
+        Some(&format!("{:#}", err)),
+    );
+}
+
+fn write(app_handle: &tauri::AppHandle, entry: SavedQuestProgress) {
+    let result = update(app_handle, |entries| {
+        entries.insert(entry.quest_id.clone(), entry);
+        true
+    });
+    if let Err(e) = result {
+        log_failure("update", &e);
+    }
+}
+
+/// Record the latest progress of a quest. Failures are logged, never fatal.
+///
+/// Held back when the quest was saved less than `SAVE_INTERVAL` ago; `flush` writes it.
+pub fn record(app_handle: &tauri::AppHandle, entry: SavedQuestProgress) {
+    if let Some(entry) = throttle(entry, Instant::now()) {
+        write(app_handle, entry);
+    }
+}
+
+/// Write progress that `record` held back, e.g. once the quest stopped
+pub fn flush(app_handle: &tauri::AppHandle, quest_id: &str) {
+    if let Some(entry) = take_pending(quest_id) {
+        write(app_handle, entry);
+    }
+}
+
+/// Forget a quest, e.g. once Discord reports it completed or it expired
+pub fn clear(app_handle: &tauri::AppHandle, quest_id: &str) {
+    THROTTLE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(quest_id);
+    let result = update(app_handle, |entries| entries.remove(quest_id).is_some());
+    if let Err(e) = result {
+        log_failure("clear", &e);
+    }
+}
+
+/// All saved entries
+pub fn load(app_handle: &tauri::AppHandle) -> Vec<SavedQuestProgress> {
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match store_path(app_handle) {
+        Ok(path) => load_from(&path).into_values().collect(),
+        Err(e) => {
+            log_failure("read", &e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("dqh_progress_{}", std::process::id()));
+        let path = dir.join(STORE_FILE);
+
+        assert!(load_from(&path).is_empty());
+
+        let mut entries = BTreeMap::new();
+        entries.insert(
+            "123".to_string(),
+            SavedQuestProgress {
+                quest_id: "123".to_string(),
+                kind: HttpQuestKind::Game {
+                    application_id: "42".to_string(),
+                },
+                seconds_needed: 900,
+                current_seconds: 300.0,
+                progress: 33.3,
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+            },
+        );
+        json_store::save_atomic(&path, &entries).unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"task_type\": \"game\""));
+        assert_eq!(load_from(&path), entries);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_save_throttled_per_quest() {
+        let entry = |quest_id: &str, current_seconds: f64| SavedQuestProgress {
+            quest_id: quest_id.to_string(),
+            kind: HttpQuestKind::Video,
+            seconds_needed: 600,
+            current_seconds,
+            progress: current_seconds / 6.0,
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        let now = Instant::now();
+
+        assert!(throttle(entry("throttle_a", 10.0), now).is_some());
+        assert!(throttle(entry("throttle_a", 15.0), now + Duration::from_secs(5)).is_none());
+        assert!(throttle(entry("throttle_b", 15.0), now + Duration::from_secs(5)).is_some());
+
+        // The newest held-back entry is kept for flushing
+        assert!(throttle(entry("throttle_a", 20.0), now + Duration::from_secs(10)).is_none());
+        assert_eq!(
+            take_pending("throttle_a").map(|e| e.current_seconds),
+            Some(20.0)
+        );
+        assert!(take_pending("throttle_a").is_none());
+
+        assert!(throttle(entry("throttle_a", 30.0), now + SAVE_INTERVAL).is_some());
+        assert!(take_pending("throttle_b").is_none());
+    }
+}
@@ -1,75 +1,791 @@
-use crate::discord_api::DiscordApiClient;
+use crate::discord_api::{quest_tasks, DiscordApiClient, QuestTask, VideoProgressUpdate};
+use crate::models::{
+    CompletionPolicy, GameHeartbeatPayload, HeartbeatPayload, QuestPlan, QuestProgressEvent,
+    QuestThrottledEvent, QuestTimeoutEvent,
+};
+use crate::progress_store::{self, SavedQuestProgress};
 use anyhow::Result;
 use rand::RngExt;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tauri::Emitter;
-use tokio::time::sleep;
+use tokio::time::{sleep, Instant};
+
+/// How a completer run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestOutcome {
+    Completed,
+    Cancelled,
+    /// Stopped by the wall-clock cap (see `with_time_limit`)
+    TimedOut,
+}
+
+/// Default cap is this many times the expected runtime...
+const MAX_DURATION_FACTOR: f64 = 3.0;
+
+/// ...but never less than this, so short quests survive slow detection and retries
+const MIN_MAX_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// Video pacing settings, reused when chaining into the next quest
+#[derive(Debug, Clone, Copy)]
+pub struct VideoSettings {
+    pub speed_multiplier: f64,
+    pub watch_ahead_secs: f64,
+    pub heartbeat_interval: u64,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            watch_ahead_secs: 0.0,
+            heartbeat_interval: 15,
+        }
+    }
+}
+
+/// Settings shared by every quest in an HTTP run (single quest, chain or batch)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    pub video: VideoSettings,
+    /// Log requests instead of sending them
+    pub dry_run: bool,
+    /// For game quests, how long to wait for Discord to report the game as running
+    /// before warning the user (`None` skips the check)
+    pub detection_timeout: Option<Duration>,
+    /// For game quests, also show the game as "Playing" over a gateway session
+    pub gateway_presence: bool,
+    /// Wall-clock cap for the whole run; `None` uses `default_max_duration`
+    pub max_duration: Option<Duration>,
+}
+
+/// Quest flow that can be driven over plain HTTP (no CDP needed)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "task_type", rename_all = "snake_case")]
+pub enum HttpQuestKind {
+    Video,
+    Game {
+        application_id: String,
+    },
+    /// Stream heartbeat with a generated stream key
+    Stream,
+}
+
+impl HttpQuestKind {
+    /// Human-readable label for error messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            HttpQuestKind::Video => "Video quest",
+            HttpQuestKind::Game { .. } => "Game heartbeat quest",
+            HttpQuestKind::Stream => "Stream quest",
+        }
+    }
+
+    /// Short name used by `--mode` and scheduled quests
+    pub fn mode(&self) -> &'static str {
+        match self {
+            HttpQuestKind::Video => "video",
+            HttpQuestKind::Game { .. } => "game",
+            HttpQuestKind::Stream => "stream",
+        }
+    }
+
+    /// Discord task type this flow completes
+    pub fn task_type(&self) -> &'static str {
+        match self {
+            HttpQuestKind::Video => "WATCH_VIDEO",
+            HttpQuestKind::Game { .. } => "PLAY_ON_DESKTOP",
+            HttpQuestKind::Stream => "STREAM_ON_DESKTOP",
+        }
+    }
+}
+
+/// A quest ready to be started by the HTTP completers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HttpQuestJob {
+    pub quest_id: String,
+    #[serde(flatten)]
+    pub kind: HttpQuestKind,
+    pub seconds_needed: u32,
+    /// Initial progress percentage (0-100)
+    pub initial_progress: f64,
+}
+
+/// A quest picked from `/quests/@me` that the HTTP completers can run
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpQuestCandidate {
+    pub job: HttpQuestJob,
+    /// Not enrolled yet; must be accepted before progress counts
+    pub needs_enroll: bool,
+}
+
+/// The Discord calls the HTTP completers make
+///
+/// Implemented by `DiscordApiClient`; tests drive the completers with a mock instead.
+pub trait QuestApi: Sync {
+    fn update_video_progress(
+        &self,
+        quest_id: &str,
+        timestamp: f64,
+    ) -> impl std::future::Future<Output = Result<VideoProgressUpdate>> + Send;
+
+    /// Returns whether Discord reports the quest as completed
+    fn send_game_heartbeat(
+        &self,
+        quest_id: &str,
+        application_id: &str,
+        terminal: bool,
+    ) -> impl std::future::Future<Output = Result<bool>> + Send;
+
+    fn send_stream_heartbeat(
+        &self,
+        quest_id: &str,
+        stream_key: &str,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    fn accept_quest(
+        &self,
+        quest_id: &str,
+    ) -> impl std::future::Future<Output = Result<serde_json::Value>> + Send;
+
+    /// Whether Discord reports `application_id` as running within `timeout`
+    fn wait_for_detected_game(
+        &self,
+        application_id: &str,
+        timeout: Duration,
+    ) -> impl std::future::Future<Output = Result<bool>> + Send;
+}
+
+impl QuestApi for DiscordApiClient {
+    async fn update_video_progress(
+        &self,
+        quest_id: &str,
+        timestamp: f64,
+    ) -> Result<VideoProgressUpdate> {
+        DiscordApiClient::update_video_progress(self, quest_id, timestamp).await
+    }
+
+    async fn send_game_heartbeat(
+        &self,
+        quest_id: &str,
+        application_id: &str,
+        terminal: bool,
+    ) -> Result<bool> {
+        DiscordApiClient::send_game_heartbeat(self, quest_id, application_id, terminal).await
+    }
+
+    async fn send_stream_heartbeat(&self, quest_id: &str, stream_key: &str) -> Result<()> {
+        DiscordApiClient::send_stream_heartbeat(self, quest_id, stream_key).await
+    }
+
+    async fn accept_quest(&self, quest_id: &str) -> Result<serde_json::Value> {
+        DiscordApiClient::accept_quest(self, quest_id).await
+    }
+
+    async fn wait_for_detected_game(
+        &self,
+        application_id: &str,
+        timeout: Duration,
+    ) -> Result<bool> {
+        DiscordApiClient::wait_for_detected_game(self, application_id, timeout).await
+    }
+}
+
+/// Receives progress and lifecycle updates from the HTTP completers
+///
+/// The app forwards them as Tauri events (see the `impl` for `AppHandle`), while
+/// headless mode prints them to stdout. Saving progress for resume is optional.
+pub trait ProgressSink: Send + Sync {
+    fn progress(&self, evt: QuestProgressEvent);
+    fn complete(&self, quest_id: &str);
+    /// The quest was stopped; carries the final progress if any was made this run
+    fn stopped(&self, last_progress: Option<QuestProgressEvent>);
+    fn error(&self, msg: &str);
+    fn warning(&self, msg: &str);
+
+    /// Discord recorded less progress than was sent, so the completer slowed down
+    fn throttled(&self, _evt: QuestThrottledEvent) {}
+
+    /// Remember the latest progress so the quest can be resumed later
+    ///
+    /// Implementations may hold back frequent updates until `flush_progress`.
+    fn save_progress(&self, _entry: SavedQuestProgress) {}
+
+    /// Write any progress `save_progress` held back, called when a quest stops
+    fn flush_progress(&self, _quest_id: &str) {}
+
+    /// Forget saved progress once the quest is done
+    fn clear_progress(&self, _quest_id: &str) {}
+
+    /// The run hit its wall-clock cap and was stopped
+    fn timed_out(&self, evt: QuestTimeoutEvent) {
+        self.error(&format!(
+            "Quest {} did not complete within {}s and was stopped",
+            evt.quest_id, evt.limit_secs
+        ));
+    }
+}
+
+/// Emit a Tauri event, mirrored to the live stream when built with `live-stream`
+///
+/// Every app event the webview sees should go through here so stream clients see it too.
+pub(crate) fn emit_event<S: Serialize + Clone>(app: &tauri::AppHandle, event: &str, payload: S) {
+    #[cfg(feature = "live-stream")]
+    crate::live_stream::publish(event, &payload);
+    let _ = app.emit(event, payload);
+}
+
+impl ProgressSink for tauri::AppHandle {
+    fn progress(&self, evt: QuestProgressEvent) {
+        emit_event(self, "quest-progress", evt);
+    }
+
+    fn complete(&self, quest_id: &str) {
+        emit_event(self, "quest-complete", ());
+        crate::notifications::quest_completed(self, Some(quest_id));
+    }
+
+    fn stopped(&self, last_progress: Option<QuestProgressEvent>) {
+        emit_event(self, "quest-stopped", last_progress);
+    }
+
+    fn error(&self, msg: &str) {
+        emit_event(self, "quest-error", msg);
+    }
+
+    fn warning(&self, msg: &str) {
+        emit_event(self, "quest-warning", msg);
+    }
+
+    fn throttled(&self, evt: QuestThrottledEvent) {
+        emit_event(self, "quest-throttled", evt);
+    }
+
+    fn save_progress(&self, entry: SavedQuestProgress) {
+        progress_store::record(self, entry);
+    }
+
+    fn flush_progress(&self, quest_id: &str) {
+        progress_store::flush(self, quest_id);
+    }
+
+    fn clear_progress(&self, quest_id: &str) {
+        progress_store::clear(self, quest_id);
+    }
+
+    fn timed_out(&self, evt: QuestTimeoutEvent) {
+        emit_event(self, "quest-timeout", evt);
+    }
+}
+
+/// Enroll in the candidate's quest unless it already is
+pub async fn ensure_enrolled(client: &impl QuestApi, candidate: &HttpQuestCandidate) -> Result<()> {
+    if candidate.needs_enroll {
+        client.accept_quest(&candidate.job.quest_id).await?;
+    }
+    Ok(())
+}
+
+/// Three times the time `job` could take with `video` pacing, at least `MIN_MAX_DURATION`
+///
+/// Videos are sized for the slowest pace throttling can drop them to: real time (or the
+/// configured speed, if slower) with the watch-ahead used up, since a throttled run
+/// halves both until Discord accepts the progress.
+pub fn default_max_duration(job: &HttpQuestJob, video: &VideoSettings) -> Duration {
+    let remaining =
+        job.seconds_needed as f64 * (1.0 - job.initial_progress.clamp(0.0, 100.0) / 100.0);
+    let expected = match job.kind {
+        HttpQuestKind::Video if video.speed_multiplier > 0.0 => {
+            video_eta_secs(remaining, video.speed_multiplier.min(1.0), 0.0)
+        }
+        _ => remaining,
+    };
+    max_duration_for(expected)
+}
+
+/// Three times `expected_secs`, at least `MIN_MAX_DURATION`
+pub fn max_duration_for(expected_secs: f64) -> Duration {
+    Duration::from_secs_f64(expected_secs.max(0.0) * MAX_DURATION_FACTOR).max(MIN_MAX_DURATION)
+}
+
+/// Run a completer, stopping it once `limit` has passed
+///
+/// Guards against runaway heartbeat loops, e.g. from a wrong `seconds_needed` or a quest
+/// Discord never marks as completed. Hitting the cap is reported through
+/// `ProgressSink::timed_out` and returns `QuestOutcome::TimedOut`.
+pub async fn with_time_limit(
+    quest_id: &str,
+    limit: Duration,
+    sink: &impl ProgressSink,
+    run: impl std::future::Future<Output = Result<QuestOutcome>>,
+) -> Result<QuestOutcome> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    match tokio::time::timeout(limit, run).await {
+        Ok(result) => result,
+        Err(_) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                &format!(
+                    "Quest {} hit its {}s time limit and was stopped",
+                    quest_id,
+                    limit.as_secs()
+                ),
+                None,
+            );
+            sink.timed_out(QuestTimeoutEvent {
+                quest_id: quest_id.to_string(),
+                limit_secs: limit.as_secs(),
+            });
+            Ok(QuestOutcome::TimedOut)
+        }
+    }
+}
+
+/// Run a single HTTP quest job with the matching completer
+pub async fn run_http_job(
+    client: &DiscordApiClient,
+    job: &HttpQuestJob,
+    options: RunOptions,
+    sink: &impl ProgressSink,
+    cancel_rx: tokio::sync::mpsc::Receiver<()>,
+) -> Result<QuestOutcome> {
+    let RunOptions {
+        video,
+        dry_run,
+        detection_timeout,
+        gateway_presence,
+        max_duration,
+    } = options;
+    let _active = crate::metrics::track_active_quest();
+    let limit = max_duration.unwrap_or_else(|| default_max_duration(job, &video));
+
+    let run = async move {
+        match &job.kind {
+            HttpQuestKind::Video => {
+                complete_video_quest(
+                    client,
+                    job.quest_id.clone(),
+                    job.seconds_needed,
+                    job.initial_progress,
+                    video.speed_multiplier,
+                    video.watch_ahead_secs,
+                    video.heartbeat_interval,
+                    dry_run,
+                    sink,
+                    cancel_rx,
+                )
+                .await
+            }
+            HttpQuestKind::Game { application_id } => {
+                // Kept alive for the whole quest; dropping it closes the gateway session
+                let _presence = if gateway_presence && !dry_run {
+                    start_gateway_presence(client, application_id).await
+                } else {
+                    None
+                };
+
+                complete_game_quest_via_heartbeat(
+                    client,
+                    job.quest_id.clone(),
+                    application_id.clone(),
+                    job.seconds_needed,
+                    job.initial_progress,
+                    detection_timeout,
+                    dry_run,
+                    sink,
+                    cancel_rx,
+                )
+                .await
+            }
+            HttpQuestKind::Stream => {
+                complete_stream_quest(
+                    client,
+                    job.quest_id.clone(),
+                    generate_stream_key(),
+                    job.seconds_needed,
+                    job.initial_progress,
+                    dry_run,
+                    sink,
+                    cancel_rx,
+                )
+                .await
+            }
+        }
+    };
+
+    with_time_limit(&job.quest_id, limit, sink, run).await
+}
+
+/// Log the request a completer would send in dry-run mode
+fn log_dry_run(url: String, payload: &impl Serialize) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let body = serde_json::to_string(payload).unwrap_or_default();
+    log(
+        LogLevel::Info,
+        LogCategory::Quest,
+        &format!("[dry-run] Would POST {}", url),
+        Some(&body),
+    );
+}
+
+/// Send a video progress update, or log it in dry-run mode
+async fn send_video_progress(
+    client: &impl QuestApi,
+    quest_id: &str,
+    timestamp: f64,
+    dry_run: bool,
+) -> Result<VideoProgressUpdate> {
+    if dry_run {
+        log_dry_run(
+            crate::endpoints::video_progress(quest_id),
+            &DiscordApiClient::video_progress_payload(timestamp),
+        );
+        return Ok(VideoProgressUpdate {
+            completed: false,
+            progress: None,
+        });
+    }
+    let update = client.update_video_progress(quest_id, timestamp).await?;
+    crate::metrics::heartbeat_sent();
+    Ok(update)
+}
+
+/// Send a stream heartbeat, or log it in dry-run mode
+async fn send_stream_heartbeat(
+    client: &impl QuestApi,
+    quest_id: &str,
+    stream_key: &str,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        log_dry_run(
+            crate::endpoints::quest_heartbeat(quest_id),
+            &HeartbeatPayload {
+                stream_key: stream_key.to_string(),
+            },
+        );
+        return Ok(());
+    }
+    client.send_stream_heartbeat(quest_id, stream_key).await?;
+    crate::metrics::heartbeat_sent();
+    Ok(())
+}
+
+/// Send a game heartbeat, or log it in dry-run mode (which never reports completion)
+async fn send_game_heartbeat(
+    client: &impl QuestApi,
+    quest_id: &str,
+    application_id: &str,
+    terminal: bool,
+    dry_run: bool,
+) -> Result<bool> {
+    if dry_run {
+        log_dry_run(
+            crate::endpoints::quest_heartbeat(quest_id),
+            &GameHeartbeatPayload {
+                application_id: application_id.to_string(),
+                terminal,
+            },
+        );
+        return Ok(false);
+    }
+    let completed = client
+        .send_game_heartbeat(quest_id, application_id, terminal)
+        .await?;
+    crate::metrics::heartbeat_sent();
+    Ok(completed)
+}
+
+fn progress_event(
+    quest_id: &str,
+    task_type: &str,
+    current_seconds: f64,
+    seconds_needed: u32,
+    eta_seconds: f64,
+    heartbeat_interval: u64,
+) -> QuestProgressEvent {
+    QuestProgressEvent {
+        quest_id: quest_id.to_string(),
+        current_seconds,
+        seconds_needed,
+        percent: (current_seconds / seconds_needed.max(1) as f64 * 100.0).clamp(0.0, 100.0),
+        eta_seconds: eta_seconds.max(0.0),
+        task_type: task_type.to_string(),
+        heartbeat_interval,
+    }
+}
+
+/// Emit a structured `quest-progress` event
+fn emit_progress(
+    sink: &impl ProgressSink,
+    quest_id: &str,
+    kind: &HttpQuestKind,
+    current_seconds: f64,
+    seconds_needed: u32,
+    eta_seconds: f64,
+    heartbeat_interval: u64,
+) -> f64 {
+    let event = progress_event(
+        quest_id,
+        kind.task_type(),
+        current_seconds,
+        seconds_needed,
+        eta_seconds,
+        heartbeat_interval,
+    );
+    let percent = event.percent;
+    sink.progress(event);
+    percent
+}
+
+/// Emit a structured `quest-progress` event from a CDP completer
+///
+/// CDP flows can't get ahead of real time, so the ETA is the time still needed.
+pub(crate) fn emit_cdp_progress(
+    app: &tauri::AppHandle,
+    quest_id: &str,
+    task_type: &str,
+    current_seconds: f64,
+    seconds_needed: u32,
+    heartbeat_interval: u64,
+) {
+    let event = progress_event(
+        quest_id,
+        task_type,
+        current_seconds,
+        seconds_needed,
+        seconds_needed as f64 - current_seconds,
+        heartbeat_interval,
+    );
+    emit_event(app, "quest-progress", event);
+}
+
+/// Real seconds left for a video quest with `remaining_sim_seconds` to go
+fn video_eta_secs(remaining_sim_seconds: f64, speed: f64, watch_ahead_secs: f64) -> f64 {
+    if watch_ahead_secs > 0.0 {
+        remaining_sim_seconds - watch_ahead_secs
+    } else {
+        remaining_sim_seconds / speed
+    }
+}
+
+/// Persist the latest reported progress so the quest can be resumed after a restart
+fn save_progress(
+    sink: &impl ProgressSink,
+    quest_id: &str,
+    kind: HttpQuestKind,
+    seconds_needed: u32,
+    current_seconds: f64,
+) {
+    sink.save_progress(SavedQuestProgress {
+        quest_id: quest_id.to_string(),
+        kind,
+        seconds_needed,
+        current_seconds,
+        progress: (current_seconds / seconds_needed.max(1) as f64 * 100.0).min(100.0),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    });
+}
+
+/// The quest a completer was running when it was stopped
+struct StoppedQuest<'a, S> {
+    sink: &'a S,
+    quest_id: &'a str,
+    kind: HttpQuestKind,
+    seconds_needed: u32,
+    dry_run: bool,
+}
+
+/// Wind down a stopped quest without losing the time since the last update
+///
+/// `reported_seconds` is what Discord last acknowledged and `current_seconds` how far
+/// the quest had actually got when the stop arrived. If that is further, `final_update`
+/// reports it to Discord, then the progress is saved and `quest-stopped` carries it.
+async fn finish_stopped<S: ProgressSink, T>(
+    quest: StoppedQuest<'_, S>,
+    reported_seconds: f64,
+    current_seconds: f64,
+    final_update: impl std::future::Future<Output = Result<T>>,
+) -> QuestOutcome {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let StoppedQuest {
+        sink,
+        quest_id,
+        kind,
+        seconds_needed,
+        dry_run,
+    } = quest;
+
+    let mut final_seconds = reported_seconds;
+    if current_seconds > reported_seconds {
+        match final_update.await {
+            Ok(_) => final_seconds = current_seconds,
+            Err(e) => log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                &format!(
+                    "Failed to send final progress for stopped quest {}",
+                    quest_id
+                ),
+                Some(&format!("{:#}", e)),
+            ),
+        }
+    }
+
+    if final_seconds <= 0.0 {
+        sink.stopped(None);
+        return QuestOutcome::Cancelled;
+    }
+
+    if !dry_run {
+        save_progress(sink, quest_id, kind.clone(), seconds_needed, final_seconds);
+        sink.flush_progress(quest_id);
+    }
+    let remaining = seconds_needed as f64 - final_seconds;
+    sink.stopped(Some(progress_event(
+        quest_id,
+        kind.task_type(),
+        final_seconds,
+        seconds_needed,
+        remaining,
+        0,
+    )));
+    QuestOutcome::Cancelled
+}
 
 /// Complete a video quest
 ///
 /// Simulates watching a video by incrementally sending video progress
 /// Based on power0matin's approach: POST { timestamp: seconds } to /quests/{id}/video-progress
+///
+/// The HTTP completers share a `dry_run` flag: requests are logged instead of sent,
+/// while progress events and pacing behave as in a real run. When stopped, they send
+/// one last update so the time since the previous one still counts.
 pub async fn complete_video_quest(
-    client: &DiscordApiClient,
+    client: &impl QuestApi,
     quest_id: String,
     seconds_needed: u32,
     initial_progress: f64,
     speed_multiplier: f64,
+    watch_ahead_secs: f64,
     heartbeat_interval: u64,
-    app_handle: tauri::AppHandle,
+    dry_run: bool,
+    sink: &impl ProgressSink,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     // Progress control parameters (based on power0matin research)
     // Speed: how many seconds to advance per update (configurable)
     if speed_multiplier <= 0.0 {
         anyhow::bail!("speed_multiplier must be greater than 0");
     }
-    let speed = speed_multiplier;
+    if watch_ahead_secs < 0.0 {
+        anyhow::bail!("watch_ahead_secs must not be negative");
+    }
+    let mut speed = speed_multiplier;
+    let mut watch_ahead_secs = watch_ahead_secs;
     // Interval: how often to send updates (in real seconds)
     let interval = heartbeat_interval;
 
     // Convert initial progress (percentage) to seconds
     let mut current_seconds = (initial_progress / 100.0 * seconds_needed as f64) as f64;
+    let start_seconds = current_seconds;
+    let started_at = Instant::now();
 
-    println!("Starting video quest: quest_id={}, target={}s, current_progress={:.1}s, speed={:.1}x, interval={}s", 
-             quest_id, seconds_needed, current_seconds, speed, interval);
+    println!("Starting video quest: quest_id={}, target={}s, current_progress={:.1}s, speed={:.1}x, watch_ahead={:.0}s, interval={}s", 
+             quest_id, seconds_needed, current_seconds, speed, watch_ahead_secs, interval);
 
     loop {
         // Calculate the remaining simulated seconds, then the real wait time
         let remaining_sim_seconds = (seconds_needed as f64) - current_seconds;
-        let real_seconds_to_finish = if speed > 0.0 {
-            remaining_sim_seconds / speed
-        } else {
-            interval as f64
-        };
-        let wait_secs = (real_seconds_to_finish.ceil() as u64).min(interval).max(1);
+        let real_seconds_to_finish = video_eta_secs(remaining_sim_seconds, speed, watch_ahead_secs);
+        let wait_secs = (real_seconds_to_finish.ceil().max(0.0) as u64)
+            .min(interval)
+            .max(1);
 
         // Wait before advancing progress (prevents immediate jump on first iteration)
+        let wait_started = Instant::now();
         tokio::select! {
             _ = sleep(Duration::from_secs(wait_secs)) => {},
             _ = cancel_rx.recv() => {
                 println!("Video quest cancelled");
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                let watched = next_video_timestamp(
+                    current_seconds,
+                    start_seconds,
+                    started_at.elapsed().as_secs_f64(),
+                    wait_started.elapsed().as_secs_f64(),
+                    speed,
+                    watch_ahead_secs,
+                )
+                .min(seconds_needed as f64);
+                let quest = StoppedQuest {
+                    sink,
+                    quest_id: &quest_id,
+                    kind: HttpQuestKind::Video,
+                    seconds_needed,
+                    dry_run,
+                };
+                let final_update = send_video_progress(client, &quest_id, watched, dry_run);
+                return Ok(finish_stopped(quest, current_seconds, watched, final_update).await);
             }
         }
 
-        // Advance timestamp based on speed and actual wait time
-        current_seconds += speed * (wait_secs as f64);
+        // Advance timestamp based on speed (or the watch-ahead target) and actual wait time
+        current_seconds = next_video_timestamp(
+            current_seconds,
+            start_seconds,
+            started_at.elapsed().as_secs_f64(),
+            wait_secs as f64,
+            speed,
+            watch_ahead_secs,
+        );
         let timestamp = current_seconds.min(seconds_needed as f64);
 
         // Add some randomness to look more natural
         let timestamp_with_jitter = timestamp + rand::rng().random_range(0.0..0.5);
 
         // Send progress update
-        match client
-            .update_video_progress(&quest_id, timestamp_with_jitter)
-            .await
-        {
-            Ok(completed) => {
-                // Calculate and emit progress percentage
-                let progress = (timestamp / seconds_needed as f64 * 100.0).min(100.0);
-                let _ = app_handle.emit("quest-progress", progress);
+        let update = send_video_progress(client, &quest_id, timestamp_with_jitter, dry_run).await;
+        match update {
+            Ok(VideoProgressUpdate {
+                completed,
+                progress: accepted,
+            }) => {
+                // Discord clamps progress that runs ahead of what it allows: continue
+                // from what it recorded, at a lower pace
+                let timestamp = match accepted {
+                    Some(accepted) if accepted < timestamp - THROTTLE_TOLERANCE_SECS => {
+                        speed = (speed / 2.0).max(1.0);
+                        watch_ahead_secs /= 2.0;
+                        current_seconds = accepted;
+                        println!(
+                            "Video progress throttled: sent {:.0}s, Discord recorded {:.0}s; speed now {:.1}x",
+                            timestamp, accepted, speed
+                        );
+                        sink.throttled(QuestThrottledEvent {
+                            quest_id: quest_id.clone(),
+                            sent_seconds: timestamp,
+                            accepted_seconds: accepted,
+                            speed_multiplier: speed,
+                        });
+                        accepted
+                    }
+                    _ => timestamp,
+                };
+
+                // Calculate and emit progress
+                let progress = emit_progress(
+                    sink,
+                    &quest_id,
+                    &HttpQuestKind::Video,
+                    timestamp,
+                    seconds_needed,
+                    video_eta_secs(seconds_needed as f64 - timestamp, speed, watch_ahead_secs),
+                    interval,
+                );
 
                 println!(
                     "Video quest progress: {:.1}% ({:.0}/{} s)",
@@ -77,32 +793,73 @@ pub async fn complete_video_quest(
                 );
 
                 if completed || timestamp >= seconds_needed as f64 {
-                    let _ = app_handle.emit("quest-complete", ());
+                    if !dry_run {
+                        sink.clear_progress(&quest_id);
+                        crate::metrics::quest_completed();
+                    }
+                    sink.complete(&quest_id);
                     println!("Video quest completed!");
-                    return Ok(());
+                    return Ok(QuestOutcome::Completed);
+                }
+
+                if !dry_run {
+                    save_progress(
+                        sink,
+                        &quest_id,
+                        HttpQuestKind::Video,
+                        seconds_needed,
+                        timestamp,
+                    );
                 }
             }
             Err(e) => {
                 println!("Video progress update failed: {}", e);
-                let _ = app_handle.emit("quest-error", e.to_string());
+                sink.error(&e.to_string());
                 return Err(e);
             }
         }
     }
 }
 
+/// How far Discord's recorded video progress may trail the sent timestamp (rounding,
+/// jitter) before the update counts as throttled
+const THROTTLE_TOLERANCE_SECS: f64 = 5.0;
+
+/// Compute the video timestamp to report next
+///
+/// By default progress advances `speed` simulated seconds per real second waited.
+/// When `watch_ahead_secs` is positive it replaces the multiplier: the target becomes
+/// the real time elapsed since the quest started plus the buffer, like a viewer who
+/// has buffered ahead. Discord clamps progress that runs too far ahead of wall-clock
+/// time, so the buffer should stay below that tolerance. Progress never moves backwards.
+fn next_video_timestamp(
+    current_seconds: f64,
+    start_seconds: f64,
+    elapsed_real_secs: f64,
+    waited_secs: f64,
+    speed: f64,
+    watch_ahead_secs: f64,
+) -> f64 {
+    if watch_ahead_secs > 0.0 {
+        current_seconds.max(start_seconds + elapsed_real_secs + watch_ahead_secs)
+    } else {
+        current_seconds + speed * waited_secs
+    }
+}
+
 /// Complete a stream quest
 ///
 /// Maintains streaming status by periodically sending heartbeats
 pub async fn complete_stream_quest(
-    client: &DiscordApiClient,
+    client: &impl QuestApi,
     quest_id: String,
     stream_key: String,
     seconds_needed: u32,
     initial_progress: f64,
-    app_handle: tauri::AppHandle,
+    dry_run: bool,
+    sink: &impl ProgressSink,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     // Heartbeat interval (30 seconds)
     let heartbeat_interval = 30;
     let total_heartbeats = (seconds_needed + heartbeat_interval - 1) / heartbeat_interval;
@@ -110,39 +867,158 @@ pub async fn complete_stream_quest(
     // Start from initial progress
     let start_heartbeat = (initial_progress / 100.0 * total_heartbeats as f64) as u32;
 
+    // Progress Discord has acknowledged, and when the last heartbeat went out
+    let mut reported_seconds = (start_heartbeat * heartbeat_interval).min(seconds_needed) as f64;
+    let mut last_beat: Option<Instant> = None;
+    let stop = |reported_seconds: f64, last_beat: Option<Instant>| {
+        let streamed = last_beat.map_or(reported_seconds, |at| {
+            (reported_seconds + at.elapsed().as_secs_f64()).min(seconds_needed as f64)
+        });
+        let quest = StoppedQuest {
+            sink,
+            quest_id: &quest_id,
+            kind: HttpQuestKind::Stream,
+            seconds_needed,
+            dry_run,
+        };
+        let final_update = send_stream_heartbeat(client, &quest_id, &stream_key, dry_run);
+        finish_stopped(quest, reported_seconds, streamed, final_update)
+    };
+
     for i in start_heartbeat..total_heartbeats {
         // Check cancel signal
         if cancel_rx.try_recv().is_ok() {
             println!("Stream quest cancelled");
-            return Ok(());
+            return Ok(stop(reported_seconds, last_beat).await);
         }
 
         // Send heartbeat
-        client.send_stream_heartbeat(&quest_id, &stream_key).await?;
+        send_stream_heartbeat(client, &quest_id, &stream_key, dry_run).await?;
+        last_beat = Some(Instant::now());
 
-        // Calculate and send progress percentage
-        let progress = ((i + 1) as f64 / total_heartbeats as f64) * 100.0;
-        let _ = app_handle.emit("quest-progress", progress);
+        // Calculate and send progress
+        let current_seconds = ((i + 1) * heartbeat_interval).min(seconds_needed) as f64;
+        reported_seconds = current_seconds;
+        let progress = emit_progress(
+            sink,
+            &quest_id,
+            &HttpQuestKind::Stream,
+            current_seconds,
+            seconds_needed,
+            ((total_heartbeats - i - 1) * heartbeat_interval) as f64,
+            heartbeat_interval as u64,
+        );
 
         println!("Stream quest progress: {:.1}%", progress);
 
         if i == total_heartbeats - 1 {
-            let _ = app_handle.emit("quest-complete", ());
+            if !dry_run {
+                sink.clear_progress(&quest_id);
+                crate::metrics::quest_completed();
+            }
+            sink.complete(&quest_id);
             println!("Stream quest completed!");
             break;
         }
 
+        if !dry_run {
+            save_progress(
+                sink,
+                &quest_id,
+                HttpQuestKind::Stream,
+                seconds_needed,
+                current_seconds,
+            );
+        }
+
         // Wait for next heartbeat
         tokio::select! {
             _ = sleep(Duration::from_secs(heartbeat_interval as u64)) => {},
             _ = cancel_rx.recv() => {
                 println!("Stream quest cancelled");
-                return Ok(());
+                return Ok(stop(reported_seconds, last_beat).await);
             }
         }
     }
 
-    Ok(())
+    Ok(QuestOutcome::Completed)
+}
+
+/// Show the quest's game as the user's "Playing" activity over the gateway
+///
+/// Presence is best effort: failures are logged and the heartbeat quest runs without it.
+async fn start_gateway_presence(
+    client: &DiscordApiClient,
+    application_id: &str,
+) -> Option<crate::discord_gateway::GatewayPresence> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    let name = match client.get_application_name(application_id).await {
+        Ok(name) => name,
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                "Skipping gateway presence: could not resolve game name",
+                Some(&format!("{:#}", e)),
+            );
+            return None;
+        }
+    };
+
+    let presence = client.connect_gateway_presence();
+    presence.set_playing_activity(application_id, &name);
+    log(
+        LogLevel::Info,
+        LogCategory::Quest,
+        &format!("Showing \"Playing {}\" over the gateway", name),
+        None,
+    );
+    Some(presence)
+}
+
+/// Check that Discord registered the game before heartbeating, warning if it didn't
+///
+/// A game Discord never detected is the usual cause of quests stuck at 0%, so this
+/// turns that silent failure into a `quest-warning` the user can act on.
+async fn verify_game_detected(
+    client: &impl QuestApi,
+    application_id: &str,
+    timeout: Duration,
+    sink: &impl ProgressSink,
+) {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    match client.wait_for_detected_game(application_id, timeout).await {
+        Ok(true) => log(
+            LogLevel::Info,
+            LogCategory::Quest,
+            &format!("Discord detected game {}", application_id),
+            None,
+        ),
+        Ok(false) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                &format!(
+                    "Discord did not report game {} as running within {}s",
+                    application_id,
+                    timeout.as_secs()
+                ),
+                None,
+            );
+            sink.warning(
+                "Discord hasn't detected the game as running, so progress may not count. \
+                 Make sure the game is running, then restart Discord if it still isn't picked up.",
+            );
+        }
+        Err(e) => log(
+            LogLevel::Warn,
+            LogCategory::Quest,
+            "Could not verify game detection",
+            Some(&format!("{:#}", e)),
+        ),
+    }
 }
 
 /// Complete a game quest by sending direct heartbeat requests
@@ -150,17 +1026,30 @@ pub async fn complete_stream_quest(
 /// This is an alternative to running a simulated game executable.
 /// Based on HAR analysis: POST { application_id, terminal: false } every 60 seconds
 pub async fn complete_game_quest_via_heartbeat(
-    client: &DiscordApiClient,
+    client: &impl QuestApi,
     quest_id: String,
     application_id: String,
     seconds_needed: u32,
     initial_progress: f64,
-    app_handle: tauri::AppHandle,
+    detection_timeout: Option<Duration>,
+    dry_run: bool,
+    sink: &impl ProgressSink,
     mut cancel_rx: tokio::sync::mpsc::Receiver<()>,
-) -> Result<()> {
+) -> Result<QuestOutcome> {
     // Fixed heartbeat interval: 60 seconds (based on Discord client behavior)
     const HEARTBEAT_INTERVAL: u64 = 60;
 
+    if let Some(timeout) = detection_timeout.filter(|_| !dry_run) {
+        tokio::select! {
+            _ = verify_game_detected(client, &application_id, timeout, sink) => {},
+            _ = cancel_rx.recv() => {
+                println!("Game quest cancelled");
+                sink.stopped(None);
+                return Ok(QuestOutcome::Cancelled);
+            }
+        }
+    }
+
     let total_heartbeats = (seconds_needed as u64 + HEARTBEAT_INTERVAL - 1) / HEARTBEAT_INTERVAL;
 
     // Start from initial progress
@@ -169,26 +1058,59 @@ pub async fn complete_game_quest_via_heartbeat(
     println!("Starting game quest via heartbeat: quest_id={}, app_id={}, target={}s, interval={}s, total_beats={}", 
              quest_id, application_id, seconds_needed, HEARTBEAT_INTERVAL, total_heartbeats);
 
+    // Progress Discord has acknowledged, and when the last heartbeat went out
+    let mut reported_seconds =
+        (start_heartbeat * HEARTBEAT_INTERVAL).min(seconds_needed as u64) as f64;
+    let mut last_beat: Option<Instant> = None;
+    let stop = |reported_seconds: f64, last_beat: Option<Instant>| {
+        let played = last_beat.map_or(reported_seconds, |at| {
+            (reported_seconds + at.elapsed().as_secs_f64()).min(seconds_needed as f64)
+        });
+        let quest = StoppedQuest {
+            sink,
+            quest_id: &quest_id,
+            kind: HttpQuestKind::Game {
+                application_id: application_id.clone(),
+            },
+            seconds_needed,
+            dry_run,
+        };
+        let final_update = send_game_heartbeat(client, &quest_id, &application_id, false, dry_run);
+        finish_stopped(quest, reported_seconds, played, final_update)
+    };
+
     for i in start_heartbeat..total_heartbeats {
         // Check cancel signal
         if cancel_rx.try_recv().is_ok() {
             println!("Game quest cancelled");
-            let _ = app_handle.emit("quest-stopped", ());
-            return Ok(());
+            return Ok(stop(reported_seconds, last_beat).await);
         }
 
         // Determine if this is the last heartbeat (terminal)
         let is_last = i == total_heartbeats - 1;
 
         // Send heartbeat
-        match client
-            .send_game_heartbeat(&quest_id, &application_id, is_last)
-            .await
-        {
+        let heartbeat =
+            send_game_heartbeat(client, &quest_id, &application_id, is_last, dry_run).await;
+        match heartbeat {
             Ok(completed) => {
-                // Calculate and send progress percentage
-                let progress = ((i + 1) as f64 / total_heartbeats as f64) * 100.0;
-                let _ = app_handle.emit("quest-progress", progress);
+                last_beat = Some(Instant::now());
+
+                // Calculate and send progress
+                let current_seconds =
+                    ((i + 1) * HEARTBEAT_INTERVAL).min(seconds_needed as u64) as f64;
+                reported_seconds = current_seconds;
+                let progress = emit_progress(
+                    sink,
+                    &quest_id,
+                    &HttpQuestKind::Game {
+                        application_id: application_id.clone(),
+                    },
+                    current_seconds,
+                    seconds_needed,
+                    ((total_heartbeats - i - 1) * HEARTBEAT_INTERVAL) as f64,
+                    HEARTBEAT_INTERVAL,
+                );
 
                 println!(
                     "Game quest progress: {:.1}% (heartbeat {}/{})",
@@ -198,14 +1120,30 @@ pub async fn complete_game_quest_via_heartbeat(
                 );
 
                 if completed || is_last {
-                    let _ = app_handle.emit("quest-complete", ());
+                    if !dry_run {
+                        sink.clear_progress(&quest_id);
+                        crate::metrics::quest_completed();
+                    }
+                    sink.complete(&quest_id);
                     println!("Game quest completed!");
-                    return Ok(());
+                    return Ok(QuestOutcome::Completed);
+                }
+
+                if !dry_run {
+                    save_progress(
+                        sink,
+                        &quest_id,
+                        HttpQuestKind::Game {
+                            application_id: application_id.clone(),
+                        },
+                        seconds_needed,
+                        current_seconds,
+                    );
                 }
             }
             Err(e) => {
                 println!("Game heartbeat failed: {}", e);
-                let _ = app_handle.emit("quest-error", e.to_string());
+                sink.error(&e.to_string());
                 return Err(e);
             }
         }
@@ -215,16 +1153,317 @@ pub async fn complete_game_quest_via_heartbeat(
             _ = sleep(Duration::from_secs(HEARTBEAT_INTERVAL)) => {},
             _ = cancel_rx.recv() => {
                 println!("Game quest cancelled");
-                let _ = app_handle.emit("quest-stopped", ());
-                return Ok(());
+                return Ok(stop(reported_seconds, last_beat).await);
             }
         }
     }
 
-    Ok(())
+    Ok(QuestOutcome::Completed)
+}
+
+/// Whether the quest's `config.expires_at` lies before `now`
+fn is_expired(quest: &serde_json::Value, now: chrono::DateTime<chrono::Utc>) -> bool {
+    quest
+        .pointer("/config/expires_at")
+        .and_then(|v| v.as_str())
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        .is_some_and(|expires| expires < now)
+}
+
+/// Turn a raw quest into an HTTP job, if it is incomplete, unexpired and has a supported task
+///
+/// Video tasks are preferred over desktop play tasks, and both over stream tasks,
+/// mirroring the frontend's task selection. `WATCH_VIDEO_ON_MOBILE` tasks are not
+/// picked: the video completer sends desktop client requests, which don't count for them.
+fn parse_http_quest(
+    quest: &serde_json::Value,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<HttpQuestCandidate> {
+    let quest_id = quest.get("id")?.as_str()?;
+
+    let status = quest.get("user_status").filter(|v| !v.is_null());
+    let is_set = |key: &str| {
+        status
+            .and_then(|s| s.get(key))
+            .is_some_and(|v| !v.is_null())
+    };
+    if is_set("completed_at") {
+        return None;
+    }
+
+    let config = quest.get("config")?;
+    if is_expired(quest, now) {
+        return None;
+    }
+
+    let tasks = quest_tasks(config);
+    let target_of = |types: &[&str]| {
+        tasks
+            .iter()
+            .find(|task| types.contains(&task.task_type) && task.target > 0)
+            .map(|task| (task.key, task.target))
+    };
+    let play = config
+        .pointer("/application/id")
+        .and_then(|v| v.as_str())
+        .and_then(|id| Some((id.to_string(), target_of(&["PLAY_ON_DESKTOP"])?)));
+
+    let (kind, (task_key, target)) = if let Some(found) = target_of(&["WATCH_VIDEO"]) {
+        (HttpQuestKind::Video, found)
+    } else if let Some((application_id, found)) = play {
+        (HttpQuestKind::Game { application_id }, found)
+    } else {
+        (HttpQuestKind::Stream, target_of(&["STREAM_ON_DESKTOP"])?)
+    };
+
+    let done = status
+        .and_then(|s| s.pointer(&format!("/progress/{}/value", task_key)))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+
+    Some(HttpQuestCandidate {
+        job: HttpQuestJob {
+            quest_id: quest_id.to_string(),
+            kind,
+            seconds_needed: target as u32,
+            initial_progress: (done / target as f64 * 100.0).clamp(0.0, 100.0),
+        },
+        needs_enroll: !is_set("enrolled_at"),
+    })
+}
+
+/// Every task of a quest with a target, and which HTTP flow can complete it
+///
+/// Quests with several tasks (e.g. video and desktop play) get one plan per task, in
+/// the same preference order the quest list uses to pick the displayed task.
+/// `WATCH_VIDEO_ON_MOBILE` plans support no flow (see `MOBILE_VIDEO_UNSUPPORTED`).
+pub fn quest_plans(quest: &serde_json::Value) -> Vec<QuestPlan> {
+    let Some(config) = quest.get("config") else {
+        return Vec::new();
+    };
+
+    quest_tasks(config)
+        .into_iter()
+        .map(
+            |QuestTask {
+                 key,
+                 task_type,
+                 target,
+                 task,
+             }| {
+                let done = quest
+                    .pointer(&format!("/user_status/progress/{}/value", key))
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                let application_id = config
+                    .pointer("/application/id")
+                    .or_else(|| task.pointer("/applications/0/id"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+
+                QuestPlan {
+                    task_type: task_type.to_string(),
+                    seconds_needed: target as u32,
+                    initial_progress: if target > 0 {
+                        (done / target as f64 * 100.0).clamp(0.0, 100.0)
+                    } else {
+                        0.0
+                    },
+                    supports_heartbeat: task_type == "STREAM_ON_DESKTOP"
+                        || (task_type == "PLAY_ON_DESKTOP" && application_id.is_some()),
+                    supports_video: task_type == "WATCH_VIDEO",
+                    application_id,
+                }
+            },
+        )
+        .collect()
+}
+
+/// Error for a quest that can't be started because its video task is mobile-only
+///
+/// Discord only counts `WATCH_VIDEO_ON_MOBILE` progress reported by the mobile app,
+/// and the video completer sends desktop client requests.
+pub const MOBILE_VIDEO_UNSUPPORTED: &str =
+    "WATCH_VIDEO_ON_MOBILE tasks can only be completed in the Discord mobile app";
+
+/// Whether any of `plans` is a mobile video task
+pub fn has_mobile_video_task(plans: &[QuestPlan]) -> bool {
+    plans
+        .iter()
+        .any(|plan| plan.task_type == "WATCH_VIDEO_ON_MOBILE")
+}
+
+fn quest_list(quests: &serde_json::Value) -> &[serde_json::Value] {
+    quests
+        .get("quests")
+        .unwrap_or(quests)
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Pick the next enrolled, incomplete, unexpired quest that the HTTP completers can run
+///
+/// `quests` is the raw `/quests/@me` response (or its `quests` array). Stream quests are
+/// skipped, since chaining only covers video and desktop play tasks.
+pub fn find_next_http_quest(quests: &serde_json::Value, exclude_id: &str) -> Option<HttpQuestJob> {
+    let now = chrono::Utc::now();
+
+    quest_list(quests)
+        .iter()
+        .filter_map(|quest| parse_http_quest(quest, now))
+        .find(|candidate| {
+            candidate.job.quest_id != exclude_id
+                && !candidate.needs_enroll
+                && candidate.job.kind != HttpQuestKind::Stream
+        })
+        .map(|candidate| candidate.job)
+}
+
+/// Every quest in `/quests/@me` that is enrolled or enrollable, not completed, and
+/// runnable by the HTTP completers, in list order
+pub fn find_batch_quests(quests: &serde_json::Value) -> Vec<HttpQuestCandidate> {
+    let now = chrono::Utc::now();
+
+    quest_list(quests)
+        .iter()
+        .filter_map(|quest| parse_http_quest(quest, now))
+        .collect()
+}
+
+/// One quest in a `/quests/@me` response
+pub fn find_quest<'a>(
+    quests: &'a serde_json::Value,
+    quest_id: &str,
+) -> Option<&'a serde_json::Value> {
+    quest_list(quests)
+        .iter()
+        .find(|q| q.get("id").and_then(|v| v.as_str()) == Some(quest_id))
+}
+
+/// The HTTP job for one quest in a `/quests/@me` response, if it can still be completed
+pub fn find_quest_candidate(
+    quests: &serde_json::Value,
+    quest_id: &str,
+) -> Option<HttpQuestCandidate> {
+    find_quest(quests, quest_id).and_then(|quest| parse_http_quest(quest, chrono::Utc::now()))
+}
+
+/// Match saved progress against the current `/quests/@me` response
+///
+/// Returns the quests that can be resumed (starting from the further of the saved and
+/// server-reported progress) and the ids of saved quests that are done with: completed
+/// or expired. Saved quests missing from the response (e.g. another account's) are left alone.
+pub fn match_saved_progress(
+    saved: &[SavedQuestProgress],
+    quests: &serde_json::Value,
+) -> (Vec<HttpQuestJob>, Vec<String>) {
+    let now = chrono::Utc::now();
+    let list = quest_list(quests);
+    let mut resumable = Vec::new();
+    let mut stale = Vec::new();
+
+    for entry in saved {
+        let Some(quest) = list
+            .iter()
+            .find(|q| q.get("id").and_then(|v| v.as_str()) == Some(entry.quest_id.as_str()))
+        else {
+            continue;
+        };
+
+        let is_completed = quest
+            .pointer("/user_status/completed_at")
+            .is_some_and(|v| !v.is_null());
+        if is_completed || is_expired(quest, now) {
+            stale.push(entry.quest_id.clone());
+            continue;
+        }
+
+        if let Some(candidate) = parse_http_quest(quest, now).filter(|c| !c.needs_enroll) {
+            let mut job = candidate.job;
+            job.initial_progress = job.initial_progress.max(entry.progress);
+            resumable.push(job);
+        }
+    }
+
+    (resumable, stale)
+}
+
+/// Apply the completion policy after a quest finished
+///
+/// Claims the reward for `Claim`/`ClaimAndNext` and, for `ClaimAndNext`, returns the
+/// next quest to start. Claim failures (e.g. a captcha requirement) are reported but
+/// don't prevent moving on to the next quest.
+pub async fn apply_completion_policy(
+    client: &DiscordApiClient,
+    quest_id: &str,
+    policy: CompletionPolicy,
+    app_handle: &tauri::AppHandle,
+) -> Option<HttpQuestJob> {
+    use crate::logger::{log, LogCategory, LogLevel};
+
+    if policy == CompletionPolicy::Stop {
+        return None;
+    }
+
+    match client.claim_quest_reward(quest_id, None).await {
+        Ok(reward) => {
+            log(
+                LogLevel::Info,
+                LogCategory::Quest,
+                &format!("Claimed reward for quest {}", quest_id),
+                None,
+            );
+            emit_event(
+                app_handle,
+                "quest-claimed",
+                serde_json::json!({ "quest_id": quest_id, "reward": reward }),
+            );
+        }
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                &format!("Failed to auto-claim reward for quest {}", quest_id),
+                Some(&e.to_string()),
+            );
+            emit_event(
+                app_handle,
+                "quest-error",
+                format!("Auto-claim failed: {}", e),
+            );
+        }
+    }
+
+    if policy != CompletionPolicy::ClaimAndNext {
+        return None;
+    }
+
+    let quests = match client.get_quests_raw().await {
+        Ok(quests) => quests,
+        Err(e) => {
+            log(
+                LogLevel::Warn,
+                LogCategory::Quest,
+                "Failed to fetch quests for the next quest",
+                Some(&e.to_string()),
+            );
+            return None;
+        }
+    };
+
+    let next = find_next_http_quest(&quests, quest_id);
+    if next.is_none() {
+        log(
+            LogLevel::Info,
+            LogCategory::Quest,
+            "No further quests to run",
+            None,
+        );
+    }
+    next
 }
 
-#[allow(dead_code)]
 fn generate_stream_key() -> String {
     use rand::distr::Alphanumeric;
     use rand::RngExt;
@@ -252,4 +1491,767 @@ mod tests {
         assert_ne!(key1, key2);
         assert_eq!(key1.len(), 39); // "stream_" + 32 chars
     }
+
+    #[test]
+    fn test_find_next_http_quest() {
+        let quests = serde_json::json!({ "quests": [
+            {
+                "id": "done",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": "2025-01-02T00:00:00Z" }
+            },
+            {
+                "id": "not-enrolled",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": null
+            },
+            {
+                "id": "current",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": null }
+            },
+            {
+                "id": "mobile",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO_ON_MOBILE": { "type": "WATCH_VIDEO_ON_MOBILE", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": null }
+            },
+            {
+                "id": "game",
+                "config": {
+                    "application": { "id": "42" },
+                    "task_config_v2": { "tasks": { "PLAY_ON_DESKTOP": { "type": "PLAY_ON_DESKTOP", "target": 900 } } }
+                },
+                "user_status": {
+                    "enrolled_at": "2025-01-01T00:00:00Z",
+                    "completed_at": null,
+                    "progress": { "PLAY_ON_DESKTOP": { "value": 450 } }
+                }
+            }
+        ]});
+
+        let next = find_next_http_quest(&quests, "current").unwrap();
+        assert_eq!(next.quest_id, "game");
+        assert_eq!(
+            next.kind,
+            HttpQuestKind::Game {
+                application_id: "42".to_string()
+            }
+        );
+        assert_eq!(next.seconds_needed, 900);
+        assert_eq!(next.initial_progress, 50.0);
+
+        assert_eq!(
+            find_next_http_quest(&quests, "game").unwrap().quest_id,
+            "current"
+        );
+    }
+
+    #[test]
+    fn test_quest_plans_cover_every_task() {
+        let quest = serde_json::json!({
+            "id": "1",
+            "config": {
+                "application": { "id": "42" },
+                "task_config_v2": { "tasks": {
+                    "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 30 },
+                    "PLAY_ON_DESKTOP": { "type": "PLAY_ON_DESKTOP", "target": 900 },
+                    "PLAY_ACTIVITY": { "type": "PLAY_ACTIVITY", "target": 600 }
+                } }
+            },
+            "user_status": { "progress": { "PLAY_ON_DESKTOP": { "value": 450 } } }
+        });
+
+        let plans = quest_plans(&quest);
+        assert_eq!(plans.len(), 3);
+
+        let play = plans
+            .iter()
+            .find(|p| p.task_type == "PLAY_ON_DESKTOP")
+            .unwrap();
+        assert!(play.supports_heartbeat && !play.supports_video);
+        assert_eq!(play.application_id.as_deref(), Some("42"));
+        assert_eq!(play.initial_progress, 50.0);
+
+        let video = plans.iter().find(|p| p.task_type == "WATCH_VIDEO").unwrap();
+        assert!(video.supports_video && !video.supports_heartbeat);
+
+        let activity = plans
+            .iter()
+            .find(|p| p.task_type == "PLAY_ACTIVITY")
+            .unwrap();
+        assert!(!activity.supports_video && !activity.supports_heartbeat);
+
+        assert_eq!(plans[0].task_type, "WATCH_VIDEO");
+        assert!(!has_mobile_video_task(&plans));
+
+        let mobile = serde_json::json!({
+            "id": "4",
+            "config": { "task_config": { "tasks": {
+                "WATCH_VIDEO_ON_MOBILE": { "type": "WATCH_VIDEO_ON_MOBILE", "target": 60 }
+            } } }
+        });
+        let plans = quest_plans(&mobile);
+        assert!(!plans[0].supports_video && !plans[0].supports_heartbeat);
+        assert!(has_mobile_video_task(&plans));
+
+        // camelCase task configs are read like the quest list reads them
+        let camel = serde_json::json!({
+            "id": "3",
+            "config": { "taskConfigV2": { "tasks": {
+                "STREAM_ON_DESKTOP": { "type": "STREAM_ON_DESKTOP", "target": 900 }
+            } } }
+        });
+        let plans = quest_plans(&camel);
+        assert_eq!(plans.len(), 1);
+        assert!(plans[0].supports_heartbeat);
+
+        assert!(quest_plans(&serde_json::json!({ "id": "2" })).is_empty());
+    }
+
+    #[test]
+    fn test_find_batch_quests() {
+        let quests = serde_json::json!({ "quests": [
+            {
+                "id": "done",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": "2025-01-02T00:00:00Z" }
+            },
+            {
+                "id": "expired",
+                "config": {
+                    "expires_at": "2000-01-01T00:00:00+00:00",
+                    "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } }
+                },
+                "user_status": null
+            },
+            {
+                "id": "stream",
+                "config": { "task_config_v2": { "tasks": { "STREAM_ON_DESKTOP": { "type": "STREAM_ON_DESKTOP", "target": 900 } } } },
+                "user_status": null
+            },
+            {
+                "id": "video",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": null }
+            }
+        ]});
+
+        let batch = find_batch_quests(&quests);
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].job.quest_id, "stream");
+        assert_eq!(batch[0].job.kind, HttpQuestKind::Stream);
+        assert!(batch[0].needs_enroll);
+        assert_eq!(batch[1].job.quest_id, "video");
+        assert!(!batch[1].needs_enroll);
+
+        // Chaining never picks stream quests or quests that still need enrolling
+        assert!(find_next_http_quest(&quests, "video").is_none());
+
+        assert_eq!(
+            find_quest_candidate(&quests, "stream").map(|c| c.job.kind.mode()),
+            Some("stream")
+        );
+        assert!(find_quest_candidate(&quests, "done").is_none());
+        assert!(find_quest_candidate(&quests, "missing").is_none());
+    }
+
+    #[test]
+    fn test_match_saved_progress() {
+        let quests = serde_json::json!({ "quests": [
+            {
+                "id": "done",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } } },
+                "user_status": { "enrolled_at": "2025-01-01T00:00:00Z", "completed_at": "2025-01-02T00:00:00Z" }
+            },
+            {
+                "id": "expired",
+                "config": {
+                    "expires_at": "2020-01-01T00:00:00Z",
+                    "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 60 } } }
+                },
+                "user_status": { "enrolled_at": "2019-12-01T00:00:00Z", "completed_at": null }
+            },
+            {
+                "id": "video",
+                "config": { "task_config": { "tasks": { "WATCH_VIDEO": { "type": "WATCH_VIDEO", "target": 600 } } } },
+                "user_status": {
+                    "enrolled_at": "2025-01-01T00:00:00Z",
+                    "completed_at": null,
+                    "progress": { "WATCH_VIDEO": { "value": 60 } }
+                }
+            }
+        ]});
+        let saved = |quest_id: &str, progress: f64| SavedQuestProgress {
+            quest_id: quest_id.to_string(),
+            kind: HttpQuestKind::Video,
+            seconds_needed: 600,
+            current_seconds: progress * 6.0,
+            progress,
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+
+        let (resumable, stale) = match_saved_progress(
+            &[
+                saved("done", 50.0),
+                saved("expired", 50.0),
+                saved("video", 25.0),
+                saved("other", 10.0),
+            ],
+            &quests,
+        );
+        assert_eq!(stale, vec!["done".to_string(), "expired".to_string()]);
+        assert_eq!(resumable.len(), 1);
+        assert_eq!(resumable[0].quest_id, "video");
+        assert_eq!(resumable[0].initial_progress, 25.0);
+
+        // Server progress wins when it is further along
+        let (resumable, _) = match_saved_progress(&[saved("video", 5.0)], &quests);
+        assert_eq!(resumable[0].initial_progress, 10.0);
+    }
+
+    #[test]
+    fn test_next_video_timestamp() {
+        // Multiplier mode: advance speed * waited
+        assert_eq!(next_video_timestamp(10.0, 0.0, 5.0, 5.0, 2.0, 0.0), 20.0);
+
+        // Watch-ahead mode: stay the buffer ahead of real elapsed time
+        assert_eq!(next_video_timestamp(0.0, 0.0, 5.0, 5.0, 2.0, 30.0), 35.0);
+        assert_eq!(next_video_timestamp(35.0, 0.0, 10.0, 5.0, 2.0, 30.0), 40.0);
+
+        // Resumed quests count from the initial progress, never backwards
+        assert_eq!(
+            next_video_timestamp(100.0, 60.0, 5.0, 5.0, 1.0, 30.0),
+            100.0
+        );
+    }
+
+    /// Everything a completer reported, in order
+    #[derive(Debug)]
+    enum SinkEvent {
+        Progress(QuestProgressEvent),
+        Complete(String),
+        /// Final `current_seconds`, if any
+        Stopped(Option<f64>),
+        Error,
+        Warning,
+        /// New speed multiplier
+        Throttled(f64),
+        /// Limit in seconds
+        TimedOut(u64),
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: std::sync::Mutex<Vec<SinkEvent>>,
+    }
+
+    impl RecordingSink {
+        fn take(&self) -> Vec<SinkEvent> {
+            std::mem::take(&mut *self.events.lock().unwrap())
+        }
+    }
+
+    impl ProgressSink for RecordingSink {
+        fn progress(&self, evt: QuestProgressEvent) {
+            self.events.lock().unwrap().push(SinkEvent::Progress(evt));
+        }
+
+        fn complete(&self, quest_id: &str) {
+            let evt = SinkEvent::Complete(quest_id.to_string());
+            self.events.lock().unwrap().push(evt);
+        }
+
+        fn stopped(&self, last_progress: Option<QuestProgressEvent>) {
+            let evt = SinkEvent::Stopped(last_progress.map(|p| p.current_seconds));
+            self.events.lock().unwrap().push(evt);
+        }
+
+        fn error(&self, _msg: &str) {
+            self.events.lock().unwrap().push(SinkEvent::Error);
+        }
+
+        fn warning(&self, _msg: &str) {
+            self.events.lock().unwrap().push(SinkEvent::Warning);
+        }
+
+        fn throttled(&self, evt: QuestThrottledEvent) {
+            let evt = SinkEvent::Throttled(evt.speed_multiplier);
+            self.events.lock().unwrap().push(evt);
+        }
+
+        fn timed_out(&self, evt: QuestTimeoutEvent) {
+            let evt = SinkEvent::TimedOut(evt.limit_secs);
+            self.events.lock().unwrap().push(evt);
+        }
+    }
+
+    /// `(current_seconds, eta_seconds)` of each progress event, then the completed quest id
+    fn progress_trace(events: &[SinkEvent]) -> (Vec<(f64, f64)>, Option<&str>) {
+        let trace = events
+            .iter()
+            .filter_map(|e| match e {
+                SinkEvent::Progress(p) => Some((p.current_seconds, p.eta_seconds)),
+                _ => None,
+            })
+            .collect();
+        let completed = match events.last() {
+            Some(SinkEvent::Complete(quest_id)) => Some(quest_id.as_str()),
+            _ => None,
+        };
+        (trace, completed)
+    }
+
+    fn test_client() -> DiscordApiClient {
+        DiscordApiClient::new("test-token".to_string(), None).unwrap()
+    }
+
+    /// Answers the completers like Discord would, recording every call
+    #[derive(Default)]
+    struct MockApi {
+        calls: std::sync::Mutex<Vec<String>>,
+        /// Report the quest as completed from this call on (1-based)
+        complete_on_call: Option<usize>,
+        /// Most video progress recorded per update, like Discord clamping fast updates
+        max_video_step: Option<f64>,
+        video_progress: std::sync::Mutex<f64>,
+    }
+
+    impl MockApi {
+        /// Record a call, returning whether the quest is now completed
+        fn call(&self, call: String) -> bool {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(call);
+            self.complete_on_call.is_some_and(|at| calls.len() >= at)
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl QuestApi for MockApi {
+        async fn update_video_progress(
+            &self,
+            quest_id: &str,
+            timestamp: f64,
+        ) -> Result<VideoProgressUpdate> {
+            let completed = self.call(format!("video {} {:.0}", quest_id, timestamp));
+            let mut progress = self.video_progress.lock().unwrap();
+            *progress = match self.max_video_step {
+                Some(step) => timestamp.min(*progress + step),
+                None => timestamp,
+            };
+            Ok(VideoProgressUpdate {
+                completed,
+                progress: Some(*progress),
+            })
+        }
+
+        async fn send_game_heartbeat(
+            &self,
+            quest_id: &str,
+            application_id: &str,
+            terminal: bool,
+        ) -> Result<bool> {
+            Ok(self.call(format!("game {} {} {}", quest_id, application_id, terminal)))
+        }
+
+        async fn send_stream_heartbeat(&self, quest_id: &str, stream_key: &str) -> Result<()> {
+            self.call(format!("stream {} {}", quest_id, stream_key));
+            Ok(())
+        }
+
+        async fn accept_quest(&self, quest_id: &str) -> Result<serde_json::Value> {
+            self.call(format!("enroll {}", quest_id));
+            Ok(serde_json::json!({}))
+        }
+
+        async fn wait_for_detected_game(
+            &self,
+            application_id: &str,
+            _timeout: Duration,
+        ) -> Result<bool> {
+            self.call(format!("detect {}", application_id));
+            Ok(true)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_game_heartbeat_timing() {
+        let client = test_client();
+        let sink = RecordingSink::default();
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let started = tokio::time::Instant::now();
+
+        let outcome = complete_game_quest_via_heartbeat(
+            &client,
+            "q1".to_string(),
+            "42".to_string(),
+            150,
+            0.0,
+            None,
+            true,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, QuestOutcome::Completed);
+        // Three beats, one interval apart; the last one is terminal and not followed by a wait
+        assert_eq!(started.elapsed(), Duration::from_secs(120));
+        let events = sink.take();
+        let (trace, completed) = progress_trace(&events);
+        assert_eq!(trace, vec![(60.0, 120.0), (120.0, 60.0), (150.0, 0.0)]);
+        assert_eq!(completed, Some("q1"));
+    }
+
+    #[test]
+    fn test_default_max_duration() {
+        let job = |kind: HttpQuestKind, initial_progress: f64| HttpQuestJob {
+            quest_id: "q1".to_string(),
+            kind,
+            seconds_needed: 1800,
+            initial_progress,
+        };
+        let video = |speed_multiplier: f64| VideoSettings {
+            speed_multiplier,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            default_max_duration(&job(HttpQuestKind::Stream, 0.0), &video(1.0)),
+            Duration::from_secs(5400)
+        );
+        // Slower videos get proportionally more time
+        assert_eq!(
+            default_max_duration(&job(HttpQuestKind::Video, 0.0), &video(0.5)),
+            Duration::from_secs(10800)
+        );
+        // Nearly done quests still get the minimum
+        assert_eq!(
+            default_max_duration(&job(HttpQuestKind::Stream, 99.0), &video(1.0)),
+            MIN_MAX_DURATION
+        );
+    }
+
+    #[test]
+    fn test_default_max_duration_watch_ahead() {
+        let job = HttpQuestJob {
+            quest_id: "q1".to_string(),
+            kind: HttpQuestKind::Video,
+            seconds_needed: 1800,
+            initial_progress: 0.0,
+        };
+        let video = VideoSettings {
+            speed_multiplier: 8.0,
+            watch_ahead_secs: 600.0,
+            ..Default::default()
+        };
+
+        // Watch-ahead ignores the speed: the run takes 1200s, well past 1800 / 8 * 3.
+        // Throttling can slow it to real time, so the cap covers that too.
+        let limit = default_max_duration(&job, &video);
+        assert!(limit.as_secs_f64() > video_eta_secs(1800.0, 8.0, 600.0));
+        assert_eq!(limit, Duration::from_secs(5400));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_time_limit_stops_runaway_quest() {
+        let client = test_client();
+        let sink = RecordingSink::default();
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let started = tokio::time::Instant::now();
+
+        // A 1h quest with a 5 minute cap
+        let run = complete_game_quest_via_heartbeat(
+            &client,
+            "q1".to_string(),
+            "42".to_string(),
+            3600,
+            0.0,
+            None,
+            true,
+            &sink,
+            cancel_rx,
+        );
+        let outcome = with_time_limit("q1", Duration::from_secs(300), &sink, run)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, QuestOutcome::TimedOut);
+        assert_eq!(started.elapsed(), Duration::from_secs(300));
+        let events = sink.take();
+        assert!(matches!(events.last(), Some(SinkEvent::TimedOut(300))));
+        assert!(!events.iter().any(|e| matches!(e, SinkEvent::Complete(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_game_heartbeat_resumes_and_cancels() {
+        let client = test_client();
+        let sink = RecordingSink::default();
+
+        // Half done: only the remaining two of four beats are sent
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let outcome = complete_game_quest_via_heartbeat(
+            &client,
+            "q1".to_string(),
+            "42".to_string(),
+            240,
+            50.0,
+            None,
+            true,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Completed);
+        let events = sink.take();
+        let (trace, _) = progress_trace(&events);
+        assert_eq!(trace, vec![(180.0, 60.0), (240.0, 0.0)]);
+
+        // Cancelled before the first beat: nothing is reported but the stop
+        let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        cancel_tx.send(()).await.unwrap();
+        let outcome = complete_game_quest_via_heartbeat(
+            &client,
+            "q1".to_string(),
+            "42".to_string(),
+            240,
+            0.0,
+            None,
+            true,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Cancelled);
+        assert!(matches!(sink.take().as_slice(), [SinkEvent::Stopped(None)]));
+
+        // Stopped 30s after the second beat: one more beat keeps those 30s
+        let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(90)).await;
+            let _ = cancel_tx.send(()).await;
+        });
+        let outcome = complete_game_quest_via_heartbeat(
+            &client,
+            "q1".to_string(),
+            "42".to_string(),
+            240,
+            0.0,
+            None,
+            true,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Cancelled);
+        let events = sink.take();
+        let (trace, _) = progress_trace(&events);
+        assert_eq!(trace, vec![(60.0, 180.0), (120.0, 120.0)]);
+        assert!(matches!(events.last(), Some(SinkEvent::Stopped(Some(secs))) if *secs == 150.0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_video_quest_pacing() {
+        let client = test_client();
+        let sink = RecordingSink::default();
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let started = tokio::time::Instant::now();
+
+        let outcome = complete_video_quest(
+            &client,
+            "q2".to_string(),
+            40,
+            0.0,
+            2.0,
+            0.0,
+            10,
+            true,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, QuestOutcome::Completed);
+        // 2x speed: 20 real seconds for 40 seconds of video, reported every 10s
+        assert_eq!(started.elapsed(), Duration::from_secs(20));
+        let events = sink.take();
+        let (trace, completed) = progress_trace(&events);
+        assert_eq!(trace, vec![(20.0, 10.0), (40.0, 0.0)]);
+        assert_eq!(completed, Some("q2"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_game_heartbeat_with_api() {
+        let api = MockApi::default();
+        let sink = RecordingSink::default();
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+
+        let outcome = complete_game_quest_via_heartbeat(
+            &api,
+            "q1".to_string(),
+            "42".to_string(),
+            150,
+            0.0,
+            Some(Duration::from_secs(30)),
+            false,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Completed);
+        // Only the last beat is terminal
+        assert_eq!(
+            api.calls(),
+            vec![
+                "detect 42",
+                "game q1 42 false",
+                "game q1 42 false",
+                "game q1 42 true"
+            ]
+        );
+        assert!(matches!(sink.take().last(), Some(SinkEvent::Complete(_))));
+
+        // Discord reporting completion ends the quest early
+        let api = MockApi {
+            complete_on_call: Some(2),
+            ..Default::default()
+        };
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let outcome = complete_game_quest_via_heartbeat(
+            &api,
+            "q1".to_string(),
+            "42".to_string(),
+            600,
+            0.0,
+            None,
+            false,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Completed);
+        assert_eq!(api.calls().len(), 2);
+        let events = sink.take();
+        let (trace, completed) = progress_trace(&events);
+        assert_eq!(trace, vec![(60.0, 540.0), (120.0, 480.0)]);
+        assert_eq!(completed, Some("q1"));
+
+        // Stopped 30s after the second beat: a final non-terminal beat is sent
+        let api = MockApi::default();
+        let (cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(90)).await;
+            let _ = cancel_tx.send(()).await;
+        });
+        let outcome = complete_game_quest_via_heartbeat(
+            &api,
+            "q1".to_string(),
+            "42".to_string(),
+            240,
+            0.0,
+            None,
+            false,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Cancelled);
+        assert_eq!(api.calls(), vec!["game q1 42 false"; 3]);
+        assert!(
+            matches!(sink.take().last(), Some(SinkEvent::Stopped(Some(secs))) if *secs == 150.0)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_video_quest_with_api() {
+        let sink = RecordingSink::default();
+
+        // Completion reported by Discord wins over the local target
+        let api = MockApi {
+            complete_on_call: Some(1),
+            ..Default::default()
+        };
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let outcome = complete_video_quest(
+            &api,
+            "q2".to_string(),
+            100,
+            0.0,
+            2.0,
+            0.0,
+            10,
+            false,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Completed);
+        assert_eq!(api.calls(), vec!["video q2 20"]);
+        let events = sink.take();
+        let (trace, completed) = progress_trace(&events);
+        assert_eq!(trace, vec![(20.0, 40.0)]);
+        assert_eq!(completed, Some("q2"));
+
+        // Discord only records 15s per update: continue from there at half speed
+        let api = MockApi {
+            max_video_step: Some(15.0),
+            ..Default::default()
+        };
+        let (_cancel_tx, cancel_rx) = tokio::sync::mpsc::channel(1);
+        let outcome = complete_video_quest(
+            &api,
+            "q2".to_string(),
+            30,
+            0.0,
+            4.0,
+            0.0,
+            10,
+            false,
+            &sink,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, QuestOutcome::Completed);
+        assert_eq!(api.calls(), vec!["video q2 30", "video q2 30"]);
+        let events = sink.take();
+        assert!(matches!(events.first(), Some(SinkEvent::Throttled(speed)) if *speed == 2.0));
+        let (trace, completed) = progress_trace(&events);
+        assert_eq!(
+            trace.iter().map(|(secs, _)| *secs).collect::<Vec<_>>(),
+            vec![15.0, 30.0]
+        );
+        assert_eq!(completed, Some("q2"));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_enrolled() {
+        let api = MockApi::default();
+        let mut candidate = HttpQuestCandidate {
+            job: HttpQuestJob {
+                quest_id: "q3".to_string(),
+                kind: HttpQuestKind::Stream,
+                seconds_needed: 60,
+                initial_progress: 0.0,
+            },
+            needs_enroll: false,
+        };
+        ensure_enrolled(&api, &candidate).await.unwrap();
+        assert!(api.calls().is_empty());
+
+        candidate.needs_enroll = true;
+        ensure_enrolled(&api, &candidate).await.unwrap();
+        assert_eq!(api.calls(), vec!["enroll q3"]);
+    }
 }
@@ -0,0 +1,213 @@
+//! Quest Profiles
+//!
+//! Named sets of start-command settings (speed, heartbeat interval, watch-ahead) kept in
+//! a JSON file in the app data dir, so frequent users don't re-enter them for every
+//! quest. A few built-in profiles encode the recommended defaults and can't be changed.
+
+use crate::json_store;
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name inside the app data dir
+const STORE_FILE: &str = "quest_profiles.json";
+
+/// Same ranges the settings page allows
+const SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.1..=2.0;
+const HEARTBEAT_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 10..=30;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Parameters a profile prefills for the start commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestProfileSettings {
+    pub speed_multiplier: f64,
+    pub heartbeat_interval: u64,
+    #[serde(default)]
+    pub watch_ahead_secs: f64,
+}
+
+impl QuestProfileSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !SPEED_RANGE.contains(&self.speed_multiplier) {
+            bail!(
+                "speed_multiplier must be between {} and {}",
+                SPEED_RANGE.start(),
+                SPEED_RANGE.end()
+            );
+        }
+        if !HEARTBEAT_INTERVAL_RANGE.contains(&self.heartbeat_interval) {
+            bail!(
+                "heartbeat_interval must be between {} and {} seconds",
+                HEARTBEAT_INTERVAL_RANGE.start(),
+                HEARTBEAT_INTERVAL_RANGE.end()
+            );
+        }
+        if !self.watch_ahead_secs.is_finite() || self.watch_ahead_secs < 0.0 {
+            bail!("watch_ahead_secs must not be negative");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestProfile {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: QuestProfileSettings,
+    /// Shipped with the app; can't be overwritten or deleted
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+fn builtin_profiles() -> Vec<QuestProfile> {
+    vec![
+        QuestProfile {
+            name: "Safe (1x, natural timing)".to_string(),
+            settings: QuestProfileSettings {
+                speed_multiplier: 1.0,
+                heartbeat_interval: 15,
+                watch_ahead_secs: 0.0,
+            },
+            builtin: true,
+        },
+        QuestProfile {
+            name: "Fast (capped)".to_string(),
+            settings: QuestProfileSettings {
+                speed_multiplier: *SPEED_RANGE.end(),
+                heartbeat_interval: *HEARTBEAT_INTERVAL_RANGE.start(),
+                watch_ahead_secs: 0.0,
+            },
+            builtin: true,
+        },
+    ]
+}
+
+fn is_builtin(name: &str) -> bool {
+    builtin_profiles()
+        .iter()
+        .any(|profile| profile.name.eq_ignore_ascii_case(name))
+}
+
+fn store_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    json_store::path(app_handle, STORE_FILE)
+}
+
+fn load_from(path: &Path) -> Vec<QuestProfile> {
+    let mut profiles: Vec<QuestProfile> = json_store::load(path).unwrap_or_default();
+    profiles.retain(|profile| !profile.builtin && !is_builtin(&profile.name));
+    profiles.sort_by_key(|profile| profile.name.to_lowercase());
+    profiles
+}
+
+/// Built-in profiles first, then the user's, by name
+pub fn list(app_handle: &tauri::AppHandle) -> Result<Vec<QuestProfile>> {
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut profiles = builtin_profiles();
+    profiles.extend(load_from(&store_path(app_handle)?));
+    Ok(profiles)
+}
+
+/// Look up a profile by name (case-insensitive)
+pub fn get(app_handle: &tauri::AppHandle, name: &str) -> Result<QuestProfile> {
+    list(app_handle)?
+        .into_iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name.trim()))
+        .with_context(|| format!("No quest profile named '{}'", name.trim()))
+}
+
+/// Save a user profile, replacing one with the same name
+pub fn save(
+    app_handle: &tauri::AppHandle,
+    name: &str,
+    settings: QuestProfileSettings,
+) -> Result<QuestProfile> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Profile name must not be empty");
+    }
+    if is_builtin(name) {
+        bail!("'{}' is a built-in profile and can't be changed", name);
+    }
+    settings.validate()?;
+
+    let profile = QuestProfile {
+        name: name.to_string(),
+        settings,
+        builtin: false,
+    };
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = store_path(app_handle)?;
+    let mut profiles = load_from(&path);
+    profiles.retain(|existing| !existing.name.eq_ignore_ascii_case(name));
+    profiles.push(profile.clone());
+    json_store::save_atomic(&path, &profiles)?;
+    Ok(profile)
+}
+
+/// Delete a user profile; returns whether it existed
+pub fn remove(app_handle: &tauri::AppHandle, name: &str) -> Result<bool> {
+    let name = name.trim();
+    if is_builtin(name) {
+        bail!("'{}' is a built-in profile and can't be deleted", name);
+    }
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = store_path(app_handle)?;
+    let mut profiles = load_from(&path);
+    let before = profiles.len();
+    profiles.retain(|existing| !existing.name.eq_ignore_ascii_case(name));
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    json_store::save_atomic(&path, &profiles)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quest_profile_store() {
+        for profile in builtin_profiles() {
+            assert!(profile.settings.validate().is_ok(), "{}", profile.name);
+        }
+        let settings = |speed: f64, interval: u64| QuestProfileSettings {
+            speed_multiplier: speed,
+            heartbeat_interval: interval,
+            watch_ahead_secs: 0.0,
+        };
+        assert!(settings(2.5, 15).validate().is_err());
+        assert!(settings(1.0, 5).validate().is_err());
+        assert!(is_builtin("fast (CAPPED)"));
+
+        let path = std::env::temp_dir().join(format!(
+            "dqh-profiles-{}/{}",
+            std::process::id(),
+            STORE_FILE
+        ));
+        let user = QuestProfile {
+            name: "evening".to_string(),
+            settings: settings(1.5, 20),
+            builtin: false,
+        };
+        let spoofed = QuestProfile {
+            name: "Fast (capped)".to_string(),
+            settings: settings(2.0, 10),
+            builtin: false,
+        };
+        json_store::save_atomic(&path, &[spoofed, user.clone()]).unwrap();
+        // Entries shadowing a built-in are ignored
+        assert_eq!(load_from(&path), vec![user]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,140 @@
+//! Quest Scheduler
+//!
+//! Stores quests the user asked to start at a later time in a JSON file in the app data
+//! dir, so the schedule survives restarts. The background loop in `lib.rs` picks up
+//! due entries and starts them through the HTTP completers.
+
+use crate::json_store;
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name inside the app data dir
+const STORE_FILE: &str = "scheduled_quests.json";
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A quest waiting to be started
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledQuest {
+    /// ID of this schedule entry (not the quest)
+    pub id: String,
+    pub quest_id: String,
+    /// `video`, `game` or `stream`; `None` picks whatever task the quest has
+    pub mode: Option<String>,
+    /// Unix timestamp (seconds) to start at
+    pub start_at: i64,
+    pub speed_multiplier: Option<f64>,
+    pub heartbeat_interval: Option<u64>,
+}
+
+impl ScheduledQuest {
+    pub fn new(
+        quest_id: String,
+        mode: Option<String>,
+        start_at: i64,
+        speed_multiplier: Option<f64>,
+        heartbeat_interval: Option<u64>,
+    ) -> Result<Self> {
+        if let Some(m) = &mode {
+            if !matches!(m.as_str(), "video" | "game" | "stream") {
+                bail!("Unknown mode '{}', expected video, game or stream", m);
+            }
+        }
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            quest_id,
+            mode,
+            start_at,
+            speed_multiplier,
+            heartbeat_interval,
+        })
+    }
+
+    pub fn is_due(&self, now: i64) -> bool {
+        self.start_at <= now
+    }
+}
+
+fn store_path(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    json_store::path(app_handle, STORE_FILE)
+}
+
+fn load_from(path: &Path) -> Vec<ScheduledQuest> {
+    let mut entries: Vec<ScheduledQuest> = json_store::load(path).unwrap_or_default();
+    entries.sort_by_key(|entry| entry.start_at);
+    entries
+}
+
+fn update<T>(
+    app_handle: &tauri::AppHandle,
+    f: impl FnOnce(&mut Vec<ScheduledQuest>) -> T,
+) -> Result<T> {
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = store_path(app_handle)?;
+    let mut entries = load_from(&path);
+    let result = f(&mut entries);
+    json_store::save_atomic(&path, &entries)?;
+    Ok(result)
+}
+
+/// All scheduled quests, soonest first
+pub fn list(app_handle: &tauri::AppHandle) -> Result<Vec<ScheduledQuest>> {
+    let _guard = STORE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    Ok(load_from(&store_path(app_handle)?))
+}
+
+pub fn add(app_handle: &tauri::AppHandle, entry: ScheduledQuest) -> Result<()> {
+    update(app_handle, |entries| entries.push(entry))
+}
+
+/// Remove a schedule entry; returns whether it existed
+pub fn remove(app_handle: &tauri::AppHandle, id: &str) -> Result<bool> {
+    update(app_handle, |entries| {
+        let before = entries.len();
+        entries.retain(|entry| entry.id != id);
+        entries.len() != before
+    })
+}
+
+/// The earliest entry whose start time has passed
+pub fn next_due(app_handle: &tauri::AppHandle, now: i64) -> Result<Option<ScheduledQuest>> {
+    Ok(list(app_handle)?
+        .into_iter()
+        .find(|entry| entry.is_due(now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_store_round_trip() {
+        assert!(
+            ScheduledQuest::new("1".into(), Some("achievement".into()), 0, None, None).is_err()
+        );
+
+        let later = ScheduledQuest::new("1".into(), Some("video".into()), 200, None, None).unwrap();
+        let sooner = ScheduledQuest::new("2".into(), None, 100, Some(1.5), Some(30)).unwrap();
+        assert_ne!(later.id, sooner.id);
+        assert!(sooner.is_due(100));
+        assert!(!later.is_due(100));
+
+        let path = std::env::temp_dir().join(format!(
+            "dqh-schedule-{}/{}",
+            std::process::id(),
+            STORE_FILE
+        ));
+        assert!(load_from(&path).is_empty());
+        json_store::save_atomic(&path, &[later.clone(), sooner.clone()]).unwrap();
+        assert_eq!(load_from(&path), vec![sooner, later]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -8,17 +8,36 @@
 
 use std::env;
 use std::fs;
-use std::io;
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 /// Main app random name prefix
 const MAIN_APP_PREFIX: &str = "svc_";
 
+/// Env var that captures the relaunched process's stdout/stderr to a sanitized log file
+const STEALTH_LOG_ENV: &str = "DQH_STEALTH_LOG";
+
+/// File name of the captured console output (inside the app log directory)
+const STEALTH_LOG_FILE: &str = "stealth-console.log";
+
+/// Captured lines held until the log directory is known (start-up output)
+const MAX_PENDING_CONSOLE_LINES: usize = 1000;
+
+/// Env var that keeps the stealth copy between launches instead of recopying every time
+const STEALTH_REUSE_ENV: &str = "DQH_STEALTH_REUSE";
+
+/// File (inside the temp directory) holding the name of the reusable stealth copy
+const STEALTH_EXE_RECORD: &str = "dqh_stealth_exe.txt";
+
 /// Flag indicating if current process is running in stealth mode
 static IS_STEALTH_MODE: AtomicBool = AtomicBool::new(false);
 
+/// Captured console output file, set once the app log directory is resolved
+static CONSOLE_LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
 /// Generate random hexadecimal string
 fn generate_random_suffix(length: usize) -> String {
     use rand::RngExt;
@@ -44,6 +63,178 @@ pub fn is_stealth_mode() -> bool {
     IS_STEALTH_MODE.load(Ordering::Relaxed)
 }
 
+/// Check if console capture for the relaunched process is requested
+///
+/// Set `DQH_STEALTH_LOG=1` before starting a release build to enable it. The sanitized
+/// output is written to `stealth-console.log` in the app log directory.
+fn stealth_log_enabled() -> bool {
+    env_flag(STEALTH_LOG_ENV)
+}
+
+/// Check if the stealth copy should be reused across launches
+///
+/// Set `DQH_STEALTH_REUSE=1` to copy the binary only when it changed, which avoids a
+/// fresh antivirus scan of a new executable on every start.
+fn stealth_reuse_enabled() -> bool {
+    env_flag(STEALTH_REUSE_ENV)
+}
+
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Whether two files have identical contents
+fn same_contents(a: &Path, b: &Path) -> bool {
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(meta_a), Ok(meta_b)) if meta_a.len() == meta_b.len() => {}
+        _ => return false,
+    }
+    matches!((fs::read(a), fs::read(b)), (Ok(data_a), Ok(data_b)) if data_a == data_b)
+}
+
+/// The recorded stealth copy, if it still exists and matches `current_exe`
+fn reusable_stealth_exe(current_exe: &Path) -> Option<PathBuf> {
+    let temp_dir = env::temp_dir();
+    let name = fs::read_to_string(temp_dir.join(STEALTH_EXE_RECORD)).ok()?;
+    let name = name.trim();
+    // Only ever a bare file name this module generated
+    if !name.starts_with(MAIN_APP_PREFIX)
+        || !name.ends_with(get_exe_extension())
+        || name.contains(['/', '\\'])
+    {
+        return None;
+    }
+    let path = temp_dir.join(name);
+    same_contents(&path, current_exe).then_some(path)
+}
+
+/// Remember `temp_exe` as the copy to reuse next launch
+fn record_stealth_exe(temp_exe: &Path) {
+    if let Some(name) = temp_exe.file_name().and_then(|n| n.to_str()) {
+        let _ = fs::write(env::temp_dir().join(STEALTH_EXE_RECORD), name);
+    }
+}
+
+/// Whether `exe` is the copy recorded for reuse
+fn is_recorded_stealth_exe(exe: &Path) -> bool {
+    let recorded = fs::read_to_string(env::temp_dir().join(STEALTH_EXE_RECORD));
+    match (recorded, exe.file_name().and_then(|n| n.to_str())) {
+        (Ok(recorded), Some(name)) => recorded.trim() == name,
+        _ => false,
+    }
+}
+
+/// Stdio handles (stdin, stdout, stderr) for the relaunched process
+///
+/// With capture enabled, stdout and stderr feed a pipe the child reads back on stdin
+/// (see `capture_console`), so its output is sanitized before it reaches disk.
+/// Otherwise everything is null.
+fn child_stdio() -> (Stdio, Stdio, Stdio) {
+    if stealth_log_enabled() {
+        let pipe = io::pipe()
+            .and_then(|(reader, writer)| writer.try_clone().map(|clone| (reader, writer, clone)));
+        match pipe {
+            Ok((reader, stdout, stderr)) => {
+                return (
+                    Stdio::from(reader),
+                    Stdio::from(stdout),
+                    Stdio::from(stderr),
+                )
+            }
+            Err(e) => eprintln!(
+                "[Stealth] Failed to create console capture pipe, output will be discarded: {}",
+                e
+            ),
+        }
+    }
+    (Stdio::null(), Stdio::null(), Stdio::null())
+}
+
+/// Read this process's own console output back from stdin and append it to the log
+///
+/// Every line goes through the logger's sanitizer first, so tokens, IDs, emails and
+/// user paths never reach the file. Lines printed before `set_console_log_dir` runs
+/// are held in memory (up to `MAX_PENDING_CONSOLE_LINES`) and written once it does.
+fn capture_console() {
+    std::thread::spawn(|| {
+        let mut pending = Vec::new();
+        let mut file = None;
+
+        let mut stdin = io::stdin().lock();
+        let mut buf = Vec::new();
+
+        loop {
+            // This thread is the pipe's only reader: if it stopped, a full pipe would block
+            // every print in the app. So read raw bytes (children may write non-UTF-8) and
+            // only give up at EOF.
+            buf.clear();
+            match stdin.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(_) => continue,
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_end_matches(['\r', '\n']);
+            pending.push(crate::logger::sanitize_message(line));
+
+            if file.is_none() {
+                let path = CONSOLE_LOG_PATH
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .clone();
+                file = path.and_then(|path| {
+                    fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .ok()
+                });
+            }
+
+            match file.as_mut() {
+                Some(file) => {
+                    for line in pending.drain(..) {
+                        let _ = writeln!(file, "{}", line);
+                    }
+                }
+                None if pending.len() > MAX_PENDING_CONSOLE_LINES => {
+                    pending.remove(0);
+                }
+                None => {}
+            }
+        }
+    });
+}
+
+/// Start writing captured console output to `<dir>/stealth-console.log`
+///
+/// Does nothing unless this is the stealth instance and `DQH_STEALTH_LOG` is set.
+pub fn set_console_log_dir(dir: &Path) {
+    use crate::logger::{log, sanitize_path, LogCategory, LogLevel};
+
+    if !is_stealth_mode() || !stealth_log_enabled() {
+        return;
+    }
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!("[Stealth] Failed to create log directory: {}", e);
+        return;
+    }
+
+    let path = dir.join(STEALTH_LOG_FILE);
+    let shown = sanitize_path(&path.to_string_lossy());
+    *CONSOLE_LOG_PATH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(path);
+    log(
+        LogLevel::Info,
+        LogCategory::General,
+        &format!("Console output is being captured to {}", shown),
+        None,
+    );
+}
+
 /// Generate a random window title that looks like a system process
 pub fn generate_stealth_window_title() -> String {
     use rand::RngExt;
@@ -116,6 +307,10 @@ fn ensure_stealth_mode_impl() -> bool {
         IS_STEALTH_MODE.store(true, Ordering::Relaxed);
         println!("[Stealth] Running in stealth mode as: {}", file_name);
 
+        if stealth_log_enabled() {
+            capture_console();
+        }
+
         // Clean up old temp files
         cleanup_old_temp_files(MAIN_APP_PREFIX);
 
@@ -124,6 +319,21 @@ fn ensure_stealth_mode_impl() -> bool {
 
     println!("[Stealth] Starting stealth mode transition...");
 
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let reuse = stealth_reuse_enabled();
+    if reuse {
+        if let Some(existing) = reusable_stealth_exe(&current_exe) {
+            match spawn_detached_process(&existing, &args) {
+                Ok(_) => {
+                    println!("[Stealth] Reusing stealth process: {:?}", existing);
+                    std::process::exit(0);
+                }
+                Err(e) => eprintln!("[Stealth] Failed to reuse stealth copy: {}", e),
+            }
+        }
+    }
+
     // Generate random name
     let random_suffix = generate_random_suffix(8);
     let ext = get_exe_extension();
@@ -151,9 +361,11 @@ fn ensure_stealth_mode_impl() -> bool {
         }
     }
 
-    // Launch new process
-    let args: Vec<String> = env::args().skip(1).collect();
+    if reuse {
+        record_stealth_exe(&temp_exe);
+    }
 
+    // Launch new process
     let spawn_result = spawn_detached_process(&temp_exe, &args);
 
     match spawn_result {
@@ -181,8 +393,13 @@ fn spawn_detached_process(exe_path: &PathBuf, args: &[String]) -> io::Result<()>
     const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
     const DETACHED_PROCESS: u32 = 0x00000008;
 
+    let (stdin, stdout, stderr) = child_stdio();
+
     Command::new(exe_path)
         .args(args)
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(stderr)
         .creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS)
         .spawn()?;
 
@@ -191,13 +408,13 @@ fn spawn_detached_process(exe_path: &PathBuf, args: &[String]) -> io::Result<()>
 
 #[cfg(target_os = "macos")]
 fn spawn_detached_process(exe_path: &PathBuf, args: &[String]) -> io::Result<()> {
-    use std::process::Stdio;
+    let (stdin, stdout, stderr) = child_stdio();
 
     Command::new(exe_path)
         .args(args)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(stderr)
         .spawn()?;
 
     Ok(())
@@ -205,6 +422,17 @@ fn spawn_detached_process(exe_path: &PathBuf, args: &[String]) -> io::Result<()>
 
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn spawn_detached_process(exe_path: &PathBuf, args: &[String]) -> io::Result<()> {
+    if stealth_log_enabled() {
+        let (stdin, stdout, stderr) = child_stdio();
+        Command::new(exe_path)
+            .args(args)
+            .stdin(stdin)
+            .stdout(stdout)
+            .stderr(stderr)
+            .spawn()?;
+        return Ok(());
+    }
+
     Command::new(exe_path).args(args).spawn()?;
 
     Ok(())
@@ -251,8 +479,11 @@ pub fn cleanup_on_exit() {
         return;
     }
 
-    // Self-destruct current temp file
+    // Self-destruct current temp file, unless it's kept for the next launch
     if let Ok(current_exe) = env::current_exe() {
+        if stealth_reuse_enabled() && is_recorded_stealth_exe(&current_exe) {
+            return;
+        }
         schedule_self_deletion(&current_exe);
     }
 }
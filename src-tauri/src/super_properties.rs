@@ -20,15 +20,98 @@ pub(crate) const DEFAULT_CHROME_VERSION: &str = "138.0.7204.251";
 pub(crate) const DEFAULT_ELECTRON_VERSION: &str = "37.6.0";
 pub(crate) const DEFAULT_OS_VERSION: &str = "10.0.19045";
 pub(crate) const DEFAULT_OS_SDK_VERSION: &str = "19045";
+/// Darwin kernel release reported by the macOS client (macOS 14)
+pub(crate) const DEFAULT_MACOS_OS_VERSION: &str = "23.6.0";
+/// Kernel release reported by the Linux client
+pub(crate) const DEFAULT_LINUX_OS_VERSION: &str = "6.8.0";
 /// Fallback build number when CDP extraction and remote JS fetch both fail.
 /// Updated: June 24th, 2026
 pub(crate) const DEFAULT_CLIENT_BUILD_NUMBER: u64 = 569817;
 pub(crate) const DEFAULT_NATIVE_BUILD_NUMBER: u64 = 84934;
+/// How many builds the one we send may lag Discord's latest before it counts as stale
+pub(crate) const STALE_BUILD_THRESHOLD: u64 = 5000;
 
-pub(crate) fn discord_user_agent(client_version: &str) -> String {
+/// Whether `current` lags `latest` by more than `STALE_BUILD_THRESHOLD` builds
+pub fn build_is_stale(current: u64, latest: u64) -> bool {
+    latest.saturating_sub(current) > STALE_BUILD_THRESHOLD
+}
+
+/// Desktop OS as reported in SuperProperties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Windows,
+    Mac,
+    Linux,
+}
+
+impl Os {
+    /// The platform this build is running on (anything unknown reports as Linux)
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Os::Windows
+        } else if cfg!(target_os = "macos") {
+            Os::Mac
+        } else {
+            Os::Linux
+        }
+    }
+
+    /// Value of the `os` field
+    pub fn name(self) -> &'static str {
+        match self {
+            Os::Windows => "Windows",
+            Os::Mac => "Mac OS X",
+            Os::Linux => "Linux",
+        }
+    }
+
+    /// Platform name in the update manifest URL
+    pub fn manifest_platform(self) -> &'static str {
+        match self {
+            Os::Windows => "win",
+            Os::Mac => "osx",
+            Os::Linux => "linux",
+        }
+    }
+
+    /// Platform name used in the detectable games list (`executables[].os`)
+    pub fn detectable_os(self) -> &'static str {
+        match self {
+            Os::Windows => "win32",
+            Os::Mac => "darwin",
+            Os::Linux => "linux",
+        }
+    }
+
+    /// Platform token inside the Electron user agent
+    fn user_agent_platform(self) -> &'static str {
+        match self {
+            Os::Windows => "Windows NT 10.0; Win64; x64",
+            // Chromium freezes the macOS version in the UA at 10_15_7
+            Os::Mac => "Macintosh; Intel Mac OS X 10_15_7",
+            Os::Linux => "X11; Linux x86_64",
+        }
+    }
+}
+
+/// Architecture name in Discord's format (`x64`, `arm64`, ...)
+pub(crate) fn discord_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "x86" => "ia32",
+        other => other,
+    }
+}
+
+/// Discord desktop user agent for `os`
+pub(crate) fn discord_user_agent_for(os: Os, client_version: &str) -> String {
     format!(
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) discord/{} Chrome/{} Electron/{} Safari/537.36",
-        client_version, DEFAULT_CHROME_VERSION, DEFAULT_ELECTRON_VERSION
+        "Mozilla/5.0 ({}) AppleWebKit/537.36 (KHTML, like Gecko) discord/{} Chrome/{} Electron/{} Safari/537.36",
+        os.user_agent_platform(),
+        client_version,
+        DEFAULT_CHROME_VERSION,
+        DEFAULT_ELECTRON_VERSION
     )
 }
 
@@ -40,6 +123,8 @@ pub enum SourceMode {
     Cdp,
     /// Parsed from Discord website JavaScript
     RemoteJs,
+    /// Remote JS build number saved by an earlier launch
+    Cached,
     /// Use built-in default values (fallback)
     Default,
 }
@@ -49,6 +134,7 @@ impl SourceMode {
         match self {
             SourceMode::Cdp => "cdp",
             SourceMode::RemoteJs => "remote_js",
+            SourceMode::Cached => "cached",
             SourceMode::Default => "default",
         }
     }
@@ -57,6 +143,7 @@ impl SourceMode {
         match self {
             SourceMode::Cdp => "CDP (Discord Client)",
             SourceMode::RemoteJs => "Remote JS",
+            SourceMode::Cached => "Cached (Remote JS)",
             SourceMode::Default => "Default",
         }
     }
@@ -236,21 +323,40 @@ impl Default for HeaderProfile {
 
 impl Default for SuperProperties {
     fn default() -> Self {
+        Self::default_for_os(Os::Windows)
+    }
+}
+
+impl SuperProperties {
+    /// Built-in defaults matching the desktop client on `os`
+    pub fn default_for_os(os: Os) -> Self {
+        let (os_version, os_arch, os_sdk_version, native_build_number) = match os {
+            Os::Windows => (
+                DEFAULT_OS_VERSION,
+                "x64",
+                Some(DEFAULT_OS_SDK_VERSION.to_string()),
+                Some(DEFAULT_NATIVE_BUILD_NUMBER),
+            ),
+            // Only the Windows client reports an SDK version and native build
+            Os::Mac => (DEFAULT_MACOS_OS_VERSION, discord_arch(), None, None),
+            Os::Linux => (DEFAULT_LINUX_OS_VERSION, discord_arch(), None, None),
+        };
+
         Self {
-            os: "Windows".to_string(),
+            os: os.name().to_string(),
             browser: "Discord Client".to_string(),
             release_channel: "stable".to_string(),
             client_version: Some(DEFAULT_CLIENT_VERSION.to_string()),
-            os_version: DEFAULT_OS_VERSION.to_string(),
-            os_arch: Some("x64".to_string()),
-            app_arch: Some("x64".to_string()),
+            os_version: os_version.to_string(),
+            os_arch: Some(os_arch.to_string()),
+            app_arch: Some(os_arch.to_string()),
             system_locale: "en-US".to_string(),
             has_client_mods: false, // Must be false
-            browser_user_agent: discord_user_agent(DEFAULT_CLIENT_VERSION),
+            browser_user_agent: discord_user_agent_for(os, DEFAULT_CLIENT_VERSION),
             browser_version: DEFAULT_ELECTRON_VERSION.to_string(),
-            os_sdk_version: Some(DEFAULT_OS_SDK_VERSION.to_string()),
+            os_sdk_version,
             client_build_number: DEFAULT_CLIENT_BUILD_NUMBER,
-            native_build_number: Some(DEFAULT_NATIVE_BUILD_NUMBER),
+            native_build_number,
             client_event_source: None,
             launch_signature: None,
             client_launch_id: None,
@@ -258,9 +364,7 @@ impl Default for SuperProperties {
             client_app_state: Some("focused".to_string()),
         }
     }
-}
 
-impl SuperProperties {
     /// Builds a Gateway Identify payload (op 2) from the current properties.
     /// The `token` parameter is the user's authentication token.
     pub fn to_gateway_identify_payload(&self, token: &str) -> serde_json::Value {
@@ -344,7 +448,10 @@ pub struct XSuperPropertiesManager {
     // Dynamically obtained client information
     client_version: Option<String>, // e.g., "1.0.9219"
     native_build_number: Option<u64>,
+    release_channel: Option<String>, // e.g., "stable", "canary", "ptb"
     header_profile: HeaderProfile,
+    /// Discord's latest build number, from the last staleness check
+    latest_build_number: Option<u64>,
 }
 
 impl XSuperPropertiesManager {
@@ -362,14 +469,22 @@ impl XSuperPropertiesManager {
             source_client: None,
             client_version: None,
             native_build_number: None,
+            release_channel: None,
             header_profile: HeaderProfile::new(),
+            latest_build_number: None,
         }
     }
 
-    /// Sets client information obtained from Discord Update API
-    pub fn set_client_info(&mut self, version: String, native_build: u64) {
+    /// Sets client information obtained from Discord Update API for `release_channel`
+    pub fn set_client_info(
+        &mut self,
+        version: String,
+        native_build: Option<u64>,
+        release_channel: &str,
+    ) {
         self.client_version = Some(version);
-        self.native_build_number = Some(native_build);
+        self.native_build_number = native_build;
+        self.release_channel = Some(release_channel.to_string());
         // Clear cache to regenerate with new information
         self.cached_super_properties = None;
     }
@@ -403,6 +518,12 @@ impl XSuperPropertiesManager {
         self.cached_super_properties = None;
     }
 
+    /// Sets build number saved to disk by an earlier launch
+    pub fn set_from_cache(&mut self, build_number: u64) {
+        self.set_from_remote_js(build_number);
+        self.source_mode = SourceMode::Cached;
+    }
+
     /// Gets the current source mode
     pub fn get_mode(&self) -> SourceMode {
         self.source_mode
@@ -413,6 +534,24 @@ impl XSuperPropertiesManager {
         self.cached_build_number
     }
 
+    /// Records Discord's latest build number for `is_build_stale`
+    pub fn set_latest_build_number(&mut self, build_number: u64) {
+        self.latest_build_number = Some(build_number);
+    }
+
+    pub fn get_latest_build_number(&self) -> Option<u64> {
+        self.latest_build_number
+    }
+
+    /// Whether the build number we send is far behind Discord's latest (false until checked)
+    pub fn is_build_stale(&self) -> bool {
+        let current = self
+            .cached_build_number
+            .unwrap_or(DEFAULT_CLIENT_BUILD_NUMBER);
+        self.latest_build_number
+            .is_some_and(|latest| build_is_stale(current, latest))
+    }
+
     pub fn client_heartbeat_session_id(&self) -> String {
         self.client_heartbeat_session_id.clone()
     }
@@ -453,6 +592,7 @@ impl XSuperPropertiesManager {
         self.source_mode = SourceMode::Default;
         self.client_version = None;
         self.native_build_number = None;
+        self.release_channel = None;
         // Regenerate session IDs
         self.client_launch_id = generate_client_launch_id();
         self.client_heartbeat_session_id = generate_client_heartbeat_session_id();
@@ -523,7 +663,8 @@ impl XSuperPropertiesManager {
             return cached.clone();
         }
 
-        let mut props = SuperProperties::default();
+        let os = Os::current();
+        let mut props = SuperProperties::default_for_os(os);
         props.launch_signature = Some(self.launch_signature.clone());
         props.client_launch_id = Some(self.client_launch_id.clone());
         props.client_heartbeat_session_id = Some(self.client_heartbeat_session_id.clone());
@@ -536,13 +677,18 @@ impl XSuperPropertiesManager {
         if let Some(ref version) = self.client_version {
             props.client_version = Some(version.clone());
             // Also update browser_user_agent
-            props.browser_user_agent = discord_user_agent(version);
+            props.browser_user_agent = discord_user_agent_for(os, version);
         }
 
-        if let Some(native_build) = self.native_build_number {
+        // Only the Windows client reports a native build
+        if let Some(native_build) = self.native_build_number.filter(|_| os == Os::Windows) {
             props.native_build_number = Some(native_build);
         }
 
+        if let Some(ref channel) = self.release_channel {
+            props.release_channel = channel.clone();
+        }
+
         props
     }
 }
@@ -566,6 +712,77 @@ pub struct DebugInfo {
     pub header_profile: HeaderProfilePreview,
 }
 
+/// A field whose captured value differs from what the app currently sends
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SuperPropertiesDiff {
+    pub field: String,
+    /// `null` when the field is missing from the captured header
+    pub captured: serde_json::Value,
+    /// `null` when the app doesn't send the field
+    pub current: serde_json::Value,
+}
+
+/// A captured X-Super-Properties header, decoded
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedSuperProperties {
+    /// The decoded JSON, pretty-printed
+    pub json: String,
+    /// The typed view, when the payload has every field `SuperProperties` requires
+    pub properties: Option<SuperProperties>,
+    /// Why the typed view is missing
+    pub parse_error: Option<String>,
+    /// Fields that differ from `current`, sorted by name
+    pub differences: Vec<SuperPropertiesDiff>,
+}
+
+/// Decode a base64 X-Super-Properties value and compare it with `current`
+pub fn decode_super_properties(
+    base64: &str,
+    current: &SuperProperties,
+) -> anyhow::Result<DecodedSuperProperties> {
+    use anyhow::Context;
+
+    let bytes = BASE64
+        .decode(base64.trim())
+        .context("Value is not valid base64")?;
+    let decoded: serde_json::Value =
+        serde_json::from_slice(&bytes).context("Decoded value is not JSON")?;
+    let captured = decoded
+        .as_object()
+        .context("Decoded value is not a JSON object")?;
+
+    let current = serde_json::to_value(current)?;
+    let current = current.as_object().cloned().unwrap_or_default();
+    let mut fields: Vec<&String> = captured.keys().chain(current.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    let differences = fields
+        .into_iter()
+        .filter_map(|field| {
+            let captured = captured.get(field).cloned().unwrap_or_default();
+            let current = current.get(field).cloned().unwrap_or_default();
+            (captured != current).then(|| SuperPropertiesDiff {
+                field: field.clone(),
+                captured,
+                current,
+            })
+        })
+        .collect();
+
+    let typed = serde_json::from_value::<SuperProperties>(decoded.clone());
+    let (properties, parse_error) = match typed {
+        Ok(props) => (Some(props), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    Ok(DecodedSuperProperties {
+        json: serde_json::to_string_pretty(&decoded)?,
+        properties,
+        parse_error,
+        differences,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -592,6 +809,50 @@ mod tests {
         assert!(json.contains("\"has_client_mods\":false"));
     }
 
+    #[test]
+    fn test_build_is_stale() {
+        assert!(!build_is_stale(569817, 569817));
+        assert!(!build_is_stale(569817, 569817 + STALE_BUILD_THRESHOLD));
+        assert!(build_is_stale(569817, 569818 + STALE_BUILD_THRESHOLD));
+        // A newer local build than the reported latest is never stale
+        assert!(!build_is_stale(600000, 569817));
+
+        let mut manager = XSuperPropertiesManager::new();
+        assert!(!manager.is_build_stale());
+        manager.set_from_remote_js(569817);
+        manager.set_latest_build_number(569817 + 2 * STALE_BUILD_THRESHOLD);
+        assert!(manager.is_build_stale());
+    }
+
+    #[test]
+    fn test_decode_super_properties_diff() {
+        let current = SuperProperties::default_for_os(Os::Windows);
+        let mut captured = serde_json::to_value(&current).unwrap();
+        captured["client_build_number"] = serde_json::json!(1);
+        captured["has_client_mods"] = serde_json::json!(true);
+        captured["design_id"] = serde_json::json!(0);
+        let base64 = BASE64.encode(captured.to_string());
+
+        let decoded = decode_super_properties(&base64, &current).unwrap();
+        assert!(decoded.properties.is_some());
+        let fields: Vec<&str> = decoded
+            .differences
+            .iter()
+            .map(|d| d.field.as_str())
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["client_build_number", "design_id", "has_client_mods"]
+        );
+        assert_eq!(decoded.differences[1].current, serde_json::Value::Null);
+
+        let partial = BASE64.encode(r#"{"os":"Windows"}"#);
+        let decoded = decode_super_properties(&partial, &current).unwrap();
+        assert!(decoded.properties.is_none() && decoded.parse_error.is_some());
+
+        assert!(decode_super_properties("not base64!", &current).is_err());
+    }
+
     #[test]
     fn test_manager_generates_unique_ids() {
         let manager1 = XSuperPropertiesManager::new();
@@ -612,24 +873,62 @@ mod tests {
         let json_str = String::from_utf8(decoded).unwrap();
         let props: SuperProperties = serde_json::from_str(&json_str).unwrap();
 
-        assert_eq!(props.os, "Windows");
+        assert_eq!(props.os, Os::current().name());
         assert!(props.launch_signature.is_some());
     }
 
+    #[test]
+    fn test_default_for_os_matches_platform() {
+        let windows = SuperProperties::default_for_os(Os::Windows);
+        assert_eq!(windows.os, "Windows");
+        assert!(windows.browser_user_agent.contains("Windows NT 10.0"));
+        assert!(windows.os_sdk_version.is_some());
+
+        let mac = SuperProperties::default_for_os(Os::Mac);
+        assert_eq!(mac.os, "Mac OS X");
+        assert!(mac.browser_user_agent.contains("Macintosh"));
+        assert!(mac.os_sdk_version.is_none());
+
+        let linux = SuperProperties::default_for_os(Os::Linux);
+        assert_eq!(linux.os, "Linux");
+        assert!(linux.browser_user_agent.contains("X11; Linux"));
+        assert!(linux
+            .browser_user_agent
+            .contains(&format!("discord/{}", DEFAULT_CLIENT_VERSION)));
+    }
+
+    #[test]
+    fn test_native_build_only_on_windows() {
+        let mut manager = XSuperPropertiesManager::new();
+        manager.set_client_info("1.0.9999".to_string(), Some(12345), "stable");
+        let props = manager.get_super_properties();
+        if Os::current() == Os::Windows {
+            assert_eq!(props.native_build_number, Some(12345));
+        } else {
+            assert_eq!(props.native_build_number, None);
+        }
+    }
+
     #[test]
     fn client_identity_keeps_user_agent_and_xsp_in_sync() {
         let mut manager = XSuperPropertiesManager::new();
-        manager.set_client_info("1.0.9241".to_string(), 83924);
+        manager.set_client_info("1.0.9241".to_string(), Some(83924), "canary");
         manager.set_from_remote_js(562538);
 
         let identity = manager.get_client_identity_snapshot();
         let props = manager.get_super_properties();
 
+        assert_eq!(props.release_channel, "canary");
         assert_eq!(identity.user_agent, props.browser_user_agent);
         assert_eq!(identity.client_version, props.client_version);
         assert!(identity.user_agent.contains("discord/1.0.9241"));
         assert_eq!(identity.client_build_number, Some(562538));
-        assert_eq!(identity.native_build_number, Some(83924));
+        // Only the Windows client reports a native build
+        if cfg!(target_os = "windows") {
+            assert_eq!(identity.native_build_number, Some(83924));
+        } else {
+            assert_eq!(identity.native_build_number, None);
+        }
     }
 
     #[test]
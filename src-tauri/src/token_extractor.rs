@@ -1,8 +1,11 @@
+use crate::endpoints;
+use crate::error::AppError;
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Windows-specific imports
 #[cfg(target_os = "windows")]
@@ -12,15 +15,16 @@ use windows::Win32::Security::Cryptography::{CryptUnprotectData, CRYPT_INTEGER_B
 #[cfg(target_os = "macos")]
 use security_framework::passwords::get_generic_password;
 /// Discord client type
-#[derive(Debug)]
-enum DiscordClient {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscordClient {
     Stable,
     Canary,
     Ptb,
 }
 
 impl DiscordClient {
-    #[cfg(target_os = "windows")]
+    /// Name of the client's data directory
     fn path(&self) -> &str {
         match self {
             DiscordClient::Stable => "discord",
@@ -29,12 +33,18 @@ impl DiscordClient {
         }
     }
 
-    #[cfg(target_os = "macos")]
-    fn path(&self) -> &str {
-        match self {
-            DiscordClient::Stable => "discord",
-            DiscordClient::Canary => "discordcanary",
-            DiscordClient::Ptb => "discordptb",
+    /// Best guess for a user-supplied data directory, from its folder name
+    fn from_data_dir(dir: &Path) -> Self {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.contains("canary") {
+            DiscordClient::Canary
+        } else if name.contains("ptb") {
+            DiscordClient::Ptb
+        } else {
+            DiscordClient::Stable
         }
     }
 
@@ -58,8 +68,252 @@ impl DiscordClient {
     }
 }
 
-/// Auto-detect and extract Discord tokens (returns all unique tokens found)
-pub fn extract_tokens() -> Result<Vec<String>> {
+/// A token together with where it was found
+#[derive(Debug, Clone)]
+pub struct ExtractedToken {
+    pub token: String,
+    pub client: DiscordClient,
+    /// LevelDB file the token was read from, with the user name redacted
+    pub file_path_redacted: String,
+}
+
+/// A Discord data directory found on disk, with what it holds
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscordInstallation {
+    pub client: DiscordClient,
+    /// Data directory, with the user name redacted
+    pub data_dir_redacted: String,
+    /// Distinct tokens found in it
+    pub token_count: usize,
+    /// Why tokens couldn't be read, if they couldn't
+    pub error: Option<String>,
+}
+
+/// OS credential store failure with platform-specific guidance
+///
+/// Returned (wrapped in `anyhow::Error`) from `extract_tokens` when the encryption
+/// key can't be obtained, so callers can `downcast_ref` it and show the remediation.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CredentialStoreError {
+    pub platform: &'static str,
+    pub reason: String,
+    pub remediation: String,
+}
+
+impl CredentialStoreError {
+    /// Map the HRESULT from `CryptUnprotectData` to a specific diagnosis
+    ///
+    /// Most failures are a DPAPI context mismatch: the key was encrypted under a different
+    /// user, or the user's DPAPI master key changed since Discord saved it.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn dpapi_failed(hresult: i32) -> Self {
+        const SAME_USER: &str = "Run this app as the same Windows user that installed and logged into Discord (not as administrator or a different account)";
+        const RELOGIN: &str = "Open Discord, log out and log back in so it re-encrypts its key for the current Windows user";
+
+        let (cause, remediation) = match hresult as u32 {
+            // NTE_BAD_KEY_STATE: "Key not valid for use in specified state"
+            0x8009_000B => (
+                "the key belongs to another Windows user, or this user's password was reset by an administrator",
+                SAME_USER,
+            ),
+            // HRESULT_FROM_WIN32(ERROR_INVALID_DATA), NTE_BAD_DATA
+            0x8007_000D | 0x8009_0005 => (
+                "the key data is not valid for this Windows profile (copied from another machine or profile, or corrupted)",
+                RELOGIN,
+            ),
+            // HRESULT_FROM_WIN32(ERROR_ACCESS_DENIED)
+            0x8007_0005 => (
+                "access to the DPAPI master key was denied",
+                SAME_USER,
+            ),
+            // HRESULT_FROM_WIN32(ERROR_FILE_NOT_FOUND / ERROR_PATH_NOT_FOUND)
+            0x8007_0002 | 0x8007_0003 => (
+                "the DPAPI master key for this Windows profile could not be found (temporary or roaming profile not fully loaded)",
+                "Sign out of Windows and back in with your normal profile, then try again",
+            ),
+            // HRESULT_FROM_WIN32(RPC_S_SERVER_UNAVAILABLE)
+            0x8007_06BA => (
+                "the domain controller protecting this account's DPAPI key could not be reached",
+                "Connect to your organisation's network (or VPN) and try again",
+            ),
+            _ => ("the operation was rejected by Windows", SAME_USER),
+        };
+
+        Self {
+            platform: "windows",
+            reason: format!(
+                "DPAPI could not decrypt the Discord encryption key: {} (0x{:08X})",
+                cause, hresult as u32
+            ),
+            remediation: remediation.to_string(),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn unrecognized_key_format() -> Self {
+        Self {
+            platform: "windows",
+            reason: "Discord's Local State encryption key is not in the expected DPAPI format"
+                .to_string(),
+            remediation: "Update Discord and this app, then open Discord and log in once"
+                .to_string(),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn keychain_denied(service_name: &str) -> Self {
+        Self {
+            platform: "macos",
+            reason: format!(
+                "Could not read '{}' from the macOS Keychain",
+                service_name
+            ),
+            remediation: "Unlock your login keychain and choose \"Always Allow\" when prompted. If the item is missing, open Discord and log in once so it is created".to_string(),
+        }
+    }
+
+    /// Tokens were found but use an encryption version this extractor can't read
+    ///
+    /// `v20` is Chromium's app-bound encryption: the key is bound to the Discord
+    /// executable, so DPAPI alone can't unwrap it from another process.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    fn unsupported_encryption_version(version: &str) -> Self {
+        Self {
+            platform: std::env::consts::OS,
+            reason: format!(
+                "The installed Discord stores its token with a newer encryption scheme ({}) that can't be read from disk",
+                version
+            ),
+            remediation: "Use Discord Client Integration (launch Discord with CDP) from the settings page, or paste your token manually on the login screen".to_string(),
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn unsupported_platform() -> Self {
+        Self {
+            platform: std::env::consts::OS,
+            reason: "Reading Discord's credential store is only supported on Windows and macOS"
+                .to_string(),
+            remediation: "Paste your token manually on the login screen".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for CredentialStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. {}", self.reason, self.remediation)
+    }
+}
+
+impl std::error::Error for CredentialStoreError {}
+
+/// Discord's legacy token epoch (2011-01-01T00:00:00Z), used by older token timestamps
+const TOKEN_EPOCH_SECS: i64 = 1_293_840_000;
+
+/// Discord snowflake epoch (2015-01-01T00:00:00Z) in milliseconds
+const SNOWFLAKE_EPOCH_MS: i64 = 1_420_070_400_000;
+
+/// Metadata decoded locally from a token's unsigned segments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    /// Embedded user id, already masked for logging
+    pub user_id: String,
+    /// When the token was issued (from the second segment)
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the account was created (from the user id snowflake)
+    pub account_created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Decode a token segment, tolerating both padded and unpadded base64url/base64
+fn decode_token_segment(segment: &str) -> Option<Vec<u8>> {
+    use base64::engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD};
+
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .or_else(|_| URL_SAFE.decode(segment))
+        .or_else(|_| BASE64.decode(segment))
+        .ok()
+}
+
+/// Parse the user id and timestamps embedded in a user token without any network call
+///
+/// Token layout: `base64(user_id).base64(timestamp).hmac`. Only the first two
+/// segments are decoded; the signature is never inspected. Returns an error for
+/// tokens that are obviously malformed, so they can be skipped before validation.
+pub fn parse_token_metadata(token: &str) -> Result<TokenMetadata> {
+    use chrono::{TimeZone, Utc};
+
+    let segments: Vec<&str> = token.trim().split('.').collect();
+    if segments.len() != 3 || segments.iter().any(|s| s.is_empty()) {
+        anyhow::bail!("Token does not have three segments");
+    }
+
+    let id_bytes = decode_token_segment(segments[0]).context("User id segment is not base64")?;
+    let user_id = String::from_utf8(id_bytes).context("User id segment is not UTF-8")?;
+    if !(17..=20).contains(&user_id.len()) || !user_id.bytes().all(|b| b.is_ascii_digit()) {
+        anyhow::bail!("User id segment is not a snowflake");
+    }
+    let snowflake: u64 = user_id.parse().context("User id is out of range")?;
+
+    let account_created_at = Utc
+        .timestamp_millis_opt((snowflake >> 22) as i64 + SNOWFLAKE_EPOCH_MS)
+        .single();
+
+    let created_at = decode_token_segment(segments[1])
+        .filter(|bytes| !bytes.is_empty() && bytes.len() <= 8)
+        .map(|bytes| bytes.iter().fold(0i64, |acc, b| (acc << 8) | *b as i64))
+        .map(|secs| {
+            if secs < TOKEN_EPOCH_SECS {
+                secs + TOKEN_EPOCH_SECS
+            } else {
+                secs
+            }
+        })
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        // Reject values that can't be a real issue time (garbage or far future)
+        .filter(|ts| account_created_at.is_none_or(|acc| *ts >= acc))
+        .filter(|ts| *ts <= Utc::now() + chrono::Duration::days(1));
+
+    Ok(TokenMetadata {
+        user_id: crate::logger::sanitize_user_id(&user_id),
+        created_at,
+        account_created_at,
+    })
+}
+
+/// Reject input that can't be a user token before any request is made
+///
+/// Catches pasted passwords and truncated tokens, which would otherwise only fail
+/// after a round-trip with a generic 401.
+pub fn validate_token_shape(token: &str) -> Result<(), AppError> {
+    parse_token_metadata(token)
+        .map(|_| ())
+        .map_err(|e| AppError::MalformedToken(e.to_string()))
+}
+
+/// Result of scanning all Discord clients
+#[derive(Debug, Clone)]
+pub struct TokenExtraction {
+    pub tokens: Vec<ExtractedToken>,
+    /// Credential store failure from a client that yielded nothing, kept so callers can
+    /// explain why an expected account is missing even when other clients had tokens
+    pub credential_error: Option<CredentialStoreError>,
+}
+
+/// Auto-detect and extract Discord tokens
+///
+/// Scans each client's default data directory (plus known alternatives such as the
+/// Microsoft Store install) and `extra_paths`, for portable installs or relocated
+/// profiles. An extra path is a data directory like `.../discord`, containing
+/// `Local State` and `Local Storage/leveldb`.
+///
+/// Returns one entry per token and client it was found in, so a token present in
+/// several clients reports each source. Callers dedupe on the raw token string.
+/// `only_client` restricts the scan to one channel.
+pub fn extract_tokens(
+    extra_paths: &[PathBuf],
+    only_client: Option<DiscordClient>,
+) -> Result<TokenExtraction> {
     use crate::logger::{log, sanitize_path, LogCategory, LogLevel};
 
     log(
@@ -68,21 +322,23 @@ pub fn extract_tokens() -> Result<Vec<String>> {
         "Starting token extraction",
         None,
     );
-    let mut tokens = std::collections::HashSet::new();
-    let clients = vec![
-        DiscordClient::Stable,
-        DiscordClient::Canary,
-        DiscordClient::Ptb,
-    ];
-
-    for client in clients {
+    let mut tokens: Vec<ExtractedToken> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut credential_error: Option<CredentialStoreError> = None;
+    let mut checked: Vec<String> = Vec::new();
+
+    let dirs = data_dirs_to_scan(extra_paths)
+        .into_iter()
+        .filter(|(client, _)| only_client.is_none_or(|only| only == *client));
+    for (client, dir) in dirs {
+        let display_path = sanitize_path(&dir.to_string_lossy());
         log(
             LogLevel::Debug,
             LogCategory::TokenExtraction,
-            &format!("Checking Discord client: {:?}", client),
+            &format!("Checking Discord client {:?} at {}", client, display_path),
             None,
         );
-        match try_extract_from_client(&client) {
+        match try_extract_from_client(&client, &dir) {
             Ok(client_tokens) => {
                 log(
                     LogLevel::Debug,
@@ -90,13 +346,32 @@ pub fn extract_tokens() -> Result<Vec<String>> {
                     &format!("Found {} tokens in {:?}", client_tokens.len(), client),
                     None,
                 );
-                for token in client_tokens {
-                    tokens.insert(token);
+                checked.push(format!(
+                    "{} ({:?}): {} tokens",
+                    display_path,
+                    client,
+                    client_tokens.len()
+                ));
+                for (token, path) in client_tokens {
+                    if seen.insert((token.clone(), client)) {
+                        tokens.push(ExtractedToken {
+                            token,
+                            client,
+                            file_path_redacted: sanitize_path(&path.to_string_lossy()),
+                        });
+                    }
                 }
             }
             Err(e) => {
+                if let Some(store_error) = e.downcast_ref::<CredentialStoreError>() {
+                    credential_error.get_or_insert_with(|| store_error.clone());
+                }
                 // Sanitize error details to prevent path leakage
                 let sanitized_error = sanitize_path(&e.to_string());
+                checked.push(format!(
+                    "{} ({:?}): {}",
+                    display_path, client, sanitized_error
+                ));
                 log(
                     LogLevel::Debug,
                     LogCategory::TokenExtraction,
@@ -107,10 +382,28 @@ pub fn extract_tokens() -> Result<Vec<String>> {
         }
     }
 
+    // Lets a user reporting "no accounts" confirm their install location was included
+    log(
+        LogLevel::Info,
+        LogCategory::TokenExtraction,
+        &format!("Checked {} Discord data paths", checked.len()),
+        Some(&checked.join("\n")),
+    );
+
+    let unique_count = tokens
+        .iter()
+        .map(|t| t.token.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    crate::metrics::tokens_detected(unique_count);
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
-        &format!("Total unique tokens found: {}", tokens.len()),
+        &format!(
+            "Total unique tokens found: {} ({} sources)",
+            unique_count,
+            tokens.len()
+        ),
         None,
     );
 
@@ -121,31 +414,133 @@ pub fn extract_tokens() -> Result<Vec<String>> {
             "No tokens found in any Discord client",
             None,
         );
+        // Prefer the actionable credential store error over the generic message
+        if let Some(store_error) = credential_error {
+            return Err(store_error.into());
+        }
         anyhow::bail!("Could not find tokens in any Discord client")
     }
 
-    Ok(tokens.into_iter().collect())
+    Ok(TokenExtraction {
+        tokens,
+        credential_error,
+    })
+}
+
+/// Data directories present on disk, with how many tokens each holds
+///
+/// Lets users with several logged-in clients pick which one `extract_tokens` reads.
+pub fn list_installations(extra_paths: &[PathBuf]) -> Vec<DiscordInstallation> {
+    use crate::logger::sanitize_path;
+
+    data_dirs_to_scan(extra_paths)
+        .into_iter()
+        .filter(|(_, dir)| dir.is_dir())
+        .map(|(client, dir)| {
+            let (token_count, error) = match try_extract_from_client(&client, &dir) {
+                Ok(tokens) => {
+                    let unique: std::collections::HashSet<_> =
+                        tokens.into_iter().map(|(token, _)| token).collect();
+                    (unique.len(), None)
+                }
+                Err(e) => (0, Some(sanitize_path(&e.to_string()))),
+            };
+            DiscordInstallation {
+                client,
+                data_dir_redacted: sanitize_path(&dir.to_string_lossy()),
+                token_count,
+                error,
+            }
+        })
+        .collect()
 }
 
+/// Default and alternative data directories for every client, then `extra_paths`,
+/// without duplicates
+fn data_dirs_to_scan(extra_paths: &[PathBuf]) -> Vec<(DiscordClient, PathBuf)> {
+    let mut dirs: Vec<(DiscordClient, PathBuf)> = Vec::new();
+    let defaults = [
+        DiscordClient::Stable,
+        DiscordClient::Canary,
+        DiscordClient::Ptb,
+    ]
+    .into_iter()
+    .flat_map(|client| {
+        default_data_dirs(client)
+            .into_iter()
+            .map(move |dir| (client, dir))
+    });
+    let extras = extra_paths
+        .iter()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| (DiscordClient::from_data_dir(dir), dir.clone()));
+
+    for (client, dir) in defaults.chain(extras) {
+        if !dirs.iter().any(|(_, existing)| *existing == dir) {
+            dirs.push((client, dir));
+        }
+    }
+    dirs
+}
+
+/// `%APPDATA%\<client>`, then the Microsoft Store package, whose AppData is virtualized
+/// under `%LOCALAPPDATA%\Packages\<package>\LocalCache\Roaming`
 #[cfg(target_os = "windows")]
-fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
-    use crate::logger::{log, sanitize_path, LogCategory, LogLevel};
+fn default_data_dirs(client: DiscordClient) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(appdata) = std::env::var_os("APPDATA") {
+        dirs.push(PathBuf::from(appdata).join(client.path()));
+    }
+    if let Some(local_appdata) = std::env::var_os("LOCALAPPDATA") {
+        let packages = PathBuf::from(local_appdata).join("Packages");
+        dirs.extend(
+            fs::read_dir(packages)
+                .into_iter()
+                .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .to_ascii_lowercase()
+                        .contains("discord")
+                })
+                .map(|entry| {
+                    entry
+                        .path()
+                        .join("LocalCache")
+                        .join("Roaming")
+                        .join(client.path())
+                })
+                .filter(|dir| dir.is_dir()),
+        );
+    }
+    dirs
+}
 
-    // Get APPDATA path
-    let appdata = std::env::var("APPDATA").context("Could not get APPDATA environment variable")?;
+#[cfg(target_os = "macos")]
+fn default_data_dirs(client: DiscordClient) -> Vec<PathBuf> {
+    std::env::var_os("HOME")
+        .map(|home| {
+            vec![PathBuf::from(home)
+                .join("Library/Application Support")
+                .join(client.path())]
+        })
+        .unwrap_or_default()
+}
 
-    // Build Discord path
-    let discord_path = PathBuf::from(appdata).join(client.path());
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn default_data_dirs(client: DiscordClient) -> Vec<PathBuf> {
+    std::env::var_os("HOME")
+        .map(|home| vec![PathBuf::from(home).join(".config").join(client.path())])
+        .unwrap_or_default()
+}
 
-    log(
-        LogLevel::Debug,
-        LogCategory::TokenExtraction,
-        &format!(
-            "Checking Discord path: {}",
-            sanitize_path(&discord_path.to_string_lossy())
-        ),
-        None,
-    );
+#[cfg(target_os = "windows")]
+fn try_extract_from_client(
+    _client: &DiscordClient,
+    discord_path: &Path,
+) -> Result<Vec<(String, PathBuf)>> {
+    use crate::logger::{log, sanitize_path, LogCategory, LogLevel};
 
     // Read Local State file to get encryption key
     let local_state_path = discord_path.join("Local State");
@@ -166,10 +561,20 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
         .context("Could not decode encrypted_key")?;
 
     // Remove "DPAPI" prefix (first 5 bytes)
-    let encrypted_key_bytes = &encrypted_key_bytes[5..];
+    let Some(encrypted_key_bytes) = encrypted_key_bytes.strip_prefix(b"DPAPI") else {
+        return Err(CredentialStoreError::unrecognized_key_format().into());
+    };
 
     // Use Windows DPAPI to decrypt master key
-    let master_key = decrypt_with_dpapi(encrypted_key_bytes)?;
+    let master_key = decrypt_with_dpapi(encrypted_key_bytes).map_err(|e| {
+        log(
+            LogLevel::Debug,
+            LogCategory::TokenExtraction,
+            "DPAPI key decryption failed",
+            Some(&e.reason),
+        );
+        e
+    })?;
 
     // Search for tokens in LevelDB
     let leveldb_path = discord_path.join("Local Storage").join("leveldb");
@@ -188,6 +593,7 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
     }
 
     let mut tokens = Vec::new();
+    let mut unsupported_version = None;
     let mut file_count = 0;
 
     // Read all .ldb and .log files
@@ -200,8 +606,11 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
                 file_count += 1;
                 if let Ok(content) = fs::read(&path) {
                     // Search for all token patterns
-                    let found_tokens = find_and_decrypt_tokens(&content, &master_key);
-                    tokens.extend(found_tokens);
+                    let found = find_and_decrypt_tokens(&content, &master_key);
+                    if found.unsupported_version.is_some() {
+                        unsupported_version = found.unsupported_version;
+                    }
+                    tokens.extend(found.tokens.into_iter().map(|t| (t, path.clone())));
                 }
             }
         }
@@ -218,11 +627,17 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
         None,
     );
 
+    if tokens.is_empty() {
+        if let Some(version) = unsupported_version {
+            return Err(CredentialStoreError::unsupported_encryption_version(&version).into());
+        }
+    }
+
     Ok(tokens)
 }
 
 #[cfg(target_os = "windows")]
-fn decrypt_with_dpapi(data: &[u8]) -> Result<Vec<u8>> {
+fn decrypt_with_dpapi(data: &[u8]) -> std::result::Result<Vec<u8>, CredentialStoreError> {
     use std::ptr;
 
     unsafe {
@@ -239,8 +654,9 @@ fn decrypt_with_dpapi(data: &[u8]) -> Result<Vec<u8>> {
         let result =
             CryptUnprotectData(&mut input_blob, None, None, None, None, 0, &mut output_blob);
 
-        if result.is_err() {
-            anyhow::bail!("DPAPI decryption failed");
+        // The error carries GetLastError() as an HRESULT
+        if let Err(e) = result {
+            return Err(CredentialStoreError::dpapi_failed(e.code().0));
         }
 
         // Copy decrypted data
@@ -252,15 +668,10 @@ fn decrypt_with_dpapi(data: &[u8]) -> Result<Vec<u8>> {
 }
 
 #[cfg(target_os = "macos")]
-fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
-    // Get Application Support path
-    let home = std::env::var("HOME").context("Could not get HOME environment variable")?;
-
-    // Build Discord path
-    let discord_path = PathBuf::from(&home)
-        .join("Library/Application Support")
-        .join(client.path());
-
+fn try_extract_from_client(
+    client: &DiscordClient,
+    discord_path: &Path,
+) -> Result<Vec<(String, PathBuf)>> {
     if !discord_path.exists() {
         anyhow::bail!("Discord path does not exist: {:?}", discord_path);
     }
@@ -281,6 +692,7 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
     }
 
     let mut tokens = Vec::new();
+    let mut unsupported_version = None;
 
     // Read all .ldb and .log files
     for entry in fs::read_dir(&leveldb_path)? {
@@ -291,13 +703,22 @@ fn try_extract_from_client(client: &DiscordClient) -> Result<Vec<String>> {
             if ext == "ldb" || ext == "log" {
                 if let Ok(content) = fs::read(&path) {
                     // Search for all token patterns
-                    let found_tokens = find_and_decrypt_tokens(&content, &master_key);
-                    tokens.extend(found_tokens);
+                    let found = find_and_decrypt_tokens(&content, &master_key);
+                    if found.unsupported_version.is_some() {
+                        unsupported_version = found.unsupported_version;
+                    }
+                    tokens.extend(found.tokens.into_iter().map(|t| (t, path.clone())));
                 }
             }
         }
     }
 
+    if tokens.is_empty() {
+        if let Some(version) = unsupported_version {
+            return Err(CredentialStoreError::unsupported_encryption_version(&version).into());
+        }
+    }
+
     Ok(tokens)
 }
 
@@ -354,11 +775,7 @@ fn get_master_key_from_keychain(client: &DiscordClient) -> Result<Vec<u8>> {
                 );
                 raw_password = password.as_bytes().to_vec();
             } else {
-                anyhow::bail!(
-                    "Could not get Discord Safe Storage key from Keychain.\n\
-                    Make sure Discord is installed and you've logged in at least once.\n\
-                    You may need to grant Keychain access when prompted."
-                );
+                return Err(CredentialStoreError::keychain_denied(service_name).into());
             }
         }
     }
@@ -393,12 +810,36 @@ fn get_master_key_from_keychain(client: &DiscordClient) -> Result<Vec<u8>> {
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-fn try_extract_from_client(_client: &DiscordClient) -> Result<Vec<String>> {
-    anyhow::bail!("Token extraction is only supported on Windows and macOS")
+fn try_extract_from_client(
+    _client: &DiscordClient,
+    _discord_path: &Path,
+) -> Result<Vec<(String, PathBuf)>> {
+    Err(CredentialStoreError::unsupported_platform().into())
 }
 
-fn find_and_decrypt_tokens(data: &[u8], master_key: &[u8]) -> Vec<String> {
-    let mut tokens = Vec::new();
+/// Encryption versions recognised but not decryptable (Chromium app-bound encryption)
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+const UNSUPPORTED_ENCRYPTION_VERSIONS: [&[u8]; 1] = [b"v20"];
+
+/// Tokens decrypted from one LevelDB file
+#[derive(Debug, Default)]
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+struct DecryptedTokens {
+    tokens: Vec<String>,
+    /// Version prefix of an entry that was skipped because its scheme isn't supported
+    unsupported_version: Option<String>,
+}
+
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+fn unsupported_encryption_version(encrypted_data: &[u8]) -> Option<String> {
+    UNSUPPORTED_ENCRYPTION_VERSIONS
+        .iter()
+        .find(|version| encrypted_data.starts_with(version))
+        .map(|version| String::from_utf8_lossy(version).into_owned())
+}
+
+fn find_and_decrypt_tokens(data: &[u8], master_key: &[u8]) -> DecryptedTokens {
+    let mut found = DecryptedTokens::default();
 
     // Convert data to string for regex matching (lossy but simple)
     let content = String::from_utf8_lossy(data);
@@ -407,22 +848,26 @@ fn find_and_decrypt_tokens(data: &[u8], master_key: &[u8]) -> Vec<String> {
     // Pattern: dQw4w9WgXcQ:([Base64])
     let re = match Regex::new(r"dQw4w9WgXcQ:([A-Za-z0-9+/=]+)") {
         Ok(re) => re,
-        Err(_) => return tokens,
+        Err(_) => return found,
     };
 
     for cap in re.captures_iter(&content) {
         if let Some(encrypted_token) = cap.get(1) {
             // Base64 decode
             if let Ok(encrypted_bytes) = BASE64.decode(encrypted_token.as_str()) {
+                if let Some(version) = unsupported_encryption_version(&encrypted_bytes) {
+                    found.unsupported_version = Some(version);
+                    continue;
+                }
                 // Decrypt token
                 if let Ok(token) = decrypt_token(&encrypted_bytes, master_key) {
-                    tokens.push(token);
+                    found.tokens.push(token);
                 }
             }
         }
     }
 
-    tokens
+    found
 }
 
 /// Decrypt token - uses different methods for Windows and macOS
@@ -517,6 +962,41 @@ fn decrypt_token(_encrypted_data: &[u8], _key: &[u8]) -> Result<String> {
     anyhow::bail!("Token decryption is only supported on Windows and macOS")
 }
 
+/// Offline mode: never contact Discord's website or update API
+static OFFLINE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Turn offline mode on or off
+///
+/// For isolated networks where the remote fetches would only time out. Build numbers
+/// then come from CDP, the cache or the built-in defaults.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Proxy for the website and update API fetches, kept in step with the API client's
+static PROXY: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Route the build number and client info fetches through `proxy_url` (`None` = system settings)
+pub fn set_proxy(proxy_url: Option<String>) {
+    *PROXY.write().unwrap_or_else(|p| p.into_inner()) = proxy_url;
+}
+
+/// HTTP client for the unauthenticated Discord fetches, honouring the configured proxy
+fn web_client(user_agent: &str) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(std::time::Duration::from_secs(8))
+        .timeout(std::time::Duration::from_secs(10));
+    if let Some(proxy_url) = PROXY.read().unwrap_or_else(|p| p.into_inner()).as_deref() {
+        builder = builder.proxy(crate::discord_api::parse_proxy_url(proxy_url)?);
+    }
+    builder.build().context("Failed to create HTTP client")
+}
+
 /// Get the latest client_build_number from Discord JavaScript files
 ///
 /// This function will:
@@ -528,6 +1008,10 @@ fn decrypt_token(_encrypted_data: &[u8], _key: &[u8]) -> Result<String> {
 pub async fn fetch_build_number_from_discord() -> Result<u64> {
     use crate::logger::{log, LogCategory, LogLevel};
 
+    if is_offline() {
+        anyhow::bail!("Offline mode is on, not fetching the build number");
+    }
+
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
@@ -535,16 +1019,11 @@ pub async fn fetch_build_number_from_discord() -> Result<u64> {
         None,
     );
 
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .connect_timeout(std::time::Duration::from_secs(8))
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .context("Failed to create HTTP client")?;
+    let client = web_client("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")?;
 
     // Request Discord login page
     let login_page = client
-        .get("https://discord.com/login")
+        .get(endpoints::login_page())
         .send()
         .await
         .context("Failed to fetch Discord login page")?
@@ -557,7 +1036,7 @@ pub async fn fetch_build_number_from_discord() -> Result<u64> {
     let script_re = Regex::new(r#"/assets/[a-z0-9-]+\.[a-f0-9]+\.js"#)?;
     let script_urls: Vec<String> = script_re
         .find_iter(&login_page)
-        .map(|m| format!("https://discord.com{}", m.as_str()))
+        .map(|m| endpoints::web_asset(m.as_str()))
         .collect();
 
     log(
@@ -572,10 +1051,7 @@ pub async fn fetch_build_number_from_discord() -> Result<u64> {
         let alt_script_re = Regex::new(r#"src="(/assets/[a-z0-9-]+\.[a-f0-9]+\.js)""#)?;
         let alt_urls: Vec<String> = alt_script_re
             .captures_iter(&login_page)
-            .filter_map(|cap| {
-                cap.get(1)
-                    .map(|m| format!("https://discord.com{}", m.as_str()))
-            })
+            .filter_map(|cap| cap.get(1).map(|m| endpoints::web_asset(m.as_str())))
             .collect();
 
         if alt_urls.is_empty() {
@@ -588,20 +1064,111 @@ pub async fn fetch_build_number_from_discord() -> Result<u64> {
     fetch_build_number_from_scripts(&client, &script_urls).await
 }
 
+/// Per-script download limit while searching for the build number
+const SCRIPT_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Most bytes read from one script; the build number is usually near the start
+const MAX_SCRIPT_BYTES: usize = 8 * 1024 * 1024;
+
+/// Bytes carried over between chunks so a match split across them is still found
+const SCAN_OVERLAP_BYTES: usize = 256;
+
+/// Searches a script for the build number as it streams in, chunk by chunk
+struct BuildNumberScanner {
+    patterns: Vec<regex::bytes::Regex>,
+    window: Vec<u8>,
+    bytes_read: usize,
+}
+
+impl BuildNumberScanner {
+    fn new() -> Result<Self> {
+        // Multiple possible buildNumber patterns
+        let patterns = [
+            r#"buildNumber["\s:]+(\d{5,})"#,
+            r#"build_number["\s:]+(\d{5,})"#,
+            r#"buildNumber:\s*"?(\d{5,})"?"#,
+            r#""buildNumber"\s*:\s*(\d+)"#,
+        ]
+        .iter()
+        .map(|pattern| regex::bytes::Regex::new(pattern))
+        .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            patterns,
+            window: Vec::new(),
+            bytes_read: 0,
+        })
+    }
+
+    /// Whether `MAX_SCRIPT_BYTES` have been read
+    fn exhausted(&self) -> bool {
+        self.bytes_read >= MAX_SCRIPT_BYTES
+    }
+
+    /// Scan the next chunk, returning the build number once found
+    fn feed(&mut self, chunk: &[u8]) -> Option<u64> {
+        let chunk = &chunk[..chunk
+            .len()
+            .min(MAX_SCRIPT_BYTES.saturating_sub(self.bytes_read))];
+        self.bytes_read += chunk.len();
+        self.window.extend_from_slice(chunk);
+
+        // A digit run at the very end may continue in the next chunk
+        let complete = self.exhausted() || !self.window.last().is_some_and(u8::is_ascii_digit);
+        let found = self.patterns.iter().find_map(|re| {
+            re.captures_iter(&self.window).find_map(|caps| {
+                let digits = caps.get(1)?;
+                if !complete && digits.end() == self.window.len() {
+                    return None;
+                }
+                let build_num = std::str::from_utf8(digits.as_bytes())
+                    .ok()?
+                    .parse::<u64>()
+                    .ok()?;
+                // BUILD NUMBER VALIDATION BOUNDS:
+                // Lower bound (100000): Discord build numbers are typically 6+ digits
+                // Upper bound (9999999): Allow for future growth to 7 digits
+                // If Discord changes their numbering scheme significantly,
+                // these bounds may need adjustment.
+                (100000..=9_999_999)
+                    .contains(&build_num)
+                    .then_some(build_num)
+            })
+        });
+
+        let keep_from = self.window.len().saturating_sub(SCAN_OVERLAP_BYTES);
+        self.window.drain(..keep_from);
+        found
+    }
+}
+
+/// Stream one script, stopping as soon as the build number is found
+async fn scan_script_for_build_number(client: &reqwest::Client, url: &str) -> Result<Option<u64>> {
+    let mut response = client
+        .get(url)
+        .timeout(SCRIPT_FETCH_TIMEOUT)
+        .send()
+        .await
+        .context("Failed to fetch JS file")?;
+
+    let mut scanner = BuildNumberScanner::new()?;
+    while let Some(chunk) = response.chunk().await.context("Failed to read JS file")? {
+        if let Some(build_num) = scanner.feed(&chunk) {
+            return Ok(Some(build_num));
+        }
+        if scanner.exhausted() {
+            break;
+        }
+    }
+    Ok(None)
+}
+
 async fn fetch_build_number_from_scripts(
     client: &reqwest::Client,
     script_urls: &[String],
 ) -> Result<u64> {
     use crate::logger::{log, LogCategory, LogLevel};
 
-    // Multiple possible buildNumber patterns
-    let patterns = [
-        r#"buildNumber["\s:]+(\d{5,})"#,
-        r#"build_number["\s:]+(\d{5,})"#,
-        r#"buildNumber:\s*"?(\d{5,})"?"#,
-        r#""buildNumber"\s*:\s*(\d+)"#,
-    ];
-
     // Check more bundles to handle Discord moving build metadata between assets
     let urls_to_check: Vec<&String> = script_urls.iter().rev().take(10).collect();
 
@@ -613,41 +1180,22 @@ async fn fetch_build_number_from_scripts(
             None,
         );
 
-        match client.get(url).send().await {
-            Ok(response) => {
-                if let Ok(js_content) = response.text().await {
-                    // Try all patterns
-                    for pattern in &patterns {
-                        if let Ok(re) = Regex::new(pattern) {
-                            if let Some(caps) = re.captures(&js_content) {
-                                if let Some(num_match) = caps.get(1) {
-                                    if let Ok(build_num) = num_match.as_str().parse::<u64>() {
-                                        // BUILD NUMBER VALIDATION BOUNDS:
-                                        // Lower bound (100000): Discord build numbers are typically 6+ digits
-                                        // Upper bound (9999999): Allow for future growth to 7 digits
-                                        // If Discord changes their numbering scheme significantly,
-                                        // these bounds may need adjustment.
-                                        if build_num >= 100000 && build_num <= 9_999_999 {
-                                            log(
-                                                LogLevel::Info,
-                                                LogCategory::TokenExtraction,
-                                                &format!("Found build number: {}", build_num),
-                                                None,
-                                            );
-                                            return Ok(build_num);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        match scan_script_for_build_number(client, url).await {
+            Ok(Some(build_num)) => {
+                log(
+                    LogLevel::Info,
+                    LogCategory::TokenExtraction,
+                    &format!("Found build number: {}", build_num),
+                    None,
+                );
+                return Ok(build_num);
             }
+            Ok(None) => {}
             Err(e) => {
                 log(
                     LogLevel::Debug,
                     LogCategory::TokenExtraction,
-                    &format!("Failed to fetch JS file: {}", e),
+                    &format!("Failed to fetch JS file: {:#}", e),
                     None,
                 );
             }
@@ -661,7 +1209,10 @@ async fn fetch_build_number_from_scripts(
 #[derive(Debug, Clone)]
 pub struct DiscordClientInfo {
     pub host_version: [u32; 3], // e.g., [1, 0, 9219]
-    pub native_build_number: u64,
+    /// Only set for Windows manifests; the other clients don't report a native build
+    pub native_build_number: Option<u64>,
+    /// Channel the manifest was fetched for ("stable", "ptb", "canary")
+    pub release_channel: String,
 }
 
 impl DiscordClientInfo {
@@ -674,33 +1225,74 @@ impl DiscordClientInfo {
     }
 }
 
+/// Native build number from an update manifest, if it carries one
+///
+/// The `discord_desktop_core` entry of the `modules` map holds the native build; the
+/// top-level `native_module_version` is only a fallback for older manifests. Callers
+/// fall back to the patch component of `host_version` as a last resort.
+fn native_build_from_manifest(manifest: &serde_json::Value) -> Option<u64> {
+    let from_core = manifest
+        .pointer("/modules/discord_desktop_core")
+        .and_then(|core| {
+            core.pointer("/full/module_version")
+                .or_else(|| core.get("module_version"))
+        })
+        .and_then(|v| v.as_u64());
+    from_core.or_else(|| manifest.get("native_module_version")?.as_u64())
+}
+
 /// Get client info from Discord Update API
 ///
 /// API: https://updates.discord.com/distributions/app/manifests/latest
 ///
+/// `channel` defaults to the running Discord client's channel (Stable if none is
+/// running), `platform` and `arch` to this machine's.
+///
 /// Reference: https://docs.discord.food/topics/client-distribution
-pub async fn fetch_discord_client_info() -> Result<DiscordClientInfo> {
+pub async fn fetch_discord_client_info(
+    channel: Option<&str>,
+    platform: Option<&str>,
+    arch: Option<&str>,
+) -> Result<DiscordClientInfo> {
+    use crate::discord_cdp_launcher::{detect_running_discord, DiscordChannel};
     use crate::logger::{log, LogCategory, LogLevel};
+    use crate::super_properties::{discord_arch, Os};
+
+    if is_offline() {
+        anyhow::bail!("Offline mode is on, not querying the update API");
+    }
+
+    let channel = match channel {
+        Some(channel) => channel,
+        // Process detection shells out, so keep it off the async workers
+        None => tokio::task::spawn_blocking(detect_running_discord)
+            .await
+            .ok()
+            .and_then(|running| running.ok())
+            .and_then(|running| running.channel)
+            .unwrap_or(DiscordChannel::Stable)
+            .as_str(),
+    };
+    let platform = platform.unwrap_or_else(|| Os::current().manifest_platform());
+    let arch = arch.unwrap_or_else(|| discord_arch());
 
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
-        "Fetching Discord client info from update API",
+        &format!(
+            "Fetching Discord client info from update API (channel={}, platform={}, arch={})",
+            channel, platform, arch
+        ),
         None,
     );
 
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .connect_timeout(std::time::Duration::from_secs(8))
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .context("Failed to create HTTP client")?;
+    let client = web_client("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")?;
 
     // Request Discord update manifest
-    let url = "https://updates.discord.com/distributions/app/manifests/latest?channel=stable&platform=win&arch=x64";
+    let url = endpoints::update_manifest(channel, platform, arch);
 
     let response = client
-        .get(url)
+        .get(&url)
         .send()
         .await
         .context("Failed to fetch Discord update manifest")?;
@@ -735,19 +1327,18 @@ pub async fn fetch_discord_client_info() -> Result<DiscordClientInfo> {
         }) as u32,
     ];
 
-    // native_build_number is usually the third number in host_version
-    // but may also be a separate field in the manifest
-    let native_build: u64 = manifest
-        .get("native_module_version")
-        .and_then(|v: &serde_json::Value| v.as_u64())
-        .unwrap_or(version[2] as u64);
+    let native_build = (platform == Os::Windows.manifest_platform())
+        .then(|| native_build_from_manifest(&manifest).unwrap_or(version[2] as u64));
 
     log(
         LogLevel::Info,
         LogCategory::TokenExtraction,
         &format!(
             "Got Discord client info: version={}.{}.{}, native_build={}",
-            version[0], version[1], version[2], native_build
+            version[0],
+            version[1],
+            version[2],
+            native_build.map_or_else(|| "none".to_string(), |n| n.to_string())
         ),
         None,
     );
@@ -755,6 +1346,7 @@ pub async fn fetch_discord_client_info() -> Result<DiscordClientInfo> {
     Ok(DiscordClientInfo {
         host_version: version,
         native_build_number: native_build,
+        release_channel: channel.to_string(),
     })
 }
 
@@ -762,13 +1354,129 @@ pub async fn fetch_discord_client_info() -> Result<DiscordClientInfo> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_build_number_scanner_across_chunks() {
+        let mut scanner = BuildNumberScanner::new().unwrap();
+        assert_eq!(scanner.feed(b"var a=1;buildNumber:\"3456"), None);
+        assert_eq!(scanner.feed(b"78\",release:\"stable\""), Some(345678));
+
+        // Values outside the plausible range are skipped
+        let mut scanner = BuildNumberScanner::new().unwrap();
+        assert_eq!(scanner.feed(b"buildNumber:12345;"), None);
+
+        // Nothing past the byte limit is read
+        let mut scanner = BuildNumberScanner::new().unwrap();
+        assert_eq!(scanner.feed(&vec![b' '; MAX_SCRIPT_BYTES]), None);
+        assert!(scanner.exhausted());
+        assert_eq!(scanner.feed(b"buildNumber:345678,"), None);
+    }
+
     #[test]
     #[ignore] // Only run when Discord is installed
     fn test_extract_tokens() {
-        let result = extract_tokens();
+        let result = extract_tokens(&[], None);
         match result {
-            Ok(tokens) => println!("Extracted {} tokens", tokens.len()),
+            Ok(extraction) => println!("Extracted {} tokens", extraction.tokens.len()),
             Err(e) => println!("Error: {}", e),
         }
     }
+
+    #[test]
+    fn test_extra_data_dirs() {
+        assert_eq!(
+            DiscordClient::from_data_dir(Path::new("/opt/Discord Canary/discordcanary")),
+            DiscordClient::Canary
+        );
+        assert_eq!(
+            DiscordClient::from_data_dir(Path::new("D:/Portable/DiscordPTB")),
+            DiscordClient::Ptb
+        );
+        assert_eq!(
+            DiscordClient::from_data_dir(Path::new("D:/Portable/data")),
+            DiscordClient::Stable
+        );
+
+        let extra = PathBuf::from("/srv/discord-data/discordptb");
+        let dirs = data_dirs_to_scan(&[extra.clone(), extra.clone(), PathBuf::new()]);
+        assert_eq!(
+            dirs.iter().filter(|(_, dir)| *dir == extra).count(),
+            1,
+            "extra paths are scanned once"
+        );
+        assert_eq!(dirs.last(), Some(&(DiscordClient::Ptb, extra)));
+    }
+
+    #[test]
+    fn test_native_build_from_manifest() {
+        let manifest = serde_json::json!({
+            "host_version": [1, 0, 9219],
+            "native_module_version": 12,
+            "modules": {
+                "discord_voice": { "full": { "module_version": 7 } },
+                "discord_desktop_core": { "full": { "module_version": 84934 }, "deltas": [] }
+            }
+        });
+        assert_eq!(native_build_from_manifest(&manifest), Some(84934));
+
+        let legacy = serde_json::json!({ "native_module_version": 12 });
+        assert_eq!(native_build_from_manifest(&legacy), Some(12));
+
+        let bare = serde_json::json!({ "modules": {} });
+        assert_eq!(native_build_from_manifest(&bare), None);
+    }
+
+    #[test]
+    fn test_parse_token_metadata() {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+        let id = "123456789012345678";
+        let issued: i64 = 1_700_000_000;
+        let token = format!(
+            "{}.{}.signature_part",
+            URL_SAFE_NO_PAD.encode(id),
+            URL_SAFE_NO_PAD.encode((issued as u32).to_be_bytes())
+        );
+
+        let meta = parse_token_metadata(&token).unwrap();
+        assert_eq!(meta.user_id, "1234...5678");
+        assert_eq!(meta.created_at.unwrap().timestamp(), issued);
+        assert!(meta.account_created_at.is_some());
+        assert!(!format!("{:?}", meta).contains(id));
+
+        assert!(parse_token_metadata("not-a-token").is_err());
+        assert!(parse_token_metadata("abc.def.ghi").is_err());
+        assert!(parse_token_metadata(&format!("{}..sig", URL_SAFE_NO_PAD.encode(id))).is_err());
+
+        assert!(validate_token_shape(&token).is_ok());
+        let err = validate_token_shape("hunter2").unwrap_err();
+        assert_eq!(err.kind(), "malformed_token");
+        assert!(err
+            .to_string()
+            .starts_with("This doesn't look like a Discord token"));
+    }
+
+    #[test]
+    fn test_unsupported_encryption_version() {
+        assert_eq!(
+            unsupported_encryption_version(b"v20\x01\x02\x03").as_deref(),
+            Some("v20")
+        );
+        assert_eq!(unsupported_encryption_version(b"v10\x01\x02\x03"), None);
+        assert_eq!(unsupported_encryption_version(b"v2"), None);
+    }
+
+    #[test]
+    fn test_dpapi_failure_diagnosis() {
+        let other_user = CredentialStoreError::dpapi_failed(0x8009_000Bu32 as i32);
+        assert!(other_user.reason.contains("another Windows user"));
+        assert!(other_user.reason.ends_with("(0x8009000B)"));
+        assert!(other_user.remediation.contains("same Windows user"));
+
+        let copied = CredentialStoreError::dpapi_failed(0x8007_000Du32 as i32);
+        assert!(copied.remediation.contains("log back in"));
+
+        let unknown = CredentialStoreError::dpapi_failed(0x8000_4005u32 as i32);
+        assert!(unknown.reason.ends_with("(0x80004005)"));
+        assert_eq!(unknown.platform, "windows");
+    }
 }
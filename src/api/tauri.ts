@@ -1,6 +1,76 @@
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 
+export type AppErrorKind =
+  | 'not_logged_in'
+  | 'token_invalid'
+  | 'malformed_token'
+  | 'rate_limited'
+  | 'network'
+  | 'discord_api'
+  | 'cdp_unavailable'
+  | 'no_executable_for_os'
+  | 'credential_store'
+  | 'other'
+
+/** Error payload returned by API-facing commands */
+export interface AppErrorPayload {
+  kind: AppErrorKind
+  message: string
+  retry_after?: number | null
+  status?: number
+  body?: string
+  /** `no_executable_for_os`: platforms the game does define executables for */
+  available_os?: string[]
+  /** Command that can be used instead, e.g. `start_game_heartbeat_quest` */
+  fallback_command?: string
+  /** `credential_store`: OS the credential store failed on */
+  platform?: string
+  /** `credential_store`: what the user can do about it */
+  remediation?: string
+}
+
+/**
+ * Typed command error. Stringifies to its message, so existing
+ * `String(e)` / `e.message` handling keeps working.
+ */
+export class AppError extends Error {
+  readonly kind: AppErrorKind
+  readonly retryAfter: number | null
+  readonly status: number | null
+  readonly availableOs: string[]
+  readonly fallbackCommand: string | null
+  readonly remediation: string | null
+
+  constructor(payload: AppErrorPayload) {
+    super(payload.message)
+    this.name = 'AppError'
+    this.kind = payload.kind
+    this.retryAfter = payload.retry_after ?? null
+    this.status = payload.status ?? null
+    this.availableOs = payload.available_os ?? []
+    this.fallbackCommand = payload.fallback_command ?? null
+    this.remediation = payload.remediation ?? null
+  }
+
+  toString(): string {
+    return this.message
+  }
+}
+
+function isAppErrorPayload(e: unknown): e is AppErrorPayload {
+  return typeof e === 'object' && e !== null && 'kind' in e && 'message' in e
+}
+
+/** `invoke` for commands that reject with an AppErrorPayload */
+async function invokeApi<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  try {
+    return await invoke<T>(cmd, args)
+  } catch (e) {
+    throw isAppErrorPayload(e) ? new AppError(e) : e
+  }
+}
+
 export interface DiscordUser {
   id: string
   username: string
@@ -123,24 +193,58 @@ export interface DetectableGame {
   name: string
   executables: Array<{
     name: string
+    /** "win32", "darwin" or "linux" */
     os: string
+    is_launcher?: boolean
   }>
   icon?: string
+  /** "Game" or "App" */
   type_name?: string
+  aliases?: string[]
 }
 
 // Auth commands
+export interface TokenSource {
+  client: 'stable' | 'canary' | 'ptb'
+  file_path_redacted: string
+}
+
 export interface ExtractedAccount {
   token: string
   user: DiscordUser
+  /** Every Discord client the token was found in */
+  source?: TokenSource[]
 }
 
-export async function autoDetectToken(): Promise<ExtractedAccount[]> {
-  return await invoke('auto_detect_token')
+/** A Discord data directory found on disk */
+export interface DiscordInstallation {
+  client: TokenSource['client']
+  data_dir_redacted: string
+  /** Distinct tokens found in it */
+  token_count: number
+  /** Why tokens couldn't be read, if they couldn't */
+  error: string | null
+}
+
+/** Discord clients with data on this machine and how many tokens each holds */
+export async function listDiscordInstallations(extraPaths?: string[]): Promise<DiscordInstallation[]> {
+  return await invoke('list_discord_installations', { extraPaths })
+}
+
+/**
+ * Detect accounts from the installed Discord clients
+ * @param extraPaths Additional Discord data directories to scan (portable installs, relocated profiles)
+ * @param channel Only read this client (default: all)
+ */
+export async function autoDetectToken(
+  extraPaths?: string[],
+  channel?: TokenSource['client']
+): Promise<ExtractedAccount[]> {
+  return await invokeApi('auto_detect_token', { extraPaths, channel })
 }
 
 export async function setToken(token: string): Promise<DiscordUser> {
-  return await invoke('set_token', { token })
+  return await invokeApi('set_token', { token })
 }
 
 // RPC commands
@@ -148,20 +252,123 @@ export function connectToDiscordRpc(activityJson: string, action: string = 'conn
   return invoke('connect_to_discord_rpc', { activity_json: activityJson, action })
 }
 
+/** Flattened quest summary returned by getQuests */
+export interface QuestSummary {
+  id: string
+  name: string
+  description: string
+  /** Progress percentage (0-100) */
+  progress: number
+  seconds_needed: number
+  /** Discord task type; unrecognized types are passed through as-is */
+  task_type: string
+  /** A completer can run this task (false for console tasks and unknown types) */
+  supported: boolean
+  application_id: string
+  application_name: string
+  /** CDN URL of the application icon */
+  application_icon: string | null
+  expires_at: string | null
+  enrolled: boolean
+  completed: boolean
+}
+
 // User status commands
-export async function getQuests(): Promise<Quest[]> {
-  return await invoke('get_quests')
+export async function getQuests(): Promise<QuestSummary[]> {
+  return await invokeApi('get_quests')
+}
+
+/** One runnable task of a quest and which start command completes it */
+export interface QuestPlan {
+  task_type: string
+  seconds_needed: number
+  /** Progress percentage (0-100) already made on this task */
+  initial_progress: number
+  application_id: string | null
+  /** Use startGameHeartbeatQuest / startStreamQuest */
+  supports_heartbeat: boolean
+  /** Use startVideoQuest */
+  supports_video: boolean
+}
+
+export async function getQuestPlan(questId: string): Promise<QuestPlan[]> {
+  return await invokeApi('get_quest_plan', { questId })
+}
+
+/** Unmodified /quests/@me response, for debugging */
+export async function getQuestsRaw(): Promise<CurrentUserQuestsResponse> {
+  return await invokeApi('get_quests_raw')
 }
 
 export async function getQuestsFull(): Promise<CurrentUserQuestsResponse> {
-  return await invoke('get_quests_full')
+  return await invokeApi('get_quests_full')
 }
 
 export async function getVirtualCurrencyBalance(): Promise<number> {
-  const response = await invoke<{ balance?: number }>('get_virtual_currency_balance')
+  const response = await invokeApi<{ balance?: number }>('get_virtual_currency_balance')
   return response.balance ?? 0
 }
 
+export interface RateLimitStatus {
+  bucket: string
+  remaining: number
+  reset_after_secs: number
+}
+
+export async function getRateLimitStatus(): Promise<RateLimitStatus | null> {
+  return await invoke('get_rate_limit_status')
+}
+
+export interface EndpointStats {
+  /** Method and path with IDs replaced, e.g. "POST /api/v9/quests/{id}/heartbeat" */
+  endpoint: string
+  requests: number
+  /** Average over the last 20 requests */
+  avg_latency_ms: number
+  last_latency_ms: number
+}
+
+/** Request latency per Discord API endpoint since login */
+export async function getApiStats(): Promise<EndpointStats[]> {
+  return await invoke('get_api_stats')
+}
+
+export interface SessionStats {
+  /** ISO 8601 */
+  session_start: string
+  uptime_secs: number
+  quests_completed: number
+  active_quests: number
+  tokens_detected: number
+  heartbeats_sent: number
+  api_errors: number
+  /** Non-success responses by HTTP status */
+  api_errors_by_status: Record<string, number>
+}
+
+/** What the app did since it started */
+export async function getSessionStats(): Promise<SessionStats> {
+  return await invoke('get_session_stats')
+}
+
+/** Result of testConnection */
+export interface ConnectionReport {
+  status: number
+  success: boolean
+  /** null when the response doesn't tell (e.g. the token was rejected first) */
+  super_properties_accepted: boolean | null
+  build_number: number
+  source: SuperPropertiesMode
+  latency_ms: number
+  /** Warning/deprecation headers and error messages Discord returned */
+  warnings: string[]
+}
+
+/** Read-only GET /users/@me with the current headers, to check the request path */
+export async function testConnection(): Promise<ConnectionReport> {
+  return await invokeApi('test_connection')
+}
+
 export async function getQuestDecisionDebug(placement: number): Promise<unknown> {
   return await invoke('get_quest_decision_debug', { placement })
 }
@@ -171,22 +378,34 @@ export async function getQuestDecisionsDebug(placement: number, num: number): Pr
 }
 
 export async function claimQuestReward(questId: string, platform?: string): Promise<unknown> {
-  return await invoke('claim_quest_reward', { questId, platform })
+  return await invokeApi('claim_quest_reward', { questId, platform })
 }
 
+/** What the backend does after a quest finishes */
+export type CompletionPolicy = 'stop' | 'claim' | 'claim_and_next'
+
 export async function startVideoQuest(
   questId: string,
   secondsNeeded: number,
   initialProgress: number,
   speedMultiplier: number,
-  heartbeatInterval: number
+  heartbeatInterval: number,
+  watchAheadSecs?: number,
+  onComplete?: CompletionPolicy,
+  dryRun?: boolean,
+  /** Wall-clock cap in seconds (default: three times the expected runtime) */
+  maxDurationSecs?: number
 ): Promise<void> {
   return await invoke('start_video_quest', {
     questId,
     secondsNeeded,
     initialProgress,
     speedMultiplier,
-    heartbeatInterval
+    heartbeatInterval,
+    watchAheadSecs,
+    onComplete,
+    dryRun,
+    maxDurationSecs
   })
 }
 
@@ -194,13 +413,20 @@ export async function startStreamQuest(
   questId: string,
   streamKey: string,
   secondsNeeded: number,
-  initialProgress: number
+  initialProgress: number,
+  onComplete?: CompletionPolicy,
+  dryRun?: boolean,
+  /** Wall-clock cap in seconds (default: three times the expected runtime) */
+  maxDurationSecs?: number
 ): Promise<void> {
   return await invoke('start_stream_quest', {
     questId,
     streamKey,
     secondsNeeded,
-    initialProgress
+    initialProgress,
+    onComplete,
+    dryRun,
+    maxDurationSecs
   })
 }
 
@@ -214,40 +440,172 @@ export async function startGameHeartbeatQuest(
   questId: string,
   applicationId: string,
   secondsNeeded: number,
-  initialProgress: number
+  initialProgress: number,
+  onComplete?: CompletionPolicy,
+  dryRun?: boolean,
+  /** Seconds to wait for Discord to detect the game before warning; omit to skip the check */
+  detectionTimeoutSecs?: number,
+  /** Also show the game as "Playing" through a gateway session */
+  gatewayPresence?: boolean,
+  /** Wall-clock cap in seconds (default: three times the expected runtime) */
+  maxDurationSecs?: number
 ): Promise<void> {
   return await invoke('start_game_heartbeat_quest', {
     questId,
     applicationId,
     secondsNeeded,
-    initialProgress
+    initialProgress,
+    onComplete,
+    dryRun,
+    detectionTimeoutSecs,
+    gatewayPresence,
+    maxDurationSecs
+  })
+}
+
+/** Payload of the `quest-batch-progress` event (index is zero-based) */
+export interface BatchProgress {
+  index: number
+  total: number
+  quest_id: string
+}
+
+/** Run every eligible quest one after another; resolves with the number of queued quests */
+export async function completeAllQuests(
+  speedMultiplier?: number,
+  heartbeatInterval?: number
+): Promise<number> {
+  return await invoke('complete_all_quests', { speedMultiplier, heartbeatInterval })
+}
+
+/** A quest waiting to be started by the scheduler */
+export interface ScheduledQuest {
+  /** ID of the schedule entry, used to cancel it */
+  id: string
+  quest_id: string
+  mode: 'video' | 'game' | 'stream' | null
+  /** Unix timestamp (seconds) */
+  start_at: number
+  speed_multiplier: number | null
+  heartbeat_interval: number | null
+}
+
+/** Start a quest at `startAtUnix` (kept across restarts; waits for login and any running quest) */
+export async function scheduleQuest(
+  questId: string,
+  startAtUnix: number,
+  mode?: 'video' | 'game' | 'stream',
+  speedMultiplier?: number,
+  heartbeatInterval?: number
+): Promise<ScheduledQuest> {
+  return await invoke('schedule_quest', { questId, mode, startAtUnix, speedMultiplier, heartbeatInterval })
+}
+
+export async function listScheduledQuests(): Promise<ScheduledQuest[]> {
+  return await invoke('list_scheduled_quests')
+}
+
+/** Returns false if the entry already started or was cancelled */
+export async function cancelScheduledQuest(id: string): Promise<boolean> {
+  return await invoke('cancel_scheduled_quest', { id })
+}
+
+export function onQuestScheduledStart(callback: (entry: ScheduledQuest) => void) {
+  return listen<ScheduledQuest>('quest-scheduled-start', (event) => {
+    callback(event.payload)
   })
 }
 
+/** Start-command settings stored in a quest profile */
+export interface QuestProfileSettings {
+  speed_multiplier: number
+  heartbeat_interval: number
+  watch_ahead_secs: number
+}
+
+export interface QuestProfile extends QuestProfileSettings {
+  name: string
+  /** Shipped with the app; can't be overwritten or deleted */
+  builtin: boolean
+}
+
+/** Built-in profiles first, then saved ones by name */
+export async function listQuestProfiles(): Promise<QuestProfile[]> {
+  return await invoke('list_quest_profiles')
+}
+
+/** Save settings under `name`, replacing a saved profile with the same name */
+export async function saveQuestProfile(name: string, settings: QuestProfileSettings): Promise<QuestProfile> {
+  return await invoke('save_quest_profile', { name, settings })
+}
+
+/** Settings of a profile, to prefill the start commands */
+export async function applyQuestProfile(name: string): Promise<QuestProfileSettings> {
+  return await invoke('apply_quest_profile', { name })
+}
+
+/** Returns false if no saved profile had that name */
+export async function deleteQuestProfile(name: string): Promise<boolean> {
+  return await invoke('delete_quest_profile', { name })
+}
+
+/** An interrupted quest that can be restarted from its saved progress */
+export interface ResumableQuest {
+  quest_id: string
+  task_type: 'video' | 'game' | 'stream'
+  application_id?: string
+  seconds_needed: number
+  /** Progress percentage (0-100) to pass as initialProgress */
+  initial_progress: number
+}
+
+export async function resumeQuests(): Promise<ResumableQuest[]> {
+  return await invoke('resume_quests')
+}
+
 // Game simulator commands
+/**
+ * Create a simulated game. Leave `executableName` empty to use the executable Discord
+ * expects for `appId` on this platform; resolves to the executable name that was created.
+ */
 export async function createSimulatedGame(
   path: string,
-  executableName: string,
+  executableName: string | undefined,
   appId: string
-): Promise<void> {
-  return await invoke('create_simulated_game', {
+): Promise<string> {
+  return await invokeApi('create_simulated_game', {
     path,
     executableName,
     appId
   })
 }
 
+/**
+ * Whether Discord currently sees the game running (poll after runSimulatedGame)
+ * @param timeoutSecs How long to wait for Discord to report it (default 8, max 30)
+ */
+export async function isGameDetected(applicationId: string, timeoutSecs?: number): Promise<boolean> {
+  return await invokeApi('is_game_detected', { applicationId, timeoutSecs })
+}
+
+/**
+ * Start the simulated game
+ * @param secondsRemaining Quest time left; the runner then exits by itself shortly after,
+ * so it isn't left running if the app crashes. Omit to run until stopped.
+ */
 export async function runSimulatedGame(
   name: string,
   path: string,
   executableName: string,
-  appId: string
+  appId: string,
+  secondsRemaining?: number
 ): Promise<void> {
   return await invoke('run_simulated_game', {
     name,
     path,
     executableName,
-    appId
+    appId,
+    secondsRemaining
   })
 }
 
@@ -255,18 +613,83 @@ export async function stopSimulatedGame(execName: string): Promise<void> {
   return await invoke('stop_simulated_game', { execName })
 }
 
-export async function fetchDetectableGames(): Promise<DetectableGame[]> {
-  return await invoke('fetch_detectable_games')
+/** Stop every running simulated game; resolves with the number stopped */
+export async function stopAllSimulatedGames(): Promise<number> {
+  return await invoke('stop_all_simulated_games')
+}
+
+/** What `emergencyStop` shut down */
+export interface EmergencyStopReport {
+  quest_stopped: boolean
+  games_stopped: number
+  rpc_disconnected: boolean
+}
+
+/** Stop the active quest, disconnect RPC and kill all simulated games; safe when idle */
+export async function emergencyStop(): Promise<EmergencyStopReport> {
+  return await invoke('emergency_stop')
+}
+
+export function onEmergencyStopped(callback: (report: EmergencyStopReport) => void) {
+  return listen<EmergencyStopReport>('emergency-stopped', (event) => {
+    callback(event.payload)
+  })
+}
+
+/** Cached for `ttlSecs` (default one hour); pass `force` to refetch */
+export async function fetchDetectableGames(force?: boolean, ttlSecs?: number): Promise<DetectableGame[]> {
+  return await invoke('fetch_detectable_games', { force, ttlSecs })
+}
+
+export interface DetectableGamesRefresh {
+  total: number
+  /** Entries not in the previously fetched list */
+  new_entries: number
+  /** Entries only the Canary endpoint listed */
+  canary_only: number
+}
+
+/** Refetch the detectable games list now (bypassing the cache), including Canary-only entries */
+export async function refreshDetectableGames(): Promise<DetectableGamesRefresh> {
+  return await invoke('refresh_detectable_games')
+}
+
+/** Executable name Discord expects for a game on `os` ("win32", "darwin", "linux"), default this platform */
+export async function getGameExecutable(appId: string, os?: string): Promise<string> {
+  return await invokeApi('get_game_executable', { appId, os })
+}
+
+/** Search detectable games by name or alias, best matches first (default limit 50) */
+export async function searchDetectableGames(query: string, limit?: number): Promise<DetectableGame[]> {
+  return await invoke('search_detectable_games', { query, limit })
 }
 
 export async function acceptQuest(questId: string): Promise<void> {
-  return await invoke('accept_quest', { questId })
+  return await invokeApi('accept_quest', { questId })
+}
+
+/** Leave an accepted quest; fails for completed quests */
+export async function abandonQuest(questId: string): Promise<void> {
+  return await invokeApi('abandon_quest', { questId })
+}
+
+/** Structured `quest-progress` payload sent by the HTTP and CDP completers */
+export interface QuestProgressEvent {
+  quest_id: string
+  current_seconds: number
+  seconds_needed: number
+  percent: number
+  eta_seconds: number
+  task_type: string
+  heartbeat_interval: number
 }
 
 // Event listeners
-export function onQuestProgress(callback: (progress: number) => void) {
-  return listen<number>('quest-progress', (event) => {
-    callback(event.payload)
+export function onQuestProgress(
+  callback: (progress: number, details: QuestProgressEvent) => void
+) {
+  return listen<QuestProgressEvent>('quest-progress', (event) => {
+    callback(event.payload.percent, event.payload)
   })
 }
 
@@ -276,12 +699,119 @@ export function onQuestComplete(callback: () => void) {
   })
 }
 
+// HTTP flows send the final progress they reported to Discord (null if none was made);
+// CDP flows send no payload
+export function onQuestStopped(callback: (lastProgress: QuestProgressEvent | null) => void) {
+  return listen<QuestProgressEvent | null>('quest-stopped', (event) => {
+    callback(event.payload ?? null)
+  })
+}
+
 export function onQuestError(callback: (error: string) => void) {
   return listen<string>('quest-error', (event) => {
     callback(event.payload)
   })
 }
 
+export function onQuestWarning(callback: (message: string) => void) {
+  return listen<string>('quest-warning', (event) => {
+    callback(event.payload)
+  })
+}
+
+// Discord recorded less video progress than was sent; the completer slowed down
+export interface QuestThrottledEvent {
+  quest_id: string
+  sent_seconds: number
+  accepted_seconds: number
+  speed_multiplier: number
+}
+
+export function onQuestThrottled(callback: (event: QuestThrottledEvent) => void) {
+  return listen<QuestThrottledEvent>('quest-throttled', (event) => {
+    callback(event.payload)
+  })
+}
+
+// A quest ran past its wall-clock cap without completing and was stopped
+export interface QuestTimeoutEvent {
+  quest_id: string
+  limit_secs: number
+}
+
+export function onQuestTimeout(callback: (event: QuestTimeoutEvent) => void) {
+  return listen<QuestTimeoutEvent>('quest-timeout', (event) => {
+    callback(event.payload)
+  })
+}
+
+/** Discord reported a problem with the logged-in account; everything was stopped and no quest starts until the next login */
+export interface AccountFlaggedEvent {
+  status: number
+  code: number | null
+  reason: 'token_revoked' | 'password_reset_required' | 'verification_required'
+  message: string
+}
+
+export function onAccountFlagged(callback: (event: AccountFlaggedEvent) => void) {
+  return listen<AccountFlaggedEvent>('account-flagged', (event) => {
+    callback(event.payload)
+  })
+}
+
+export type LogLevel = 'DEBUG' | 'INFO' | 'WARN' | 'ERROR'
+
+/** Lowest level kept in the exported log (default: INFO in release builds) */
+export async function setMinLogLevel(level: LogLevel): Promise<void> {
+  return await invoke('set_min_log_level', { level })
+}
+
+/**
+ * Offline mode for isolated networks: skip Discord's website and update API and use
+ * CDP, the cached build number or the defaults instead
+ */
+export async function setOfflineMode(offline: boolean): Promise<void> {
+  return await invoke('set_offline_mode', { offline })
+}
+
+export async function getOfflineMode(): Promise<boolean> {
+  return await invoke('get_offline_mode')
+}
+
+/** Show a native notification when a quest completes (off by default) */
+export async function setNotificationsEnabled(enabled: boolean): Promise<void> {
+  return await invoke('set_notifications_enabled', { enabled })
+}
+
+export type LogCategory =
+  | 'TokenExtraction'
+  | 'Api'
+  | 'Quest'
+  | 'Gateway'
+  | 'GameSim'
+  | 'Rpc'
+  | 'General'
+
+/** Log export as JSON, optionally limited to some categories and/or a minimum level */
+export async function exportLogs(categories?: LogCategory[], levelAtLeast?: LogLevel): Promise<string> {
+  return await invoke('export_logs', { categories, levelAtLeast })
+}
+
+/** Write the log export to a file (default: timestamped file on the desktop); resolves with the path */
+export async function exportLogsToFile(path?: string): Promise<string> {
+  return await invoke('export_logs_to_file', { path })
+}
+
+/** Also write logs to rotating files in the app log dir, kept across restarts; resolves with the directory while enabled */
+export async function setFileLogging(enabled: boolean): Promise<string | null> {
+  return await invoke('set_file_logging', { enabled })
+}
+
+/** Directory log files are written to, or null when file logging is off */
+export async function getFileLoggingDir(): Promise<string | null> {
+  return await invoke('get_file_logging_dir')
+}
+
 export async function forceVideoProgress(questId: string, timestamp: number): Promise<void> {
   return await invoke('force_video_progress', { questId, timestamp })
 }
@@ -344,6 +874,43 @@ export async function getDebugInfo(): Promise<DebugInfo> {
   return await invoke('get_debug_info')
 }
 
+/** Where to connect to the local live progress WebSocket */
+export interface LiveStreamInfo {
+  port: number
+  token: string
+  /** `ws://127.0.0.1:<port>/?token=<token>` */
+  url: string
+}
+
+/**
+ * Connection details of the local live progress WebSocket, or null when the app was
+ * built without the `live-stream` feature. Frames are `{ type, data }` JSON.
+ */
+export async function getLiveStreamInfo(): Promise<LiveStreamInfo | null> {
+  return await invoke('get_live_stream_info')
+}
+
+/** A field whose captured value differs from what the app sends (null = absent) */
+export interface SuperPropertiesDiff {
+  field: string
+  captured: unknown
+  current: unknown
+}
+
+export interface DecodedSuperProperties {
+  /** Decoded JSON, pretty-printed */
+  json: string
+  /** Typed view, null when required fields are missing (see parse_error) */
+  properties: SuperProperties | null
+  parse_error: string | null
+  differences: SuperPropertiesDiff[]
+}
+
+/** Decode a captured X-Super-Properties value and diff it against the current one */
+export async function decodeSuperProperties(base64: string): Promise<DecodedSuperProperties> {
+  return await invoke('decode_super_properties', { base64 })
+}
+
 // Runner information
 export interface RunnerInfo {
   embedded: boolean
@@ -367,14 +934,33 @@ export interface CdpStatus {
 export interface CdpSuperProperties {
   base64: string
   decoded: SuperProperties
+  /** How the module was found: 'named' (getSuperPropertiesBase64) or 'scan' */
+  strategy?: 'named' | 'scan'
+  /** Webpack chunk global the module was found through */
+  chunk?: string
 }
 
 export async function checkCdpStatus(port?: number): Promise<CdpStatus> {
   return await invoke('check_cdp_status', { port })
 }
 
-export async function fetchSuperPropertiesCdp(port?: number): Promise<CdpSuperProperties> {
-  return await invoke('fetch_super_properties_cdp', { port })
+/**
+ * Read SuperProperties from the running Discord client via CDP
+ * @param timeoutSecs Evaluation timeout; raise it for slow clients (default 10s)
+ */
+export async function fetchSuperPropertiesCdp(
+  port?: number,
+  timeoutSecs?: number
+): Promise<CdpSuperProperties> {
+  return await invokeApi('fetch_super_properties_cdp', { port, timeoutSecs })
+}
+
+/**
+ * Identify the account signed in to the running Discord client via CDP.
+ * Needs no token and returns none, so it can't be used to log in by itself.
+ */
+export async function fetchCurrentUserCdp(port?: number): Promise<DiscordUser> {
+  return await invokeApi('fetch_current_user_cdp', { port })
 }
 
 export type DiscordChannelArg = 'auto' | 'stable' | 'ptb' | 'canary'
@@ -391,6 +977,17 @@ export async function isDiscordRunning(channel?: DiscordChannelArg): Promise<boo
   return await invoke('is_discord_running', { channel })
 }
 
+export interface RunningDiscord {
+  running: boolean
+  channel: DiscordChannelResult | null
+  pid: number | null
+}
+
+/** Which Discord client is running (Stable is checked first, then PTB, then Canary) */
+export async function detectRunningDiscord(): Promise<RunningDiscord> {
+  return await invoke('detect_running_discord')
+}
+
 export async function launchDiscordCdp(port?: number, channel?: DiscordChannelArg): Promise<DiscordCdpLaunchResult> {
   return await invoke('launch_discord_cdp', { port, channel })
 }
@@ -408,24 +1005,60 @@ export async function createDiscordDebugShortcut(port?: number): Promise<string>
 }
 
 // SuperProperties Mode types and commands
-export type SuperPropertiesMode = 'cdp' | 'remote_js' | 'default'
+export type SuperPropertiesMode = 'cdp' | 'remote_js' | 'cached' | 'default'
 
 export interface SuperPropertiesModeInfo {
   mode: SuperPropertiesMode
   mode_display: string
   build_number: number | null
+  /** Discord's latest build, once the periodic staleness check has run */
+  latest_build_number: number | null
+  /** The build number sent is far behind Discord's latest; refresh SuperProperties */
+  is_stale: boolean
+}
+
+/** Payload of `build-number-stale` */
+export interface BuildNumberStaleEvent {
+  build_number: number | null
+  latest_build_number: number
+}
+
+export function onBuildNumberStale(callback: (event: BuildNumberStaleEvent) => void) {
+  return listen<BuildNumberStaleEvent>('build-number-stale', (event) => {
+    callback(event.payload)
+  })
 }
 
 export interface AutoFetchResult {
   success: boolean
   mode: SuperPropertiesMode
   build_number: number | null
+  /** Port Discord's CDP was found on (may differ from the one requested) */
+  cdp_port: number | null
+  /** Set when CDP failed: remote fetches were skipped because offline mode is on */
+  offline?: boolean
 }
 
 export async function getSuperPropertiesMode(): Promise<SuperPropertiesModeInfo> {
   return await invoke('get_super_properties_mode')
 }
 
+/** Behavior when starting a quest while SuperProperties are still the built-in defaults */
+export type DefaultModeGate = 'off' | 'warn' | 'block'
+
+export async function getDefaultModeGate(): Promise<DefaultModeGate> {
+  return await invoke('get_default_mode_gate')
+}
+
+export async function setDefaultModeGate(gate: DefaultModeGate): Promise<void> {
+  return await invoke('set_default_mode_gate', { gate })
+}
+
+/** Proxy for Discord API traffic: http://, https:// or socks5:// (credentials allowed). Pass null to use system settings. */
+export async function setProxy(proxyUrl: string | null): Promise<void> {
+  return await invoke('set_proxy', { proxyUrl })
+}
+
 export async function autoFetchSuperProperties(cdpPort?: number): Promise<AutoFetchResult> {
   return await invoke('auto_fetch_super_properties', { cdpPort })
 }
@@ -434,6 +1067,21 @@ export async function retrySuperProperties(cdpPort?: number): Promise<AutoFetchR
   return await invoke('retry_super_properties', { cdpPort })
 }
 
+export interface RefreshClientHeadersResult extends AutoFetchResult {
+  /** User-Agent and versions sent from now on */
+  client_identity: ClientIdentitySnapshot
+}
+
+/** Re-read SuperProperties and the client version after Discord updated mid-session */
+export async function refreshClientHeaders(cdpPort?: number): Promise<RefreshClientHeadersResult> {
+  return await invoke('refresh_client_headers', { cdpPort })
+}
+
+/** Re-read SuperProperties over CDP every intervalSecs (min 60) so build updates are picked up; null disables */
+export async function setSuperPropertiesRefresh(intervalSecs: number | null, cdpPort?: number): Promise<void> {
+  return await invoke('set_super_properties_refresh', { intervalSecs, cdpPort })
+}
+
 // CDP captured headers (full network capture)
 export interface CapturedRequest {
   url: string
@@ -462,8 +1110,13 @@ export async function startCdpQuest(
   applicationName: string,
   secondsNeeded: number,
   initialProgress: number,
-  cdpPort: number,
-  checkpointTimes?: number[]
+  /** Omit to use the backend's default port (`DQH_CDP_PORT` or 9223) */
+  cdpPort?: number,
+  checkpointTimes?: number[],
+  /** Applied once the quest completes; needs a logged-in account */
+  onComplete?: CompletionPolicy,
+  /** Wall-clock cap in seconds (default: three times the expected runtime) */
+  maxDurationSecs?: number
 ): Promise<void> {
   return await invoke('start_cdp_quest', {
     questId,
@@ -473,10 +1126,27 @@ export async function startCdpQuest(
     secondsNeeded,
     initialProgress,
     cdpPort,
-    checkpointTimes: checkpointTimes || []
+    checkpointTimes: checkpointTimes || [],
+    onComplete,
+    maxDurationSecs
   })
 }
 
-export async function navigateDiscordSpa(targetPath: string, cdpPort: number): Promise<void> {
+export async function navigateDiscordSpa(targetPath: string, cdpPort?: number): Promise<void> {
   return await invoke('navigate_discord_spa', { targetPath, cdpPort })
 }
+
+/** Accept a quest through the running Discord client via CDP, without a token */
+export async function acceptQuestCdp(questId: string, cdpPort?: number): Promise<unknown> {
+  return await invokeApi('accept_quest_cdp', { questId, cdpPort })
+}
+
+/** Send a game heartbeat through the running Discord client via CDP; resolves with whether the quest completed */
+export async function sendHeartbeatCdp(
+  questId: string,
+  applicationId: string,
+  terminal: boolean,
+  cdpPort?: number
+): Promise<boolean> {
+  return await invokeApi('send_heartbeat_cdp', { questId, applicationId, terminal, cdpPort })
+}
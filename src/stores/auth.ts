@@ -51,7 +51,11 @@ export const useAuthStore = defineStore('auth', () => {
       // This ensures all data is ready before ending the loading state
       try {
         const questsStore = useQuestsStore()
-        await autoFetchSuperProperties(questsStore.cdpPort)
+        const fetchResult = await autoFetchSuperProperties(questsStore.cdpPort)
+        // Remember the port Discord was actually found on
+        if (fetchResult.cdp_port !== null) {
+          questsStore.cdpPort = fetchResult.cdp_port
+        }
 
         // Check CDP availability and update banner immediately after login
         questsStore.initCdpMode().catch(err => {
@@ -1,5 +1,5 @@
 import { defineStore } from 'pinia'
-import { ref, watch } from 'vue'
+import { computed, ref, watch } from 'vue'
 import type { Quest, DetectableGame, ExcludedQuest } from '@/api/tauri'
 import { getQuestKind } from '@/utils/questTasks'
 
@@ -19,13 +19,16 @@ import {
   runSimulatedGame,
   stopSimulatedGame,
   fetchDetectableGames,
+  emergencyStop as emergencyStopCommand,
+  getGameExecutable,
   connectToDiscordRpc,
   acceptQuest,
   startGameHeartbeatQuest,
-  forceVideoProgress,
   startCdpQuest,
   checkCdpStatus,
-  getVirtualCurrencyBalance
+  getVirtualCurrencyBalance,
+  setNotificationsEnabled,
+  applyQuestProfile as applyQuestProfileCommand
 } from '@/api/tauri'
 import { homeDir, sep } from '@tauri-apps/api/path'
 import { emit } from '@tauri-apps/api/event'
@@ -75,6 +78,15 @@ export const useQuestsStore = defineStore('quests', () => {
   }
   const heartbeatInterval = ref(initialInterval)
 
+  // Watch-ahead (seconds) - how far video progress may run ahead of real time, set by quest profiles
+  const STORAGE_WATCH_AHEAD_KEY = 'questHelper_watchAheadSecs'
+  const savedWatchAhead = localStorage.getItem(STORAGE_WATCH_AHEAD_KEY)
+  let initialWatchAhead = savedWatchAhead ? parseFloat(savedWatchAhead) : 0
+  if (!Number.isFinite(initialWatchAhead) || initialWatchAhead < 0) {
+    initialWatchAhead = 0
+  }
+  const watchAheadSecs = ref(initialWatchAhead)
+
   // Game polling interval (seconds) - for Play/Game quests progress detection
   const STORAGE_GAME_POLLING_KEY = 'questHelper_gamePollingInterval'
   const savedGamePolling = localStorage.getItem(STORAGE_GAME_POLLING_KEY)
@@ -101,12 +113,19 @@ export const useQuestsStore = defineStore('quests', () => {
   const STORAGE_CDP_PORT_KEY = 'questHelper_cdpPort'
   const savedCdpPort = localStorage.getItem(STORAGE_CDP_PORT_KEY)
   const cdpPort = ref(savedCdpPort ? parseInt(savedCdpPort) : 9223)
+  const cdpPortSaved = ref(savedCdpPort !== null)
+  // Port passed to CDP commands: undefined until the user saves one, so the backend default (DQH_CDP_PORT) applies
+  const cdpPortOverride = computed(() => (cdpPortSaved.value ? cdpPort.value : undefined))
 
   // Optional display: account Orbs balance. Disabled by default to avoid extra requests.
   const STORAGE_SHOW_ORBS_BALANCE_KEY = 'questHelper_showOrbsBalance'
   const savedShowOrbsBalance = localStorage.getItem(STORAGE_SHOW_ORBS_BALANCE_KEY)
   const showOrbsBalance = ref(savedShowOrbsBalance === null ? true : savedShowOrbsBalance === 'true')
 
+  // Native notification when a quest completes. Disabled by default.
+  const STORAGE_DESKTOP_NOTIFICATIONS_KEY = 'questHelper_desktopNotifications'
+  const desktopNotifications = ref(localStorage.getItem(STORAGE_DESKTOP_NOTIFICATIONS_KEY) === 'true')
+
   // Activity quest checkpoint interval (seconds) - min/max time between checkpoints
   const STORAGE_ACTIVITY_CHECKPOINT_MIN_KEY = 'questHelper_activityCheckpointMin'
   const savedCheckpointMin = localStorage.getItem(STORAGE_ACTIVITY_CHECKPOINT_MIN_KEY)
@@ -134,6 +153,11 @@ export const useQuestsStore = defineStore('quests', () => {
     localStorage.setItem(STORAGE_INTERVAL_KEY, String(newInterval))
   })
 
+  // Persist watch-ahead changes
+  watch(watchAheadSecs, (newSecs) => {
+    localStorage.setItem(STORAGE_WATCH_AHEAD_KEY, String(newSecs))
+  })
+
   // Persist game polling interval changes
   watch(gamePollingInterval, (newInterval) => {
     localStorage.setItem(STORAGE_GAME_POLLING_KEY, String(newInterval))
@@ -147,6 +171,7 @@ export const useQuestsStore = defineStore('quests', () => {
   // Persist CDP port changes
   watch(cdpPort, (newPort) => {
     localStorage.setItem(STORAGE_CDP_PORT_KEY, String(newPort))
+    cdpPortSaved.value = true
   })
 
   watch(showOrbsBalance, (enabled) => {
@@ -158,6 +183,13 @@ export const useQuestsStore = defineStore('quests', () => {
     }
   })
 
+  watch(desktopNotifications, (enabled) => {
+    localStorage.setItem(STORAGE_DESKTOP_NOTIFICATIONS_KEY, String(enabled))
+    setNotificationsEnabled(enabled).catch(err => {
+      console.warn('Failed to update desktop notifications:', err)
+    })
+  }, { immediate: true })
+
   function normalizeCheckpoint(value: number, fallback: number, min: number, max: number): number {
     if (!Number.isFinite(value)) return fallback
     const n = Math.round(value)
@@ -397,10 +429,10 @@ export const useQuestsStore = defineStore('quests', () => {
 
       if (gameQuestMode.value === 'cdp') {
         // CDP mode: use Discord's internal api.post() for video progress
-        await startCdpQuest(questId, 'video', '', '', secondsNeeded, initialProgress, cdpPort.value)
+        await startCdpQuest(questId, 'video', '', '', secondsNeeded, initialProgress, cdpPortOverride.value)
       } else {
         console.log(`[startVideo] mode=${gameQuestMode.value} speed=${speedMultiplier.value}x interval=${heartbeatInterval.value}s`)
-        await startVideoQuest(questId, secondsNeeded, progressPct, speedMultiplier.value, heartbeatInterval.value)
+        await startVideoQuest(questId, secondsNeeded, progressPct, speedMultiplier.value, heartbeatInterval.value, watchAheadSecs.value)
       }
 
       activeQuestId.value = questId
@@ -456,7 +488,7 @@ export const useQuestsStore = defineStore('quests', () => {
           appName,
           secondsNeeded,
           initialProgress,
-          cdpPort.value
+          cdpPortOverride.value
         )
 
         activeQuestId.value = quest.id
@@ -490,36 +522,25 @@ export const useQuestsStore = defineStore('quests', () => {
         setupListeners()
 
       } else {
-        // Simulate mode - original behavior
-        // 2. Fetch detectable games to find executable name
-        // Use cached list if available
+        // 2. Look up the game for its display name
         const gamesList = await getDetectableGames()
         const game = gamesList.find(g => g.id === appId)
         if (!game) throw new Error(`Game not found in Discord's detectable list (AppID: ${appId})`)
 
-        // Use caller-selected exe if provided, otherwise pick the first win32 executable
-        let exeName: string
-        if (selectedExeName) {
-          exeName = selectedExeName
-        } else {
-          const winExe = game.executables.find(e => e.os === 'win32')
-          if (!winExe) throw new Error(`No Windows executable definition for game ${game.name}`)
-          exeName = winExe.name
-        }
-
-        console.log(`Starting simulated game for ${game.name} (${exeName})...`)
-
         // 3. Setup path
         const home = await homeDir()
         const separator = await sep()
         const installPath = `${home}${separator}Documents${separator}DiscordQuestGames`
 
-        // 4. Create simulated game executable
-        await createSimulatedGame(installPath, exeName, appId)
+        // 4. Create simulated game executable. Without a caller-selected exe the backend
+        // picks the one Discord expects for this platform
+        const exeName = await createSimulatedGame(installPath, selectedExeName, appId)
         activeGameExe.value = exeName
+        console.log(`Starting simulated game for ${game.name} (${exeName})...`)
 
         // 5. Run simulated game
-        await runSimulatedGame(game.name, installPath, exeName, appId)
+        const secondsRemaining = Math.max(0, Math.ceil(secondsNeeded - initialProgress))
+        await runSimulatedGame(game.name, installPath, exeName, appId, secondsRemaining)
 
         // 6. Connect RPC
         const activity = {
@@ -597,7 +618,7 @@ export const useQuestsStore = defineStore('quests', () => {
         appName,
         totalSeconds,
         0,
-        cdpPort.value,
+        cdpPortOverride.value,
         checkpointTimes
       )
 
@@ -624,19 +645,7 @@ export const useQuestsStore = defineStore('quests', () => {
     stopProgressSimulation()
 
     try {
-      // Force Save Logic for Video Quests (skip in CDP mode — progress is server-managed)
-      if (activeQuestId.value && activeQuestType.value === 'video' && activeQuestTargetDuration.value > 0 && gameQuestMode.value !== 'cdp') {
-        try {
-          const currentSeconds = (localProgress.value / 100) * activeQuestTargetDuration.value
-          // Only force if we have significant progress
-          if (currentSeconds > 0) {
-            console.log(`Force submitting video progress: ${currentSeconds.toFixed(1)}s (ID: ${activeQuestId.value})`)
-            await forceVideoProgress(activeQuestId.value, currentSeconds)
-          }
-        } catch (e) {
-          console.error('Failed to force submit progress on stop:', e)
-        }
-      }
+      // Video progress watched since the last update is submitted by the backend on stop
 
       // If manually stopping, ensure queue is also stopped/cleared
       if (isQueueRunning.value) {
@@ -654,15 +663,8 @@ export const useQuestsStore = defineStore('quests', () => {
         if (quest && quest.config.application?.id) {
           try {
             const appId = quest.config.application.id
-            const detectableGames = await fetchDetectableGames()
-            const game = detectableGames.find(g => g.id === appId)
-            if (game) {
-              const winExe = game.executables.find(e => e.os === 'win32')
-              if (winExe) {
-                exeToStop = winExe.name
-                console.log('Recovered executable name:', exeToStop)
-              }
-            }
+            exeToStop = await getGameExecutable(appId)
+            console.log('Recovered executable name:', exeToStop)
           } catch (err) {
             console.error('Failed to recover executable name:', err)
           }
@@ -704,6 +706,28 @@ export const useQuestsStore = defineStore('quests', () => {
     }
   }
 
+  /** Panic button: stop everything in the backend and drop all local quest state */
+  async function emergencyStop() {
+    stopping.value = true
+    stopProgressSimulation()
+    stopPolling()
+    try {
+      isQueueRunning.value = false
+      questQueue.value = []
+      await emergencyStopCommand()
+    } finally {
+      activeQuestId.value = null
+      activeQuestType.value = null
+      activeQuestProgress.value = 0
+      activeQuestTargetDuration.value = 0
+      localProgress.value = 0
+      activeGameExe.value = null
+      cleanupListeners()
+      stopping.value = false
+    }
+    await fetchQuests(true, true)
+  }
+
   function setupListeners() {
     cleanupListeners()
 
@@ -807,6 +831,14 @@ export const useQuestsStore = defineStore('quests', () => {
     speedMultiplier.value = speed
   }
 
+  /** Prefill speed and heartbeat interval from a saved or built-in profile */
+  async function applyQuestProfile(name: string) {
+    const settings = await applyQuestProfileCommand(name)
+    speedMultiplier.value = settings.speed_multiplier
+    heartbeatInterval.value = settings.heartbeat_interval
+    watchAheadSecs.value = settings.watch_ahead_secs
+  }
+
   async function acceptQuestWrapper(questId: string) {
     try {
       await acceptQuest(questId)
@@ -975,7 +1007,7 @@ export const useQuestsStore = defineStore('quests', () => {
   // Check CDP availability and auto-fallback if mode is 'cdp' but CDP isn't reachable
   async function initCdpMode() {
     try {
-      const status = await checkCdpStatus(cdpPort.value)
+      const status = await checkCdpStatus(cdpPortOverride.value)
       cdpAvailable.value = status.connected
       if (gameQuestMode.value === 'cdp' && !status.connected) {
         console.warn('CDP mode selected but CDP not available — falling back to simulate mode')
@@ -1001,6 +1033,7 @@ export const useQuestsStore = defineStore('quests', () => {
     orbsBalanceLoading,
     orbsBalanceError,
     showOrbsBalance,
+    desktopNotifications,
     activityCheckpointMin,
     activityCheckpointMax,
     activeQuestId,
@@ -1010,9 +1043,11 @@ export const useQuestsStore = defineStore('quests', () => {
     localProgress, // Export local progress
     speedMultiplier,
     heartbeatInterval,
+    watchAheadSecs,
     gamePollingInterval,
     gameQuestMode,
     cdpPort,
+    cdpPortOverride,
     cdpAvailable,
     stopping,
     activeGameExe,
@@ -1026,7 +1061,9 @@ export const useQuestsStore = defineStore('quests', () => {
     startPlay,
     startActivity,
     stop,
+    emergencyStop,
     setSpeedMultiplier,
+    applyQuestProfile,
     acceptQuest: acceptQuestWrapper,
     acceptAllQuests,
     // Add to queue logic needs integration with listeners
@@ -85,8 +85,13 @@ export function getQuestKind(quest: Quest): QuestKind {
   return 'video'
 }
 
+/** Mobile video progress only counts from the Discord mobile app, so these can't be started here */
+export function isMobileVideoTask(task: QuestTaskView): boolean {
+  return task.type === 'WATCH_VIDEO_ON_MOBILE'
+}
+
 export function isVideoTask(task: QuestTaskView): boolean {
-  return task.type === 'WATCH_VIDEO' || task.type === 'WATCH_VIDEO_ON_MOBILE' || task.type.includes('VIDEO')
+  return !isMobileVideoTask(task) && (task.type === 'WATCH_VIDEO' || task.type.includes('VIDEO'))
 }
 
 export function isDesktopPlayTask(task: QuestTaskView): boolean {